@@ -52,6 +52,16 @@ impl ResourceHandles {
     pub fn is_all_done(&self) -> bool {
         self.waiting.is_empty()
     }
+
+    /// How many requested [`Asset`]s have finished loading.
+    pub fn finished_count(&self) -> usize {
+        self.finished.len()
+    }
+
+    /// How many [`Asset`]s have been requested, loaded or not.
+    pub fn total_count(&self) -> usize {
+        self.waiting.len() + self.finished.len()
+    }
 }
 
 fn load_resource_assets(world: &mut World) {
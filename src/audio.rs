@@ -1,18 +1,51 @@
-use bevy::prelude::*;
-use crate::asset_tracking::LoadResource;
+use crate::{asset_tracking::LoadResource, demo::mood::Mood, screens::Screen};
+use bevy::{
+    asset::LoadState,
+    audio::{SpatialScale, Volume},
+    prelude::*,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, ops::RangeInclusive};
 
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Music>();
     app.register_type::<SoundEffect>();
     app.register_type::<AudioAssets>();
+    app.register_type::<AudioMix>();
+    app.init_resource::<AudioMix>();
+    app.register_type::<MusicVolume>();
+    app.init_resource::<MusicVolume>();
+    app.register_type::<SfxVolume>();
+    app.init_resource::<SfxVolume>();
+
+    app.register_type::<DominantMood>();
+    app.init_resource::<DominantMood>();
+    app.register_type::<AmbientLayer>();
 
     app.add_event::<PlaySound>();
     app.load_resource::<AudioAssets>();
 
-    app.add_systems(Update, (
-        apply_global_volume.run_if(resource_changed::<GlobalVolume>),
-        play_sound_effects,
-    ));
+    app.add_systems(
+        Update,
+        (
+            apply_global_volume.run_if(
+                resource_changed::<GlobalVolume>
+                    .or(resource_changed::<AudioMix>)
+                    .or(resource_changed::<MusicVolume>)
+                    .or(resource_changed::<SfxVolume>),
+            ),
+            apply_audio_mix_to_new_sounds,
+            play_sound_effects,
+        ),
+    );
+    app.add_systems(
+        Update,
+        (update_dominant_mood, crossfade_ambient_layers)
+            .chain()
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_dominant_mood);
 }
 
 /// A resource that holds handles to all our sound effects.
@@ -25,6 +58,10 @@ pub struct AudioAssets {
     pub mood_change: Handle<AudioSource>,
     #[dependency]
     pub mood_collision: Handle<AudioSource>,
+    /// Ambient loops for each mood, read from `audio/ambient/<mood>.ogg`. These aren't
+    /// dependencies, so the game still loads without them and a missing loop just leaves its
+    /// mood silent.
+    pub ambient: HashMap<Mood, Handle<AudioSource>>,
 }
 
 impl FromWorld for AudioAssets {
@@ -32,18 +69,82 @@ impl FromWorld for AudioAssets {
         let assets = world.resource::<AssetServer>();
         Self {
             correct_zone_entry: assets.load("audio/sound_effects/button_click.ogg"),
-            mood_change: assets.load("audio/sound_effects/button_hover.ogg"), 
+            mood_change: assets.load("audio/sound_effects/button_hover.ogg"),
             mood_collision: assets.load("audio/sound_effects/step1.ogg"),
+            ambient: Mood::all()
+                .into_iter()
+                .map(|mood| {
+                    let path = format!("audio/ambient/{mood:?}.ogg").to_lowercase();
+                    (mood, assets.load(path))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Per-category volume multipliers, applied on top of [`GlobalVolume`].
+/// Levels can override this to rebalance music against sound effects.
+#[derive(Resource, Reflect, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AudioMix {
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioMix {
+    fn default() -> Self {
+        Self {
+            music_volume: 1.0,
+            sfx_volume: 1.0,
         }
     }
 }
 
+/// The player's music volume, set in the settings menu.
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Resource)]
+pub struct MusicVolume(pub Volume);
+
+/// The player's sound effect volume, set in the settings menu.
+#[derive(Resource, Reflect, Debug, Clone, Copy, Default)]
+#[reflect(Resource)]
+pub struct SfxVolume(pub Volume);
+
+/// The multiplier for an audio entity on top of [`GlobalVolume`], based on its category.
+/// Combines the level's [`AudioMix`] with the player's own volume for that category.
+fn category_volume(
+    audio_mix: &AudioMix,
+    music_volume: &MusicVolume,
+    sfx_volume: &SfxVolume,
+    is_music: bool,
+    is_sfx: bool,
+) -> Volume {
+    match (is_music, is_sfx) {
+        (true, _) => Volume::Linear(audio_mix.music_volume) * music_volume.0,
+        (_, true) => Volume::Linear(audio_mix.sfx_volume) * sfx_volume.0,
+        _ => Volume::Linear(1.0),
+    }
+}
+
 /// An event to trigger a sound effect.
 #[derive(Event)]
 pub enum PlaySound {
-    CorrectZoneEntry,
-    MoodChanged { from: crate::demo::mood::Mood, to: crate::demo::mood::Mood },
-    MoodCollision { mood1: crate::demo::mood::Mood, mood2: crate::demo::mood::Mood },
+    /// A Moodel in the right mood entered a goal zone at `position`.
+    CorrectZoneEntry {
+        position: Vec2,
+    },
+    MoodChanged {
+        from: crate::demo::mood::Mood,
+        to: crate::demo::mood::Mood,
+    },
+    /// Two Moodels bumped into each other at `position`.
+    MoodCollision {
+        mood1: crate::demo::mood::Mood,
+        mood2: crate::demo::mood::Mood,
+        position: Vec2,
+    },
+    Victory,
 }
 
 /// This system listens for `PlaySound` events and plays the corresponding sound.
@@ -60,14 +161,33 @@ fn play_sound_effects(
     // Process all sound events (can play multiple sounds simultaneously)
     for event in sound_events.read() {
         match event {
-            PlaySound::CorrectZoneEntry => {
-                commands.spawn(sound_effect(audio_assets.correct_zone_entry.clone()));
+            PlaySound::CorrectZoneEntry { position } => {
+                commands.spawn(spatial_sound_effect(
+                    audio_assets.correct_zone_entry.clone(),
+                    *position,
+                    None,
+                ));
             }
             PlaySound::MoodChanged { from: _, to: _ } => {
-                commands.spawn(sound_effect(audio_assets.mood_change.clone()));
+                commands.spawn(sound_effect_varied(
+                    audio_assets.mood_change.clone(),
+                    &SfxVariation::default(),
+                ));
             }
-            PlaySound::MoodCollision { mood1: _, mood2: _ } => {
-                commands.spawn(sound_effect(audio_assets.mood_collision.clone()));
+            PlaySound::MoodCollision { position, .. } => {
+                commands.spawn(spatial_sound_effect(
+                    audio_assets.mood_collision.clone(),
+                    *position,
+                    Some(&SfxVariation::default()),
+                ));
+            }
+            PlaySound::Victory => {
+                // No dedicated fanfare yet, so play the zone chime at a higher pitch.
+                commands.spawn((
+                    AudioPlayer::new(audio_assets.correct_zone_entry.clone()),
+                    PlaybackSettings::DESPAWN.with_speed(1.5),
+                    SoundEffect,
+                ));
             }
         }
     }
@@ -83,11 +203,7 @@ pub struct Music;
 
 /// A music audio instance.
 pub fn music(handle: Handle<AudioSource>) -> impl Bundle {
-    (
-        AudioPlayer::new(handle),
-        PlaybackSettings::LOOP,
-        Music
-    )
+    (AudioPlayer::new(handle), PlaybackSettings::LOOP, Music)
 }
 
 /// An organizational marker component that should be added to a spawned [`AudioPlayer`] if it's in the
@@ -103,16 +219,320 @@ pub fn sound_effect(handle: Handle<AudioSource>) -> impl Bundle {
     (
         AudioPlayer::new(handle),
         PlaybackSettings::DESPAWN,
-        SoundEffect
+        SoundEffect,
     )
 }
 
-/// [`GlobalVolume`] doesn't apply to already-running audio entities, so this system will update them.
+/// Random spread applied to each play of a sound effect, so repeated clips don't sound identical.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SfxVariation {
+    /// Playback speed multiplier, which also shifts the pitch.
+    pub pitch_range: RangeInclusive<f32>,
+    /// Linear volume multiplier.
+    pub volume_range: RangeInclusive<f32>,
+}
+
+impl Default for SfxVariation {
+    fn default() -> Self {
+        Self {
+            pitch_range: 0.9..=1.1,
+            volume_range: 0.8..=1.0,
+        }
+    }
+}
+
+/// Converts world units to the units of spatial audio, so a sound across the level is still
+/// audible rather than fading out over a few hundred pixels.
+const SPATIAL_AUDIO_SCALE: f32 = 1.0 / 200.0;
+
+/// Distance in world units between the ears of the camera's [`SpatialListener`].
+pub const SPATIAL_EAR_GAP: f32 = 400.0;
+
+/// Despawning playback settings with a randomized pitch and volume.
+fn varied_playback(variation: &SfxVariation) -> PlaybackSettings {
+    let mut rng = rand::rng();
+    let speed = rng.random_range(variation.pitch_range.clone());
+    let volume = rng.random_range(variation.volume_range.clone());
+    PlaybackSettings::DESPAWN
+        .with_speed(speed)
+        .with_volume(Volume::Linear(volume))
+}
+
+/// A sound effect audio instance with a randomized pitch and volume.
+pub fn sound_effect_varied(handle: Handle<AudioSource>, variation: &SfxVariation) -> impl Bundle {
+    (
+        AudioPlayer::new(handle),
+        varied_playback(variation),
+        SoundEffect,
+    )
+}
+
+/// A sound effect audio instance played from `position` in the world, panned relative to the
+/// camera's [`SpatialListener`].
+pub fn spatial_sound_effect(
+    handle: Handle<AudioSource>,
+    position: Vec2,
+    variation: Option<&SfxVariation>,
+) -> impl Bundle {
+    let playback = variation.map_or(PlaybackSettings::DESPAWN, varied_playback);
+    (
+        AudioPlayer::new(handle),
+        playback
+            .with_spatial(true)
+            .with_spatial_scale(SpatialScale::new_2d(SPATIAL_AUDIO_SCALE)),
+        Transform::from_translation(position.extend(0.0)),
+        SoundEffect,
+    )
+}
+
+/// [`GlobalVolume`] and the category volumes don't apply to already-running audio entities, so this system will update them.
 fn apply_global_volume(
     global_volume: Res<GlobalVolume>,
-    mut audio_query: Query<(&PlaybackSettings, &mut AudioSink)>,
+    audio_mix: Res<AudioMix>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    mut audio_query: Query<AudioSinkQueryData>,
+) {
+    for (playback, sink, spatial_sink, is_music, is_sfx) in &mut audio_query {
+        let category = category_volume(&audio_mix, &music_volume, &sfx_volume, is_music, is_sfx);
+        set_sink_volume(
+            sink,
+            spatial_sink,
+            global_volume.volume * playback.volume * category,
+        );
+    }
+}
+
+/// Newly started audio only picks up [`GlobalVolume`], so scale it by its category volume.
+fn apply_audio_mix_to_new_sounds(
+    global_volume: Res<GlobalVolume>,
+    audio_mix: Res<AudioMix>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    mut audio_query: Query<AudioSinkQueryData, Or<(Added<AudioSink>, Added<SpatialAudioSink>)>>,
+) {
+    for (playback, sink, spatial_sink, is_music, is_sfx) in &mut audio_query {
+        let category = category_volume(&audio_mix, &music_volume, &sfx_volume, is_music, is_sfx);
+        set_sink_volume(
+            sink,
+            spatial_sink,
+            global_volume.volume * playback.volume * category,
+        );
+    }
+}
+
+/// Playing audio, which has either a regular or a spatial sink, and its category.
+type AudioSinkQueryData = (
+    &'static PlaybackSettings,
+    Option<&'static mut AudioSink>,
+    Option<&'static mut SpatialAudioSink>,
+    Has<Music>,
+    Has<SoundEffect>,
+);
+
+fn set_sink_volume(
+    sink: Option<Mut<AudioSink>>,
+    spatial_sink: Option<Mut<SpatialAudioSink>>,
+    volume: Volume,
+) {
+    if let Some(mut sink) = sink {
+        sink.set_volume(volume);
+    }
+    if let Some(mut spatial_sink) = spatial_sink {
+        spatial_sink.set_volume(volume);
+    }
+}
+
+/// Seconds between recounting the moods of the live Moodels.
+const DOMINANT_MOOD_INTERVAL_SECS: f32 = 1.0;
+/// How many more Moodels another mood needs than the dominant one to take over.
+const DOMINANT_MOOD_MARGIN: usize = 2;
+/// Seconds for an ambient loop to fade fully in or out.
+const AMBIENT_CROSSFADE_SECS: f32 = 2.0;
+/// Volume of an ambient loop once faded in, relative to the music.
+const AMBIENT_VOLUME: f32 = 0.5;
+
+/// The mood most Moodels are in, which picks the ambient loop that plays.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct DominantMood {
+    pub mood: Option<Mood>,
+    timer: Timer,
+}
+
+impl Default for DominantMood {
+    fn default() -> Self {
+        Self {
+            mood: None,
+            timer: Timer::from_seconds(DOMINANT_MOOD_INTERVAL_SECS, TimerMode::Repeating),
+        }
+    }
+}
+
+/// An ambient loop for a mood, fading in while its mood is dominant and out otherwise.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct AmbientLayer {
+    pub mood: Mood,
+    /// How far the loop has faded in, from 0.0 to 1.0.
+    pub fade: f32,
+}
+
+/// Picks the most common mood. Switching away from `current` needs a lead of more than
+/// [`DOMINANT_MOOD_MARGIN`], so a near-tie doesn't flip the ambience back and forth.
+fn pick_dominant_mood(counts: &HashMap<Mood, usize>, current: Option<Mood>) -> Option<Mood> {
+    let count = |mood: Mood| counts.get(&mood).copied().unwrap_or(0);
+    let leader = Mood::all().into_iter().max_by_key(|mood| count(*mood))?;
+    if count(leader) == 0 {
+        return None;
+    }
+    match current {
+        Some(current)
+            if count(current) > 0 && count(leader) <= count(current) + DOMINANT_MOOD_MARGIN =>
+        {
+            Some(current)
+        }
+        _ => Some(leader),
+    }
+}
+
+fn update_dominant_mood(
+    time: Res<Time>,
+    mut dominant: ResMut<DominantMood>,
+    mood_query: Query<&Mood>,
+) {
+    if !dominant.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let mut counts = HashMap::new();
+    for mood in &mood_query {
+        *counts.entry(*mood).or_insert(0) += 1;
+    }
+    let mood = pick_dominant_mood(&counts, dominant.mood);
+    if mood != dominant.mood {
+        info!("Dominant mood is now {:?}", mood);
+        dominant.mood = mood;
+    }
+}
+
+/// Fades the dominant mood's ambient loop in and every other loop out, starting the dominant
+/// loop if it isn't playing yet.
+fn crossfade_ambient_layers(
+    mut commands: Commands,
+    time: Res<Time>,
+    dominant: Res<DominantMood>,
+    audio_assets: Option<Res<AudioAssets>>,
+    asset_server: Res<AssetServer>,
+    global_volume: Res<GlobalVolume>,
+    audio_mix: Res<AudioMix>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    mut layer_query: Query<(
+        Entity,
+        &mut AmbientLayer,
+        &mut PlaybackSettings,
+        Option<&mut AudioSink>,
+    )>,
 ) {
-    for (playback, mut sink) in &mut audio_query {
-        sink.set_volume(global_volume.volume * playback.volume);
+    let Some(audio_assets) = audio_assets else {
+        return;
+    };
+
+    if let Some(mood) = dominant.mood {
+        let playing = layer_query.iter().any(|(_, layer, ..)| layer.mood == mood);
+        let handle = audio_assets.ambient.get(&mood).filter(|handle| {
+            !matches!(
+                asset_server.get_load_state(*handle),
+                Some(LoadState::Failed(_))
+            )
+        });
+        if let (false, Some(handle)) = (playing, handle) {
+            commands.spawn((
+                Name::new(format!("{mood:?} Ambience")),
+                AmbientLayer { mood, fade: 0.0 },
+                AudioPlayer::new(handle.clone()),
+                // Starts silent and fades in
+                PlaybackSettings::LOOP.with_volume(Volume::Linear(0.0)),
+                Music,
+                StateScoped(Screen::Gameplay),
+            ));
+        }
+    }
+
+    let step = time.delta_secs() / AMBIENT_CROSSFADE_SECS;
+    let category = category_volume(&audio_mix, &music_volume, &sfx_volume, true, false);
+    for (entity, mut layer, mut playback, sink) in &mut layer_query {
+        if dominant.mood == Some(layer.mood) {
+            layer.fade = (layer.fade + step).min(1.0);
+        } else {
+            layer.fade -= step;
+            if layer.fade <= 0.0 {
+                commands.entity(entity).despawn();
+                continue;
+            }
+        }
+        // Kept on the playback settings so volume changes in the settings menu respect the fade
+        playback.volume = Volume::Linear(layer.fade * AMBIENT_VOLUME);
+        if let Some(mut sink) = sink {
+            sink.set_volume(global_volume.volume * playback.volume * category);
+        }
     }
-}
\ No newline at end of file
+}
+
+fn reset_dominant_mood(mut dominant: ResMut<DominantMood>) {
+    *dominant = DominantMood::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(moods: &[(Mood, usize)]) -> HashMap<Mood, usize> {
+        moods.iter().copied().collect()
+    }
+
+    #[test]
+    fn dominant_mood_is_the_most_common() {
+        let counts = counts(&[(Mood::Happy, 2), (Mood::Rage, 5)]);
+        assert_eq!(pick_dominant_mood(&counts, None), Some(Mood::Rage));
+        assert_eq!(pick_dominant_mood(&HashMap::new(), Some(Mood::Rage)), None);
+    }
+
+    #[test]
+    fn near_tie_keeps_the_dominant_mood() {
+        // Rage leads by the margin, which isn't enough to take over from Happy
+        let near_tie = counts(&[(Mood::Happy, 4), (Mood::Rage, 4 + DOMINANT_MOOD_MARGIN)]);
+        assert_eq!(
+            pick_dominant_mood(&near_tie, Some(Mood::Happy)),
+            Some(Mood::Happy)
+        );
+
+        let clear_lead = counts(&[(Mood::Happy, 4), (Mood::Rage, 5 + DOMINANT_MOOD_MARGIN)]);
+        assert_eq!(
+            pick_dominant_mood(&clear_lead, Some(Mood::Happy)),
+            Some(Mood::Rage)
+        );
+    }
+
+    #[test]
+    fn vanished_mood_is_replaced() {
+        let counts = counts(&[(Mood::Calm, 1)]);
+        assert_eq!(
+            pick_dominant_mood(&counts, Some(Mood::Rage)),
+            Some(Mood::Calm)
+        );
+    }
+
+    #[test]
+    fn varied_playback_stays_in_range() {
+        let variation = SfxVariation::default();
+        for _ in 0..200 {
+            let playback = varied_playback(&variation);
+            assert!(variation.pitch_range.contains(&playback.speed));
+            // Allow for rounding in the volume conversion
+            let volume = playback.volume.to_linear();
+            assert!(*variation.volume_range.start() - 1e-4 <= volume);
+            assert!(volume <= *variation.volume_range.end() + 1e-4);
+        }
+    }
+}
@@ -4,48 +4,148 @@
 //! behaviors such as organic wandering, flocking/repulsion, boundary avoidance,
 //! and state-based abilities, all determined by their current `Mood`.
 
-use bevy::prelude::*;
 use avian2d::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 use noisy_bevy::fbm_simplex_2d_seeded;
 use rand::Rng;
-use std::ops::Range;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::Range,
+};
 
 use crate::{
-    AppSystems, PausableSystems, COLLISION_LAYER_OBSTACLE,
+    AppSystems, COLLISION_LAYER_OBSTACLE, PausableSystems,
     demo::{
+        culling::Culled,
+        level::closest_point_on_convex_polygon,
         mood::Mood,
-        movement::{MovementController, PlayArea},
+        movement::{MovementController, PlayArea, PlayAreaShape},
+        player_input::cursor_world_position,
+        rng::GameRng,
+        spatial_grid::{SpatialGrid, update_spatial_grid},
+        victory::VictorySequence,
     },
+    keybindings::{Action, action_just_pressed},
+    save,
 };
 
-/// Configuration resource for AI behavior parameters
-#[derive(Resource, Reflect)]
+/// Configuration resource for AI behavior parameters. Tweaks made in the AI tuning menu are
+/// saved and loaded at startup.
+#[derive(Resource, Reflect, Serialize, Deserialize)]
 #[reflect(Resource)]
+#[serde(default)]
 pub struct AiConfig {
     // Magnetism strengths
     pub cohesion_strength: f32,
     pub separation_strength: f32,
+    // Per-mood perception, copied into each Moodel's `AiMagnetism`
+    pub vision_radius: HashMap<Mood, f32>,
+    pub separation_distance: HashMap<Mood, f32>,
     // Boundary avoidance
     pub avoidance_margin: f32,
     pub avoidance_strength: f32,
+    // Obstacle avoidance
+    pub obstacle_avoidance_margin: f32,
+    pub obstacle_avoidance_strength: f32,
     // Rage-specific tuning
     pub rage_charge_aim_duration: f32,
     pub rage_charge_duration: f32,
     pub rage_charge_cooldown: Range<f32>,
     pub rage_max_hits_before_burnout: u32,
+    /// Impulse applied to a Moodel hit by a Rage charge.
+    pub rage_charge_knockback: f32,
+    /// How long a burned out Rage Moodel waits before it can charge again.
+    pub rage_burnout_cooldown: f32,
+    /// Whether charges lead moving targets instead of aiming where they were.
+    pub rage_prediction_enabled: bool,
+    // Sandbox cursor attraction
+    pub cursor_attract_strength: f32,
+    // Happy flocking
+    /// How much more a follower is drawn to its flock leader than to any other neighbor.
+    pub leader_cohesion_multiplier: f32,
+    /// Seconds between re-picking the flock leaders.
+    pub leader_reassign_interval: f32,
+}
+
+/// Where tweaked AI tuning is saved.
+const AI_CONFIG_SAVE_KEY: &str = "ai_config.json";
+
+impl AiConfig {
+    /// Reads the saved tuning, falling back to the defaults if there is none or it can't be read.
+    pub fn load() -> Self {
+        let Some(json) = save::read(AI_CONFIG_SAVE_KEY) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|error| {
+            warn!("Ignoring unreadable AI tuning: {}", error);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => save::write(AI_CONFIG_SAVE_KEY, &json),
+            Err(error) => error!("Failed to serialize AI tuning: {}", error),
+        }
+    }
+
+    /// How far a Moodel in this mood can see others.
+    pub fn vision_radius_for(&self, mood: Mood) -> f32 {
+        self.vision_radius
+            .get(&mood)
+            .copied()
+            .unwrap_or(DEFAULT_VISION_RADIUS)
+    }
+
+    /// How much space a Moodel in this mood keeps from others.
+    pub fn separation_distance_for(&self, mood: Mood) -> f32 {
+        self.separation_distance
+            .get(&mood)
+            .copied()
+            .unwrap_or(DEFAULT_SEPARATION_DISTANCE)
+    }
 }
 
+/// Fallbacks for moods missing from the per-mood `AiConfig` tables.
+const DEFAULT_VISION_RADIUS: f32 = 250.0;
+const DEFAULT_SEPARATION_DISTANCE: f32 = 20.0;
+
 impl Default for AiConfig {
     fn default() -> Self {
         Self {
             cohesion_strength: 0.05,
             separation_strength: 0.01,
+            vision_radius: HashMap::from([
+                (Mood::Neutral, 250.0),
+                (Mood::Calm, 250.0),
+                (Mood::Happy, 250.0),
+                (Mood::Rage, 350.0), // Rage scans far for targets
+                (Mood::Sad, 150.0),  // Sad is withdrawn
+                (Mood::Fear, 300.0), // Fear is on the lookout
+            ]),
+            separation_distance: HashMap::from([
+                (Mood::Neutral, 20.0),
+                (Mood::Calm, 20.0),
+                (Mood::Happy, 15.0), // Happy huddles close
+                (Mood::Rage, 30.0),
+                (Mood::Sad, 40.0), // Sad keeps its distance
+                (Mood::Fear, 30.0),
+            ]),
             avoidance_margin: 100.0,
             avoidance_strength: 2.0,
+            obstacle_avoidance_margin: 60.0,
+            obstacle_avoidance_strength: 1.5,
             rage_charge_aim_duration: 0.75,
             rage_charge_duration: 1.5,
             rage_charge_cooldown: 5.0..8.0,
             rage_max_hits_before_burnout: 3,
+            rage_charge_knockback: 500.0,
+            rage_burnout_cooldown: 12.0,
+            rage_prediction_enabled: true,
+            cursor_attract_strength: 0.4,
+            leader_cohesion_multiplier: 4.0,
+            leader_reassign_interval: 1.0,
         }
     }
 }
@@ -53,13 +153,17 @@ impl Default for AiConfig {
 pub(super) fn plugin(app: &mut App) {
     // Register and initialize the new AiConfig resource
     app.register_type::<AiConfig>();
-    app.init_resource::<AiConfig>();
+    app.insert_resource(AiConfig::load());
+    app.register_type::<CursorAttract>();
+    app.init_resource::<CursorAttract>();
 
     // Register all AI components
     app.register_type::<AiEntity>();
     app.register_type::<AiWanderState>();
     app.register_type::<AiMagnetism>();
     app.register_type::<AiAction>();
+    app.register_type::<FlockLeader>();
+    app.init_resource::<FlockLeaderTimer>();
 
     // Add the new AI systems to the update schedule in a specific order
     app.add_systems(
@@ -69,15 +173,80 @@ pub(super) fn plugin(app: &mut App) {
             update_ai_behavior,
             // 2. Modify the intent with social flocking/repulsion forces.
             update_ai_magnetism,
+            // 2b. Optionally drift towards the cursor (sandbox toy mode).
+            update_ai_cursor_attraction.run_if(cursor_attract_enabled),
             // 3. Add a final, strong force to avoid play area boundaries.
             update_ai_boundary_avoidance,
+            // 4. Steer around walls instead of relying on physics to stop us.
+            update_ai_obstacle_avoidance,
         )
             .chain()
+            // Magnetism looks up neighbors in the spatial grid.
+            .after(update_spatial_grid)
+            // The victory celebration drives the Moodels itself.
+            .run_if(
+                not(in_state(VictorySequence::Celebrating))
+                    .and(not(in_state(VictorySequence::Flourishing))),
+            )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
+
+    // Give each cluster of Happy Moodels a leader for the others to follow.
+    app.add_systems(
+        Update,
+        (
+            tick_flock_leader_timer.in_set(AppSystems::TickTimers),
+            update_flock_leaders
+                .after(update_spatial_grid)
+                .before(update_ai_magnetism)
+                .in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+
+    // Keep each Moodel's perception in line with its mood.
+    app.add_systems(
+        Update,
+        apply_magnetism_for_mood
+            .before(update_ai_behavior)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+
+    // Toggle the cursor attraction sandbox mode.
+    app.add_systems(
+        Update,
+        toggle_cursor_attract
+            .run_if(action_just_pressed(Action::CursorAttract))
+            .in_set(AppSystems::RecordInput),
+    );
+
+    // Debug drawing of what each Moodel sees and wants.
+    #[cfg(feature = "dev")]
+    {
+        app.register_type::<AiDebugGizmos>();
+        app.init_resource::<AiDebugGizmos>();
+        app.add_systems(
+            Update,
+            draw_ai_debug_gizmos
+                .run_if(ai_debug_gizmos_enabled)
+                .in_set(AppSystems::Update),
+        );
+    }
 }
 
+/// Whether to draw each Moodel's intent, vision radius and charge target (dev builds only).
+#[cfg(feature = "dev")]
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct AiDebugGizmos(pub bool);
+
+/// Sandbox toy mode: when enabled, wandering Moodels are gently drawn towards the cursor.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct CursorAttract(pub bool);
+
 /// Marker component for AI-controlled entities.
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
@@ -130,21 +299,51 @@ pub struct AiMagnetism {
     pub separation_distance: f32,
 }
 
+/// Marks the Happy Moodel that the others in its flock gather around.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct FlockLeader;
+
+/// Counts down to the next time the flock leaders are re-picked.
+#[derive(Resource)]
+struct FlockLeaderTimer(Timer);
+
+impl Default for FlockLeaderTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(1.0, TimerMode::Repeating))
+    }
+}
+
 /// A component to identify static obstacles for AI raycasting.
 #[derive(Component)]
 pub struct ObstacleCollider;
 
+/// The outline of a polygon obstacle, relative to its position, in counter-clockwise order.
+/// Obstacles without one or an [`ObstacleRadius`] are avoided by their bounding box.
+#[derive(Component)]
+pub struct ObstacleHull(pub Vec<Vec2>);
+
+/// The radius of a round obstacle, so the AI steers around the circle rather than its bounding box.
+#[derive(Component)]
+pub struct ObstacleRadius(pub f32);
+
+/// How close a commanded Moodel must get to a waypoint to count as having reached it.
+pub const WAYPOINT_ARRIVAL_DISTANCE: f32 = 25.0;
+
 /// Represents the specific action an AI is currently performing.
 #[derive(Debug, Clone, Reflect, PartialEq)]
 pub enum AiAction {
-    /// Highest priority: moving to a player-commanded target using steering behaviors.
-    MovingToTarget { destination: Vec2 },
+    /// Highest priority: moving through player-commanded waypoints in order using steering
+    /// behaviors. The front waypoint is the current destination.
+    MovingToTarget { waypoints: VecDeque<Vec2> },
     /// Default state: moving around using noise-based wandering.
     Wandering,
     /// A rage-specific state: pausing to lock onto a target.
     Aiming { target: Entity },
     /// A rage-specific state: moving at high speed towards a target's last known position.
     Charging { target_pos: Vec2 },
+    /// Picked up by the player, who moves it around directly.
+    Held,
 }
 
 /// State component for AI behavior, incorporating actions and timers.
@@ -165,8 +364,7 @@ pub struct AiWanderState {
 
 impl AiWanderState {
     /// Creates a new, default wander state for an AI.
-    pub fn new() -> Self {
-        let mut rng = rand::rng();
+    pub fn new(rng: &mut impl Rng) -> Self {
         Self {
             state_timer: Timer::from_seconds(rng.random_range(0.5..1.5), TimerMode::Once),
             noise_seed: rng.random(),
@@ -178,35 +376,62 @@ impl AiWanderState {
 }
 
 /// System that updates the AI's core action state and base movement intent.
-fn update_ai_behavior(
+pub fn update_ai_behavior(
     time: Res<Time>,
     config: Res<AiConfig>,
+    play_area: Res<PlayArea>,
     all_moodels: Query<(Entity, &Transform, &Mood)>,
-    mut ai_query: Query<(
-        Entity,
-        &Transform,
-        &mut MovementController,
-        &mut AiWanderState,
-        &Mood,
-        &LinearVelocity,
-    ), With<AiEntity>>,
+    velocities: Query<&LinearVelocity>,
+    mut ai_query: Query<
+        (
+            Entity,
+            &Transform,
+            &mut MovementController,
+            &mut AiWanderState,
+            &Mood,
+            &LinearVelocity,
+            &AiMagnetism,
+        ),
+        (With<AiEntity>, Without<Culled>),
+    >,
     mut spatial_query: SpatialQuery,
+    mut rng: ResMut<GameRng>,
 ) {
-    let mut rng = rand::rng();
-
     let potential_targets: Vec<_> = all_moodels
         .iter()
         .filter(|(_, _, mood)| **mood != Mood::Rage)
         .collect();
 
-    for (entity, transform, mut controller, mut wander_state, mood, velocity) in &mut ai_query {
+    for (entity, transform, mut controller, mut wander_state, mood, velocity, magnetism) in
+        &mut ai_query
+    {
         wander_state.state_timer.tick(time.delta());
         wander_state.ability_cooldown.tick(time.delta());
         let current_pos = transform.translation.truncate();
 
+        // Fear watches for the closest Rage moodel it can see, so it can run away from it.
+        let flee_from = if *mood == Mood::Fear {
+            all_moodels
+                .iter()
+                .filter(|(other, _, other_mood)| *other != entity && **other_mood == Mood::Rage)
+                .map(|(_, other_transform, _)| other_transform.translation.truncate())
+                .filter(|threat| threat.distance(current_pos) < magnetism.vision_radius)
+                .min_by(|a, b| {
+                    a.distance_squared(current_pos)
+                        .total_cmp(&b.distance_squared(current_pos))
+                })
+                .map(|threat| current_pos - threat)
+        } else {
+            None
+        };
+
         match wander_state.action.clone() {
             // --- NEW, HIGHEST PRIORITY STEERING BEHAVIOR ---
-            AiAction::MovingToTarget { destination } => {
+            AiAction::MovingToTarget { waypoints } => {
+                let Some(&destination) = waypoints.front() else {
+                    wander_state.action = AiAction::Wandering;
+                    continue;
+                };
                 // Blend forces: Seek, Obstacle Avoidance, and Wander
                 let seek_force = (destination - current_pos).normalize_or_zero();
                 let avoidance_force = calculate_avoidance_force(
@@ -215,32 +440,49 @@ fn update_ai_behavior(
                     velocity.0, // Use actual velocity from Avian2D physics
                     entity,
                 );
-                let wander_force = get_wander_intent(mood, &mut wander_state, &time, &mut rng);
+                let wander_force =
+                    get_wander_intent(mood, &mut wander_state, flee_from, &time, &mut *rng);
 
                 // Combine forces with weights
                 let final_intent = (seek_force * 1.0)
                     + (avoidance_force * 1.5) // Avoidance is high priority
-                    + (wander_force * 0.3);   // Wander is a subtle addition
+                    + (wander_force * 0.3); // Wander is a subtle addition
 
                 controller.intent = final_intent.normalize_or_zero();
 
-                // Check for arrival
-                if current_pos.distance(destination) < 25.0 {
-                    info!("Entity {:?} reached commanded destination.", entity);
-                    wander_state.action = AiAction::Wandering;
-                    controller.intent = Vec2::ZERO;
+                // Check for arrival, then move on to the next waypoint
+                if current_pos.distance(destination) < WAYPOINT_ARRIVAL_DISTANCE {
+                    let mut remaining = waypoints;
+                    remaining.pop_front();
+                    if remaining.is_empty() {
+                        info!("Entity {:?} reached commanded destination.", entity);
+                        wander_state.action = AiAction::Wandering;
+                        controller.intent = Vec2::ZERO;
+                    } else {
+                        wander_state.action = AiAction::MovingToTarget {
+                            waypoints: remaining,
+                        };
+                    }
                 }
             }
             AiAction::Wandering => {
                 if *mood == Mood::Rage && wander_state.ability_cooldown.just_finished() {
-                    let closest_target = potential_targets
+                    // Closest first, skipping anything hidden behind a wall
+                    let mut candidates: Vec<_> = potential_targets
                         .iter()
                         .filter(|(target_entity, _, _)| *target_entity != entity)
-                        .min_by_key(|(_, target_transform, _)| {
-                            let distance =
-                                transform.translation.distance(target_transform.translation);
-                            (distance * 100.0) as u32
-                        });
+                        .collect();
+                    candidates.sort_by_key(|(_, target_transform, _)| {
+                        let distance = transform.translation.distance(target_transform.translation);
+                        (distance * 100.0) as u32
+                    });
+                    let closest_target = candidates.into_iter().find(|(_, target_transform, _)| {
+                        has_line_of_sight(
+                            &spatial_query,
+                            current_pos,
+                            target_transform.translation.truncate(),
+                        )
+                    });
 
                     if let Some((target_entity, _, _)) = closest_target {
                         info!("Rage Moodel {:?} is AIMING at {:?}", entity, target_entity);
@@ -259,17 +501,30 @@ fn update_ai_behavior(
                         controller.intent = Vec2::ZERO;
                         continue;
                     }
+                    // Nothing in sight, so look again after another cooldown
+                    wander_state.ability_cooldown.reset();
                 }
-                controller.intent = get_wander_intent(mood, &mut wander_state, &time, &mut rng);
+                controller.intent =
+                    get_wander_intent(mood, &mut wander_state, flee_from, &time, &mut *rng);
             }
             AiAction::Aiming { target } => {
                 controller.intent = Vec2::ZERO;
                 if wander_state.state_timer.just_finished() {
                     if let Ok((_, target_transform, _)) = all_moodels.get(target) {
                         info!("Rage Moodel {:?} is CHARGING!", entity);
-                        wander_state.action = AiAction::Charging {
-                            target_pos: target_transform.translation.truncate(),
-                        };
+                        let mut target_pos = target_transform.translation.truncate();
+                        if config.rage_prediction_enabled {
+                            if let Ok(target_velocity) = velocities.get(target) {
+                                target_pos = play_area.clamp(predict_intercept(
+                                    current_pos,
+                                    controller.max_speed,
+                                    target_pos,
+                                    target_velocity.0,
+                                    config.rage_charge_duration,
+                                ));
+                            }
+                        }
+                        wander_state.action = AiAction::Charging { target_pos };
                         // Use config value for charge duration
                         wander_state
                             .state_timer
@@ -282,6 +537,9 @@ fn update_ai_behavior(
                     }
                 }
             }
+            AiAction::Held => {
+                controller.intent = Vec2::ZERO;
+            }
             AiAction::Charging { target_pos } => {
                 controller.intent =
                     (target_pos - transform.translation.truncate()).normalize_or_zero();
@@ -302,8 +560,13 @@ fn update_ai_behavior(
 }
 
 /// System that applies flocking and repulsion forces, respecting priority actions.
+///
+/// Neighbors come from the `SpatialGrid`, so each AI only looks at the Moodels in the
+/// cells around it. This is roughly O(n·k) for k Moodels per neighborhood, instead of
+/// checking every pair of Moodels (O(n²)).
 fn update_ai_magnetism(
     config: Res<AiConfig>, // Get the config resource
+    grid: Res<SpatialGrid>,
     mut query: Query<
         (
             Entity,
@@ -313,12 +576,10 @@ fn update_ai_magnetism(
             &AiWanderState,
             &AiMagnetism,
         ),
-        With<AiEntity>,
+        (With<AiEntity>, Without<Culled>),
     >,
-    all_moodels: Query<(Entity, &Transform, &Mood)>,
+    all_moodels: Query<(&Transform, &Mood, Has<FlockLeader>)>,
 ) {
-    let positions: Vec<_> = all_moodels.iter().collect();
-
     for (entity, transform, mood, mut controller, wander_state, magnetism) in &mut query {
         // PRIORITY OVERRIDE: If the AI is not wandering, skip all magnetism.
         if !matches!(wander_state.action, AiAction::Wandering) {
@@ -326,12 +587,19 @@ fn update_ai_magnetism(
         }
         let mut cohesion_vec = Vec2::ZERO;
         let mut separation_vec = Vec2::ZERO;
-        let mut friendly_neighbor_count = 0;
+        // Friendly neighbors count once each, except a Happy flock leader which counts more.
+        let mut friendly_weight = 0.0;
 
-        for (other_entity, other_transform, other_mood) in &positions {
-            if entity == *other_entity {
+        for other_entity in
+            grid.neighbors(transform.translation.truncate(), magnetism.vision_radius)
+        {
+            if entity == other_entity {
                 continue;
             }
+            let Ok((other_transform, other_mood, other_is_leader)) = all_moodels.get(other_entity)
+            else {
+                continue;
+            };
             let distance = transform
                 .translation
                 .truncate()
@@ -346,20 +614,28 @@ fn update_ai_magnetism(
                         separation_vec += dir / (distance + 0.1);
                     }
                 }
-                let attraction_factor = get_attraction_factor(*mood, **other_mood);
+                let attraction_factor = get_attraction_factor(*mood, *other_mood);
                 if attraction_factor != 0.0 {
+                    let follows_leader =
+                        other_is_leader && *mood == Mood::Happy && *other_mood == Mood::Happy;
+                    let weight = if follows_leader {
+                        config.leader_cohesion_multiplier
+                    } else {
+                        1.0
+                    };
                     cohesion_vec += (other_transform.translation.truncate()
                         - transform.translation.truncate())
-                        * attraction_factor;
+                        * attraction_factor
+                        * weight;
                     if attraction_factor > 0.0 {
-                        friendly_neighbor_count += 1;
+                        friendly_weight += weight;
                     }
                 }
             }
         }
 
-        if friendly_neighbor_count > 0 {
-            cohesion_vec /= friendly_neighbor_count as f32;
+        if friendly_weight > 0.0 {
+            cohesion_vec /= friendly_weight;
         }
 
         // Use config values for strengths
@@ -369,16 +645,208 @@ fn update_ai_magnetism(
     }
 }
 
+fn tick_flock_leader_timer(
+    time: Res<Time>,
+    config: Res<AiConfig>,
+    mut timer: ResMut<FlockLeaderTimer>,
+) {
+    if config.is_changed() {
+        timer.0.set_duration(std::time::Duration::from_secs_f32(
+            config.leader_reassign_interval,
+        ));
+    }
+    timer.0.tick(time.delta());
+}
+
+/// System that picks one leader per cluster of Happy Moodels: the one with the most Happy
+/// neighbors, skipping any already within sight of a better-connected leader. Leaders that
+/// stop being Happy are demoted straight away, and a replacement is picked on the spot.
+fn update_flock_leaders(
+    mut commands: Commands,
+    timer: Res<FlockLeaderTimer>,
+    grid: Res<SpatialGrid>,
+    moodel_query: Query<
+        (Entity, &Transform, &Mood, &AiMagnetism, Has<FlockLeader>),
+        With<AiEntity>,
+    >,
+    demoted_query: Query<(Entity, &Mood), (With<FlockLeader>, Changed<Mood>)>,
+) {
+    let mut leader_demoted = false;
+    for (entity, mood) in &demoted_query {
+        if *mood != Mood::Happy {
+            commands.entity(entity).remove::<FlockLeader>();
+            leader_demoted = true;
+        }
+    }
+    if !timer.0.just_finished() && !leader_demoted {
+        return;
+    }
+
+    // Rank the Happy Moodels by how many Happy neighbors they can see.
+    // Current leaders win ties, so leadership doesn't flicker between equals.
+    let mut candidates: Vec<_> = moodel_query
+        .iter()
+        .filter(|(_, _, mood, _, _)| **mood == Mood::Happy)
+        .map(|(entity, transform, _, magnetism, is_leader)| {
+            let position = transform.translation.truncate();
+            let happy_neighbors = grid
+                .neighbors(position, magnetism.vision_radius)
+                .filter(|other| *other != entity)
+                .filter_map(|other| moodel_query.get(other).ok())
+                .filter(|(_, other_transform, other_mood, _, _)| {
+                    **other_mood == Mood::Happy
+                        && other_transform.translation.truncate().distance(position)
+                            < magnetism.vision_radius
+                })
+                .count();
+            (
+                entity,
+                position,
+                magnetism.vision_radius,
+                happy_neighbors,
+                is_leader,
+            )
+        })
+        .filter(|(_, _, _, happy_neighbors, _)| *happy_neighbors > 0)
+        .collect();
+    candidates.sort_by_key(|(_, _, _, happy_neighbors, is_leader)| {
+        std::cmp::Reverse((*happy_neighbors, *is_leader))
+    });
+
+    let mut leaders: Vec<(Entity, Vec2)> = Vec::new();
+    for (entity, position, vision_radius, _, _) in candidates {
+        let outside_other_flocks = leaders
+            .iter()
+            .all(|(_, leader_pos)| leader_pos.distance(position) >= vision_radius);
+        if outside_other_flocks {
+            leaders.push((entity, position));
+        }
+    }
+
+    for (entity, _, _, _, is_leader) in &moodel_query {
+        let should_lead = leaders.iter().any(|(leader, _)| *leader == entity);
+        if should_lead && !is_leader {
+            commands.entity(entity).insert(FlockLeader);
+        } else if !should_lead && is_leader {
+            commands.entity(entity).remove::<FlockLeader>();
+        }
+    }
+}
+
+/// System that sets vision radius and separation distance from the mood, when the mood or config changes.
+fn apply_magnetism_for_mood(
+    config: Res<AiConfig>,
+    mut query: Query<(Ref<Mood>, &mut AiMagnetism), With<AiEntity>>,
+) {
+    for (mood, mut magnetism) in &mut query {
+        if !mood.is_changed() && !config.is_changed() {
+            continue;
+        }
+        magnetism.vision_radius = config.vision_radius_for(*mood);
+        magnetism.separation_distance = config.separation_distance_for(*mood);
+    }
+}
+
+fn cursor_attract_enabled(cursor_attract: Res<CursorAttract>) -> bool {
+    cursor_attract.0
+}
+
+fn toggle_cursor_attract(mut cursor_attract: ResMut<CursorAttract>) {
+    cursor_attract.0 = !cursor_attract.0;
+    info!("Cursor attraction: {}", cursor_attract.0);
+}
+
+#[cfg(feature = "dev")]
+fn ai_debug_gizmos_enabled(debug_gizmos: Res<AiDebugGizmos>) -> bool {
+    debug_gizmos.0
+}
+
+/// Draws an arrow along each Moodel's intent (longer means stronger steering), its vision
+/// radius, and a line to whatever a Rage Moodel is aiming or charging at.
+#[cfg(feature = "dev")]
+fn draw_ai_debug_gizmos(
+    mut gizmos: Gizmos,
+    ai_query: Query<
+        (
+            &GlobalTransform,
+            &MovementController,
+            &AiMagnetism,
+            &AiWanderState,
+        ),
+        With<AiEntity>,
+    >,
+    targets: Query<&GlobalTransform>,
+) {
+    const INTENT_ARROW_LENGTH: f32 = 80.0;
+    let intent_color = Color::srgb(0.2, 0.9, 1.0);
+    let vision_color = Color::srgba(1.0, 1.0, 1.0, 0.15);
+
+    for (transform, controller, magnetism, wander_state) in &ai_query {
+        let position = transform.translation().truncate();
+        if controller.intent != Vec2::ZERO {
+            let tip = position + controller.intent * INTENT_ARROW_LENGTH;
+            gizmos.arrow_2d(position, tip, intent_color);
+        }
+        gizmos.circle_2d(position, magnetism.vision_radius, vision_color);
+
+        match wander_state.action {
+            AiAction::Aiming { target } => {
+                if let Ok(target_transform) = targets.get(target) {
+                    let target_pos = target_transform.translation().truncate();
+                    gizmos.line_2d(position, target_pos, Color::srgb(1.0, 0.85, 0.1));
+                }
+            }
+            AiAction::Charging { target_pos } => {
+                gizmos.line_2d(position, target_pos, Color::srgb(1.0, 0.15, 0.15));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// System that pulls wandering AI towards the cursor, like fish following food.
+fn update_ai_cursor_attraction(
+    config: Res<AiConfig>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut query: Query<
+        (&Transform, &mut MovementController, &AiWanderState),
+        (With<AiEntity>, Without<Culled>),
+    >,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(cursor_pos) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    for (transform, mut controller, wander_state) in &mut query {
+        // PRIORITY OVERRIDE: If the AI is not wandering, skip cursor attraction.
+        if !matches!(wander_state.action, AiAction::Wandering) {
+            continue;
+        }
+        let to_cursor = cursor_pos - transform.translation.truncate();
+        controller.intent += to_cursor.normalize_or_zero() * config.cursor_attract_strength;
+    }
+}
+
 /// System to steer AI away from play area edges, respecting priority actions.
 fn update_ai_boundary_avoidance(
     play_area: Res<PlayArea>,
     config: Res<AiConfig>, // Get the config resource
-    mut query: Query<(&Transform, &mut MovementController, &AiWanderState), With<AiEntity>>,
+    mut query: Query<
+        (
+            &Transform,
+            &mut MovementController,
+            &AiWanderState,
+            Has<Culled>,
+        ),
+        With<AiEntity>,
+    >,
 ) {
     let half_size = play_area.size / 2.0;
     let boundaries = Rect::from_center_half_size(play_area.center, half_size);
 
-    for (transform, mut controller, wander_state) in &mut query {
+    for (transform, mut controller, wander_state, is_culled) in &mut query {
         // PRIORITY OVERRIDE: If the AI is not wandering, skip boundary avoidance.
         if !matches!(wander_state.action, AiAction::Wandering) {
             continue;
@@ -387,25 +855,103 @@ fn update_ai_boundary_avoidance(
         let mut avoidance_force = Vec2::ZERO;
 
         // Use config values for margin and strength
-        if pos.x < boundaries.min.x + config.avoidance_margin {
-            avoidance_force.x = config.avoidance_strength;
+        if let PlayAreaShape::Circle { radius } = play_area.shape {
+            // Steer radially inward near the circumference
+            let offset = pos - play_area.center;
+            if offset.length() > radius - config.avoidance_margin {
+                avoidance_force = -offset.normalize_or_zero() * config.avoidance_strength;
+            }
+        } else {
+            if pos.x < boundaries.min.x + config.avoidance_margin {
+                avoidance_force.x = config.avoidance_strength;
+            }
+            if pos.x > boundaries.max.x - config.avoidance_margin {
+                avoidance_force.x = -config.avoidance_strength;
+            }
+            if pos.y < boundaries.min.y + config.avoidance_margin {
+                avoidance_force.y = config.avoidance_strength;
+            }
+            if pos.y > boundaries.max.y - config.avoidance_margin {
+                avoidance_force.y = -config.avoidance_strength;
+            }
         }
-        if pos.x > boundaries.max.x - config.avoidance_margin {
-            avoidance_force.x = -config.avoidance_strength;
+
+        // Add the avoidance force to the final intent. This force is strong
+        // and should override other behaviors when near a wall.
+        controller.intent += avoidance_force;
+        // Culled Moodels keep their old intent instead of recomputing it, so stop the
+        // avoidance force from piling up on it frame after frame.
+        if is_culled {
+            controller.intent = controller.intent.clamp_length_max(1.0);
         }
-        if pos.y < boundaries.min.y + config.avoidance_margin {
-            avoidance_force.y = config.avoidance_strength;
+    }
+}
+
+/// System to steer AI away from nearby obstacles, respecting priority actions.
+fn update_ai_obstacle_avoidance(
+    config: Res<AiConfig>,
+    obstacle_query: Query<
+        (
+            &ColliderAabb,
+            &Transform,
+            Option<&ObstacleHull>,
+            Option<&ObstacleRadius>,
+        ),
+        With<ObstacleCollider>,
+    >,
+    mut query: Query<
+        (&Transform, &mut MovementController, &AiWanderState),
+        (With<AiEntity>, Without<Culled>),
+    >,
+) {
+    for (transform, mut controller, wander_state) in &mut query {
+        // PRIORITY OVERRIDE: Commanded moves steer themselves and Rage charges punch through.
+        if !matches!(wander_state.action, AiAction::Wandering) {
+            continue;
         }
-        if pos.y > boundaries.max.y - config.avoidance_margin {
-            avoidance_force.y = -config.avoidance_strength;
+        let pos = transform.translation.truncate();
+        let mut avoidance_force = Vec2::ZERO;
+
+        for (aabb, obstacle_transform, hull, radius) in &obstacle_query {
+            let origin = obstacle_transform.translation.truncate();
+            let closest = closest_obstacle_point(pos, origin, aabb, hull, radius);
+            let offset = pos - closest;
+            let distance = offset.length();
+            if distance >= config.obstacle_avoidance_margin {
+                continue;
+            }
+            // Inside the bounds the closest point is ourselves, so push away from the center instead.
+            let away = if distance > 0.0 {
+                offset / distance
+            } else {
+                (pos - aabb.center()).normalize_or_zero()
+            };
+            let proximity = 1.0 - distance / config.obstacle_avoidance_margin;
+            avoidance_force += away * proximity * config.obstacle_avoidance_strength;
         }
 
-        // Add the avoidance force to the final intent. This force is strong
-        // and should override other behaviors when near a wall.
         controller.intent += avoidance_force;
     }
 }
 
+/// The closest point on an obstacle to `pos`: on its hull or circle if it has one,
+/// otherwise on its bounds. Points inside the obstacle are their own closest point.
+fn closest_obstacle_point(
+    pos: Vec2,
+    origin: Vec2,
+    aabb: &ColliderAabb,
+    hull: Option<&ObstacleHull>,
+    radius: Option<&ObstacleRadius>,
+) -> Vec2 {
+    if let Some(ObstacleHull(hull)) = hull {
+        return origin + closest_point_on_convex_polygon(hull, pos - origin);
+    }
+    if let Some(ObstacleRadius(radius)) = radius {
+        return origin + (pos - origin).clamp_length_max(*radius);
+    }
+    pos.clamp(aabb.min, aabb.max)
+}
+
 /// Generates a noise vector using different seeds for X and Y axes for more random movement.
 fn get_2d_noise(input: f32, octaves: u8, seed: f32) -> Vec2 {
     let noise_x = fbm_simplex_2d_seeded(Vec2::new(input, 0.0), octaves as usize, 0.5, 2.0, seed);
@@ -423,6 +969,7 @@ fn get_2d_noise(input: f32, octaves: u8, seed: f32) -> Vec2 {
 fn get_wander_intent(
     mood: &Mood,
     wander_state: &mut AiWanderState,
+    flee_from: Option<Vec2>,
     time: &Time,
     rng: &mut impl Rng,
 ) -> Vec2 {
@@ -456,6 +1003,14 @@ fn get_wander_intent(
                 Vec2::ZERO
             }
         }
+        Mood::Fear => match flee_from {
+            // Bolt directly away from the threat
+            Some(away) => away.normalize_or_zero(),
+            // Jittery, nervous wandering when no threat is in sight
+            None => {
+                get_2d_noise(time_input * 0.8, 2, wander_state.noise_seed).normalize_or_zero() * 0.5
+            }
+        },
     }
 }
 
@@ -472,11 +1027,50 @@ fn get_attraction_factor(my_mood: Mood, other_mood: Mood) -> f32 {
         (Rage, Sad) => -1.0,
         (Rage, _) => 0.2,
         (Calm, Calm) => 0.2,
+        (Fear, Rage) => -1.0,
+        (Fear, Calm) => 0.3,
         (Neutral, _) => -0.1,
         _ => 0.0,
     }
 }
 
+/// Where to charge to meet a moving target, assuming it keeps its current velocity.
+/// The lead is capped at `max_time`, since the charge won't last longer than that.
+fn predict_intercept(
+    charger_pos: Vec2,
+    charger_speed: f32,
+    target_pos: Vec2,
+    target_velocity: Vec2,
+    max_time: f32,
+) -> Vec2 {
+    if charger_speed <= 0.0 {
+        return target_pos;
+    }
+    // Refine the travel time a few times, since the target moves while we close the gap.
+    let mut time_to_hit = charger_pos.distance(target_pos) / charger_speed;
+    for _ in 0..3 {
+        let lead = target_pos + target_velocity * time_to_hit.min(max_time);
+        time_to_hit = charger_pos.distance(lead) / charger_speed;
+    }
+    target_pos + target_velocity * time_to_hit.min(max_time)
+}
+
+/// Whether no obstacle blocks the straight line between two points. Other Moodels don't block sight.
+fn has_line_of_sight(spatial_query: &SpatialQuery, from: Vec2, to: Vec2) -> bool {
+    let Ok(direction) = Dir2::new(to - from) else {
+        return true;
+    };
+    spatial_query
+        .cast_ray(
+            from,
+            direction,
+            from.distance(to),
+            true,
+            &SpatialQueryFilter::from_mask(COLLISION_LAYER_OBSTACLE),
+        )
+        .is_none()
+}
+
 /// Helper function that uses raycasting to calculate a steering force to avoid obstacles.
 fn calculate_avoidance_force(
     spatial_query: &mut SpatialQuery,
@@ -485,7 +1079,9 @@ fn calculate_avoidance_force(
     self_entity: Entity,
 ) -> Vec2 {
     let mut avoidance_force = Vec2::ZERO;
-    if velocity.length_squared() < 0.1 { return avoidance_force; } // No avoidance if not moving
+    if velocity.length_squared() < 0.1 {
+        return avoidance_force;
+    } // No avoidance if not moving
 
     // How far ahead to "see" for obstacles. Scales with speed.
     let look_ahead_distance = 50.0 + velocity.length() * 0.2;
@@ -501,7 +1097,9 @@ fn calculate_avoidance_force(
 
     for &dir in &whiskers {
         // Continue if the direction is somehow zero to prevent panics
-        if dir == Vec2::ZERO { continue; }
+        if dir == Vec2::ZERO {
+            continue;
+        }
 
         if let Some(hit) = spatial_query.cast_ray(
             position,
@@ -521,3 +1119,65 @@ fn calculate_avoidance_force(
 
     avoidance_force.normalize_or_zero()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_obstacles_are_avoided_by_their_radius() {
+        let origin = Vec2::new(100.0, 100.0);
+        let aabb = ColliderAabb {
+            min: origin - Vec2::splat(50.0),
+            max: origin + Vec2::splat(50.0),
+        };
+        let radius = ObstacleRadius(50.0);
+        // Just inside the bounding box's corner, but well clear of the circle itself.
+        let corner = origin + Vec2::splat(45.0);
+        let closest = closest_obstacle_point(corner, origin, &aabb, None, Some(&radius));
+        assert!((closest.distance(origin) - 50.0).abs() < 1e-4);
+        assert!(corner.distance(closest) > 10.0);
+        // The bounding box alone would have counted the corner as touching the obstacle.
+        assert_eq!(
+            closest_obstacle_point(corner, origin, &aabb, None, None),
+            corner
+        );
+        // Points inside the circle are their own closest point.
+        let inside = origin + Vec2::new(10.0, 0.0);
+        assert_eq!(
+            closest_obstacle_point(inside, origin, &aabb, None, Some(&radius)),
+            inside
+        );
+    }
+
+    #[test]
+    fn intercept_of_a_stationary_target_is_the_target() {
+        let target = Vec2::new(300.0, -120.0);
+        assert_eq!(
+            predict_intercept(Vec2::ZERO, 200.0, target, Vec2::ZERO, 2.0),
+            target
+        );
+    }
+
+    #[test]
+    fn intercept_leads_a_moving_target() {
+        let target = Vec2::new(400.0, 0.0);
+        let velocity = Vec2::new(0.0, 100.0);
+        let aim = predict_intercept(Vec2::ZERO, 200.0, target, velocity, 5.0);
+        // Solving |target + velocity * t| = 200 * t gives t = 4 / sqrt(3).
+        let exact = target + velocity * (4.0 / 3f32.sqrt());
+        assert!(
+            aim.distance(exact) < 2.0,
+            "aimed at {aim}, expected {exact}"
+        );
+    }
+
+    #[test]
+    fn intercept_of_an_escaping_target_is_capped() {
+        // Running away faster than the charger, so it can never be caught.
+        let target = Vec2::new(100.0, 0.0);
+        let velocity = Vec2::new(300.0, 0.0);
+        let aim = predict_intercept(Vec2::ZERO, 200.0, target, velocity, 1.5);
+        assert_eq!(aim, target + velocity * 1.5);
+    }
+}
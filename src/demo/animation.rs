@@ -11,7 +11,7 @@ use std::time::Duration;
 use crate::{
     AppSystems, PausableSystems,
     audio::sound_effect,
-    demo::{movement::MovementController, player::PlayerAssets},
+    demo::{culling::Culled, movement::MovementController, player::PlayerAssets},
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -36,7 +36,10 @@ pub(super) fn plugin(app: &mut App) {
 
 /// Update the sprite direction and animation state (idling/walking).
 fn update_animation_movement(
-    mut player_query: Query<(&MovementController, &mut Sprite, &mut PlayerAnimation)>,
+    mut player_query: Query<
+        (&MovementController, &mut Sprite, &mut PlayerAnimation),
+        Without<Culled>,
+    >,
 ) {
     for (controller, mut sprite, mut animation) in &mut player_query {
         let dx = controller.intent.x;
@@ -54,14 +57,17 @@ fn update_animation_movement(
 }
 
 /// Update the animation timer.
-fn update_animation_timer(time: Res<Time>, mut query: Query<&mut PlayerAnimation>) {
+fn update_animation_timer(
+    time: Res<Time>,
+    mut query: Query<&mut PlayerAnimation, Without<Culled>>,
+) {
     for mut animation in &mut query {
         animation.update_timer(time.delta());
     }
 }
 
 /// Update the texture atlas to reflect changes in the animation.
-fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut Sprite)>) {
+fn update_animation_atlas(mut query: Query<(&PlayerAnimation, &mut Sprite), Without<Culled>>) {
     for (animation, mut sprite) in &mut query {
         let Some(atlas) = sprite.texture_atlas.as_mut() else {
             continue;
@@ -0,0 +1,188 @@
+//! Keeps the play area in view.
+//!
+//! When a level spawns, or the window is resized, the camera zooms and centers so the
+//! whole play area fits the window with a small margin.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowResized},
+};
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        movement::{PlayArea, PlayAreaShape},
+        player_input::Selected,
+    },
+    keybindings::{Action, action_just_pressed},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        fit_camera_to_play_area
+            .run_if(
+                in_state(Screen::Gameplay)
+                    .and(resource_changed::<PlayArea>.or(on_event::<WindowResized>)),
+            )
+            .in_set(AppSystems::Update),
+    );
+    app.register_type::<CameraFollow>();
+    app.init_resource::<CameraFollow>();
+    app.add_systems(
+        Update,
+        (
+            toggle_camera_follow
+                .run_if(action_just_pressed(Action::FollowSelected))
+                .in_set(AppSystems::RecordInput),
+            follow_selected_moodel
+                .after(fit_camera_to_play_area)
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_camera);
+}
+
+/// Roughly how long the camera takes to catch up with its target, in seconds.
+const FOLLOW_SMOOTH_TIME: f32 = 0.3;
+
+/// The Moodel the camera is following, if any.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct CameraFollow(pub Option<Entity>);
+
+/// Space kept between the play area and the window edges, in logical pixels.
+const FIT_MARGIN: f32 = 40.0;
+
+/// The orthographic scale at which `area` fits in a viewport of `viewport` logical pixels,
+/// leaving [`FIT_MARGIN`] on every side.
+fn fit_scale(area: Vec2, viewport: Vec2) -> f32 {
+    let available = (viewport - 2.0 * FIT_MARGIN).max(Vec2::ONE);
+    let ratio = area / available;
+    ratio.x.max(ratio.y)
+}
+
+fn fit_camera_to_play_area(
+    play_area: Res<PlayArea>,
+    follow: Res<CameraFollow>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let (mut transform, mut projection) = camera.into_inner();
+    let Projection::Orthographic(orthographic) = projection.as_mut() else {
+        return;
+    };
+    let area = match play_area.shape {
+        PlayAreaShape::Rect => play_area.size,
+        PlayAreaShape::Circle { radius } => Vec2::splat(2.0 * radius),
+    };
+    orthographic.scale = fit_scale(area, window.size());
+    // A followed Moodel keeps the camera where it is
+    if follow.0.is_none() {
+        transform.translation.x = play_area.center.x;
+        transform.translation.y = play_area.center.y;
+    }
+}
+
+/// Follows the selected Moodel, if exactly one is selected, or stops following.
+fn toggle_camera_follow(
+    mut follow: ResMut<CameraFollow>,
+    selected_query: Query<Entity, With<Selected>>,
+) {
+    if follow.0.take().is_some() {
+        return;
+    }
+    match selected_query.iter().collect::<Vec<_>>()[..] {
+        [entity] => follow.0 = Some(entity),
+        _ => info!("Select a single Moodel to follow it"),
+    }
+}
+
+/// Eases the camera toward the followed Moodel, or back to the play area center once it is
+/// deselected, with a critically damped spring.
+fn follow_selected_moodel(
+    time: Res<Time>,
+    mut follow: ResMut<CameraFollow>,
+    play_area: Res<PlayArea>,
+    target_query: Query<&Transform, (With<Selected>, Without<Camera2d>)>,
+    mut camera: Single<&mut Transform, With<Camera2d>>,
+    mut velocity: Local<Vec2>,
+) {
+    let target = follow.0.and_then(|entity| target_query.get(entity).ok());
+    if follow.0.is_some() && target.is_none() {
+        follow.0 = None;
+    }
+    let goal = match target {
+        Some(transform) => play_area.clamp(transform.translation.truncate()),
+        None => play_area.center,
+    };
+
+    let position = camera.translation.truncate();
+    let position = smooth_damp(position, goal, &mut velocity, time.delta_secs());
+    camera.translation.x = position.x;
+    camera.translation.y = position.y;
+}
+
+/// Moves `current` toward `target` like a critically damped spring, carrying `velocity` between
+/// frames. Doesn't overshoot.
+fn smooth_damp(current: Vec2, target: Vec2, velocity: &mut Vec2, delta: f32) -> Vec2 {
+    let omega = 2.0 / FOLLOW_SMOOTH_TIME;
+    let x = omega * delta;
+    // Cheap approximation of exp(-x)
+    let decay = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let offset = current - target;
+    let temp = (*velocity + omega * offset) * delta;
+    *velocity = (*velocity - omega * temp) * decay;
+    target + (offset + temp) * decay
+}
+
+/// Menus are laid out for the default camera.
+fn reset_camera(
+    mut follow: ResMut<CameraFollow>,
+    camera: Single<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    follow.0 = None;
+    let (mut transform, mut projection) = camera.into_inner();
+    if let Projection::Orthographic(orthographic) = projection.as_mut() {
+        orthographic.scale = 1.0;
+    }
+    transform.translation.x = 0.0;
+    transform.translation.y = 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn large_arena_fits_the_window() {
+        let area = Vec2::new(2000.0, 1500.0);
+        let viewport = Vec2::new(1280.0, 720.0);
+        let scale = fit_scale(area, viewport);
+        // Zoomed out, with the taller side filling the window up to the margins.
+        assert!(scale > 1.0);
+        let on_screen = area / scale;
+        assert!(on_screen.x <= viewport.x - 2.0 * FIT_MARGIN);
+        assert!((on_screen.y - (viewport.y - 2.0 * FIT_MARGIN)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn smooth_damp_settles_without_overshooting() {
+        let target = Vec2::new(100.0, -50.0);
+        let mut position = Vec2::ZERO;
+        let mut velocity = Vec2::ZERO;
+        let mut previous_distance = position.distance(target);
+        for _ in 0..300 {
+            position = smooth_damp(position, target, &mut velocity, 1.0 / 60.0);
+            // Allow for float rounding once it has all but arrived.
+            assert!(position.x <= target.x + 1e-4 && position.y >= target.y - 1e-4);
+            let distance = position.distance(target);
+            assert!(distance <= previous_distance + 1e-4);
+            previous_distance = distance;
+        }
+        assert!(previous_distance < 0.1);
+    }
+}
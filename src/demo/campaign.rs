@@ -0,0 +1,256 @@
+//! An ordered run of levels, played one after another.
+//!
+//! When a level's victory celebration finishes the game moves on to the victory
+//! screen, which offers to play the next level.
+//!
+//! The levels are listed in `assets/levels/campaign.manifest.json`, which hot-reloads
+//! like the mood rules. When the file is missing, the built-in level list applies.
+//!
+//! Beaten levels are saved to disk (or local storage on web), so unlocked levels
+//! stay unlocked across sessions.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::{
+    demo::{
+        level::{ActiveLevel, ActiveLevelSource, LevelClock},
+        victory::VictorySequence,
+    },
+    save,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Campaign>();
+    app.init_resource::<Campaign>();
+    app.register_type::<CampaignProgress>();
+    app.insert_resource(CampaignProgress::load());
+
+    app.add_systems(Startup, load_level_manifest);
+    app.add_systems(
+        Update,
+        save_campaign_progress.run_if(
+            resource_changed::<CampaignProgress>.and(not(resource_added::<CampaignProgress>)),
+        ),
+    );
+    app.add_systems(Update, apply_loaded_level_manifest);
+
+    app.add_systems(OnEnter(VictorySequence::Finished), finish_level);
+    app.add_systems(OnEnter(Screen::Title), restart_campaign);
+}
+
+/// The levels of the campaign, in the order they are played.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct Campaign {
+    /// The levels, in the order they are played.
+    pub levels: Vec<CampaignLevel>,
+    /// Index into `levels` of the level being played.
+    pub current: usize,
+    /// How each completed level went, in the order they were beaten.
+    pub results: Vec<LevelResult>,
+}
+
+/// A level of the campaign, as listed in the manifest.
+#[derive(Reflect, Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignLevel {
+    /// Level file path, relative to the assets folder.
+    pub path: String,
+    /// The name shown in the level select menu.
+    pub name: String,
+    /// An image shown next to the level in the level select menu.
+    #[serde(default)]
+    pub thumbnail: Option<String>,
+}
+
+impl CampaignLevel {
+    fn new(path: &str, name: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            name: name.to_string(),
+            thumbnail: None,
+        }
+    }
+}
+
+/// The completion time of a beaten level, next to its par time.
+#[derive(Reflect, Debug, Clone)]
+pub struct LevelResult {
+    pub name: String,
+    pub time_secs: f32,
+    pub par_time_secs: Option<f32>,
+}
+
+impl Default for Campaign {
+    fn default() -> Self {
+        Self {
+            levels: vec![
+                CampaignLevel::new("levels/tutorial_1.level.json", "The Walled Garden"),
+                CampaignLevel::new("levels/tutorial_2.level.json", "Two Rooms"),
+            ],
+            current: 0,
+            results: Vec::new(),
+        }
+    }
+}
+
+impl Campaign {
+    /// The file path of the level being played, if the campaign isn't over.
+    pub fn current_level(&self) -> Option<&str> {
+        self.levels
+            .get(self.current)
+            .map(|level| level.path.as_str())
+    }
+
+    /// Whether there is a level after the one being played.
+    pub fn has_next(&self) -> bool {
+        self.current + 1 < self.levels.len()
+    }
+
+    /// Moves on to the next level, returning its path, or `None` when the campaign is complete.
+    pub fn advance(&mut self) -> Option<&str> {
+        self.current += 1;
+        self.current_level()
+    }
+}
+
+/// The levels that have been beaten, by file path. Each level unlocks once the one before it
+/// has been beaten.
+#[derive(Resource, Reflect, Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct CampaignProgress {
+    pub completed: HashSet<String>,
+}
+
+/// Where progress is saved: a file in the working directory natively, or a local
+/// storage key on web.
+const PROGRESS_SAVE_KEY: &str = "mood_progress.json";
+
+impl CampaignProgress {
+    /// Reads the saved progress, starting fresh if there is none or it can't be read.
+    pub fn load() -> Self {
+        let Some(json) = save::read(PROGRESS_SAVE_KEY) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|error| {
+            warn!("Ignoring unreadable campaign progress: {}", error);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => save::write(PROGRESS_SAVE_KEY, &json),
+            Err(error) => error!("Failed to serialize campaign progress: {}", error),
+        }
+    }
+
+    /// Whether the level at `index` of the campaign can be played.
+    pub fn is_unlocked(&self, campaign: &Campaign, index: usize) -> bool {
+        index == 0
+            || campaign
+                .levels
+                .get(index - 1)
+                .is_some_and(|previous| self.completed.contains(&previous.path))
+    }
+}
+
+fn save_campaign_progress(progress: Res<CampaignProgress>) {
+    progress.save();
+}
+
+const LEVEL_MANIFEST_PATH: &str = "levels/campaign.manifest.json";
+
+/// The campaign's level list, as loaded from [`LEVEL_MANIFEST_PATH`].
+#[derive(Asset, TypePath, Debug, Clone, Deserialize)]
+pub struct LevelManifest {
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Resource)]
+struct LevelManifestHandle(Handle<LevelManifest>);
+
+fn load_level_manifest(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(LevelManifestHandle(asset_server.load(LEVEL_MANIFEST_PATH)));
+}
+
+/// Copies the manifest's levels into the campaign when it loads or is edited.
+fn apply_loaded_level_manifest(
+    mut asset_events: EventReader<AssetEvent<LevelManifest>>,
+    manifest_handle: Option<Res<LevelManifestHandle>>,
+    manifests: Res<Assets<LevelManifest>>,
+    mut campaign: ResMut<Campaign>,
+) {
+    let Some(manifest_handle) = manifest_handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id == manifest_handle.0.id() {
+                if let Some(manifest) = manifests.get(*id) {
+                    info!("Campaign levels loaded from {}", LEVEL_MANIFEST_PATH);
+                    campaign.levels = manifest.levels.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Records how the level went and shows the victory screen. Levels from outside the campaign,
+/// like shared ones, don't count towards it.
+fn finish_level(
+    mut campaign: ResMut<Campaign>,
+    mut progress: ResMut<CampaignProgress>,
+    active_level: Res<ActiveLevel>,
+    source: Res<ActiveLevelSource>,
+    clock: Res<LevelClock>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    next_screen.set(Screen::Victory);
+    if !source.is_campaign() {
+        return;
+    }
+    // The clock stops ticking once the level is won, so it holds the completion time.
+    if let Some(level) = &active_level.0 {
+        campaign.results.push(LevelResult {
+            name: level.name.clone(),
+            time_secs: clock.elapsed,
+            par_time_secs: level.meta.par_time_secs,
+        });
+    }
+    if let Some(path) = campaign.current_level() {
+        progress.completed.insert(path.to_string());
+    }
+    if !campaign.has_next() {
+        info!("Campaign complete!");
+    }
+}
+
+/// Starts over from the first level after returning to the title screen. Leaving gameplay
+/// for the defeat screen keeps the current level, so it can be retried.
+fn restart_campaign(mut campaign: ResMut<Campaign>) {
+    campaign.current = 0;
+    campaign.results.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn campaign_advances_until_the_last_level() {
+        let mut campaign = Campaign::default();
+        assert_eq!(
+            campaign.current_level(),
+            Some("levels/tutorial_1.level.json")
+        );
+        assert!(campaign.has_next());
+        assert_eq!(campaign.advance(), Some("levels/tutorial_2.level.json"));
+        // Winning the final level ends the campaign.
+        assert!(!campaign.has_next());
+        assert_eq!(campaign.advance(), None);
+    }
+}
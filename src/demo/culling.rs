@@ -0,0 +1,88 @@
+//! Skips expensive per-frame work for Moodels outside the camera view.
+//!
+//! Wandering Moodels outside the viewport (expanded by a margin) are marked [`Culled`].
+//! The AI behavior, magnetism and animation systems ignore culled Moodels, but
+//! boundary avoidance still runs so they can't drift out of the play area.
+//!
+//! Moodels busy with anything else, like following a command or charging, are never culled,
+//! so they still arrive and stop when out of view.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::ai::{AiAction, AiEntity, AiWanderState, update_ai_behavior},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<CullingConfig>();
+    app.init_resource::<CullingConfig>();
+    app.register_type::<Culled>();
+
+    app.add_systems(
+        Update,
+        update_culling
+            .before(update_ai_behavior)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Tuning for off-screen culling.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct CullingConfig {
+    pub enabled: bool,
+    /// How far outside the viewport a Moodel must be before it is culled, in world units.
+    pub margin: f32,
+}
+
+impl Default for CullingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            margin: 200.0,
+        }
+    }
+}
+
+/// Marker for Moodels that are currently outside the camera view.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Culled;
+
+fn update_culling(
+    mut commands: Commands,
+    config: Res<CullingConfig>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    moodel_query: Query<(Entity, &Transform, &AiWanderState, Has<Culled>), With<AiEntity>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let visible_area = if config.enabled {
+        camera_world_rect(camera, camera_transform).map(|rect| rect.inflate(config.margin))
+    } else {
+        None
+    };
+
+    for (entity, transform, wander_state, is_culled) in &moodel_query {
+        let should_cull = matches!(wander_state.action, AiAction::Wandering)
+            && visible_area.is_some_and(|rect| !rect.contains(transform.translation.truncate()));
+        if should_cull && !is_culled {
+            commands.entity(entity).insert(Culled);
+        } else if !should_cull && is_culled {
+            commands.entity(entity).remove::<Culled>();
+        }
+    }
+}
+
+/// The part of the world currently shown by the camera.
+fn camera_world_rect(camera: &Camera, camera_transform: &GlobalTransform) -> Option<Rect> {
+    let viewport = camera.logical_viewport_rect()?;
+    let corner_a = camera
+        .viewport_to_world_2d(camera_transform, viewport.min)
+        .ok()?;
+    let corner_b = camera
+        .viewport_to_world_2d(camera_transform, viewport.max)
+        .ok()?;
+    Some(Rect::from_corners(corner_a, corner_b))
+}
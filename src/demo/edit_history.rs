@@ -0,0 +1,142 @@
+//! Undo and redo for the in-game editor tools.
+//!
+//! Every edit is stored as an [`EditAction`]. Undoing an action reverts it and
+//! pushes the opposite action onto the redo stack, and redoing does the reverse.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::level::{
+        LevelPalette, ObstacleData, ObstacleSource, ResetLevel, SpawnLevel, spawn_obstacle,
+    },
+    keybindings::keyboard_available,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<EditHistory>();
+    app.init_resource::<EditHistory>();
+
+    app.add_systems(
+        Update,
+        handle_edit_history_input
+            .run_if(in_state(Screen::Gameplay).and(keyboard_available))
+            .in_set(AppSystems::RecordInput)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), clear_edit_history);
+    // Restarting or replacing the level rebuilds it, so old edits no longer apply.
+    app.add_systems(
+        Update,
+        clear_edit_history.run_if(on_event::<ResetLevel>.or(on_event::<SpawnLevel>)),
+    );
+}
+
+/// A single reversible edit to the level.
+#[derive(Debug, Clone)]
+pub enum EditAction {
+    /// An obstacle was added to the level.
+    SpawnedObstacle(Entity),
+    /// An obstacle was removed from the level, and can be rebuilt from its data.
+    RemovedObstacle(ObstacleData),
+}
+
+/// The undo and redo stacks of the editor tools.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct EditHistory {
+    #[reflect(ignore)]
+    undo: Vec<EditAction>,
+    #[reflect(ignore)]
+    redo: Vec<EditAction>,
+    /// How many edits can be undone. The oldest are forgotten first.
+    pub max_depth: usize,
+}
+
+impl Default for EditHistory {
+    fn default() -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            max_depth: 50,
+        }
+    }
+}
+
+impl EditHistory {
+    /// Records a new edit. This clears the redo stack, as it no longer follows on.
+    pub fn record(&mut self, action: EditAction) {
+        self.redo.clear();
+        self.push_undo(action);
+    }
+
+    fn push_undo(&mut self, action: EditAction) {
+        self.undo.push(action);
+        if self.undo.len() > self.max_depth {
+            let excess = self.undo.len() - self.max_depth;
+            self.undo.drain(..excess);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+fn handle_edit_history_input(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<EditHistory>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    palette: Res<LevelPalette>,
+    obstacle_query: Query<&ObstacleSource>,
+) {
+    if !input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !input.just_pressed(KeyCode::KeyZ)
+    {
+        return;
+    }
+    let redo = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    let Some(action) = (if redo {
+        history.redo.pop()
+    } else {
+        history.undo.pop()
+    }) else {
+        info!("Nothing to {}", if redo { "redo" } else { "undo" });
+        return;
+    };
+
+    // Reverting the action gives the action that reverts it again.
+    let inverse = match action {
+        EditAction::SpawnedObstacle(entity) => {
+            let Ok(source) = obstacle_query.get(entity) else {
+                warn!("Can't revert the edit, the obstacle no longer exists");
+                return;
+            };
+            commands.entity(entity).despawn();
+            EditAction::RemovedObstacle(source.0.clone())
+        }
+        EditAction::RemovedObstacle(data) => {
+            let Some(entity) =
+                spawn_obstacle(&mut commands, &mut meshes, &mut materials, &palette, &data)
+            else {
+                return;
+            };
+            EditAction::SpawnedObstacle(entity)
+        }
+    };
+
+    if redo {
+        history.push_undo(inverse);
+    } else {
+        history.redo.push(inverse);
+    }
+}
+
+fn clear_edit_history(mut history: ResMut<EditHistory>) {
+    history.clear();
+}
@@ -0,0 +1,162 @@
+//! Gamepad controls, for playing from the couch or with a controller on web. With the default
+//! bindings:
+//!
+//! - The left stick moves a reticle over the play area.
+//! - A selects the Moodel nearest to the reticle.
+//! - B commands the selection to move to the reticle.
+//! - The triggers cycle the selection through the Moodels, moving the reticle onto each.
+//!
+//! Selections and commands become [`PlayerIntent`]s, the same as mouse and touch input.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        ai::AiEntity,
+        movement::PlayArea,
+        player_input::{PlayerIntent, Selectable, Selected},
+    },
+    keybindings::{Action, ActionInput},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GamepadReticle>();
+    app.init_resource::<GamepadReticle>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), spawn_reticle_sprite);
+    app.add_systems(
+        Update,
+        (
+            (move_gamepad_reticle, handle_gamepad_buttons)
+                .chain()
+                .in_set(AppSystems::RecordInput),
+            update_reticle_sprite.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// How fast the reticle moves at full stick deflection, in world units per second.
+const RETICLE_SPEED: f32 = 600.0;
+/// Stick deflections smaller than this are ignored, so a drifting stick doesn't creep.
+const STICK_DEADZONE: f32 = 0.15;
+/// Drawn above the Moodels.
+const RETICLE_Z: f32 = 5.0;
+
+/// The gamepad's reticle. It appears the first time a gamepad is used.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct GamepadReticle {
+    pub active: bool,
+    /// Where the reticle is, in world coordinates.
+    pub position: Vec2,
+}
+
+/// The sprite drawn at the [`GamepadReticle`].
+#[derive(Component)]
+struct ReticleSprite;
+
+fn spawn_reticle_sprite(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Gamepad Reticle"),
+        ReticleSprite,
+        Sprite::from_color(Color::srgba(1.0, 1.0, 1.0, 0.8), Vec2::splat(18.0)),
+        Transform::from_xyz(0.0, 0.0, RETICLE_Z)
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        Visibility::Hidden,
+        StateScoped(Screen::Gameplay),
+    ));
+}
+
+fn move_gamepad_reticle(
+    time: Res<Time>,
+    play_area: Res<PlayArea>,
+    gamepads: Query<&Gamepad>,
+    mut reticle: ResMut<GamepadReticle>,
+) {
+    for gamepad in &gamepads {
+        let stick = gamepad.left_stick();
+        if stick.length() < STICK_DEADZONE {
+            continue;
+        }
+        reticle.active = true;
+        reticle.position = play_area.clamp(
+            reticle.position + stick.clamp_length_max(1.0) * RETICLE_SPEED * time.delta_secs(),
+        );
+    }
+}
+
+fn handle_gamepad_buttons(
+    gamepads: Query<&Gamepad>,
+    input: ActionInput,
+    mut reticle: ResMut<GamepadReticle>,
+    moodel_query: Query<(Entity, &Transform), (With<AiEntity>, With<Selectable>)>,
+    selected_query: Query<Entity, With<Selected>>,
+    mut ev_intent: EventWriter<PlayerIntent>,
+) {
+    if gamepads
+        .iter()
+        .any(|gamepad| gamepad.get_just_pressed().next().is_some())
+    {
+        reticle.active = true;
+    }
+
+    let step = match (
+        input.just_pressed(Action::GamepadNext),
+        input.just_pressed(Action::GamepadPrevious),
+    ) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    };
+    if let Some(forwards) = step {
+        // Sort so the cycling order is stable from frame to frame.
+        let mut moodels: Vec<(Entity, Vec2)> = moodel_query
+            .iter()
+            .map(|(entity, transform)| (entity, transform.translation.truncate()))
+            .collect();
+        moodels.sort_by_key(|(entity, _)| *entity);
+        let current = selected_query
+            .iter()
+            .next()
+            .and_then(|selected| moodels.iter().position(|(entity, _)| *entity == selected));
+        let next = match (current, forwards) {
+            _ if moodels.is_empty() => None,
+            (Some(index), true) => Some((index + 1) % moodels.len()),
+            (Some(index), false) => Some((index + moodels.len() - 1) % moodels.len()),
+            (None, true) => Some(0),
+            (None, false) => Some(moodels.len() - 1),
+        };
+        if let Some(next) = next {
+            reticle.position = moodels[next].1;
+            ev_intent.write(PlayerIntent::Select(reticle.position));
+        }
+    }
+
+    if input.just_pressed(Action::GamepadSelect) {
+        ev_intent.write(PlayerIntent::Select(reticle.position));
+    }
+    if input.just_pressed(Action::GamepadCommand) {
+        ev_intent.write(PlayerIntent::Command {
+            position: reticle.position,
+            queued: false,
+        });
+    }
+}
+
+fn update_reticle_sprite(
+    reticle: Res<GamepadReticle>,
+    mut sprite_query: Query<(&mut Transform, &mut Visibility), With<ReticleSprite>>,
+) {
+    for (mut transform, mut visibility) in &mut sprite_query {
+        transform.translation = reticle.position.extend(RETICLE_Z);
+        *visibility = if reticle.active {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,242 @@
+//! Keyboard-only controls, so the core loop is playable without a mouse. With the default
+//! bindings:
+//!
+//! - Tab (Shift+Tab backwards) moves a focus cursor between selectable Moodels and selects
+//!   the focused one.
+//! - Enter selects the focused Moodel again, e.g. after a command cleared the selection.
+//! - The arrow keys move a target reticle.
+//! - Space commands the selected Moodel to move to the reticle.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        ai::AiEntity,
+        movement::PlayArea,
+        player_input::{CommandMoodelEvent, Selectable, Selected, command_group},
+        victory::VictorySequence,
+    },
+    keybindings::{Action, action_just_pressed, keyboard_available},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<KeyboardControlMode>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), reset_keyboard_control);
+    app.add_systems(
+        Update,
+        (
+            (
+                cycle_focus.run_if(action_just_pressed(Action::FocusNext)),
+                select_focused.run_if(
+                    action_just_pressed(Action::SelectFocused)
+                        .or(action_just_pressed(Action::FocusNext)),
+                ),
+                move_reticle.run_if(keyboard_available),
+                // Space also skips the victory celebration, which shouldn't send a command.
+                command_to_reticle.run_if(
+                    action_just_pressed(Action::CommandToReticle)
+                        .and(in_state(VictorySequence::Inactive)),
+                ),
+            )
+                .chain()
+                .in_set(AppSystems::RecordInput),
+            draw_keyboard_cursors
+                .run_if(keyboard_control_active)
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(PausableSystems),
+    );
+}
+
+/// How fast the target reticle moves, in world units per second.
+const RETICLE_SPEED: f32 = 400.0;
+
+/// State of the keyboard control scheme. It becomes active the first time the focus moves.
+#[derive(Resource, Default)]
+pub struct KeyboardControlMode {
+    pub active: bool,
+    /// The Moodel under the focus cursor.
+    pub focused: Option<Entity>,
+    /// Where the next move command will send the selected Moodel.
+    pub reticle: Vec2,
+}
+
+fn keyboard_control_active(mode: Res<KeyboardControlMode>) -> bool {
+    mode.active
+}
+
+fn reset_keyboard_control(mut mode: ResMut<KeyboardControlMode>) {
+    mode.focused = None;
+}
+
+fn cycle_focus(
+    input: Res<ButtonInput<KeyCode>>,
+    mut mode: ResMut<KeyboardControlMode>,
+    moodel_query: Query<Entity, (With<AiEntity>, With<Selectable>)>,
+    selected_query: Query<Entity, With<Selected>>,
+) {
+    mode.active = true;
+
+    // Sort so the cycling order is stable from frame to frame.
+    let mut moodels: Vec<Entity> = moodel_query.iter().collect();
+    if moodels.is_empty() {
+        mode.focused = None;
+        return;
+    }
+    moodels.sort();
+
+    let backwards = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    // Continue from a Moodel selected with the mouse if nothing has been focused yet.
+    let current = mode
+        .focused
+        .or_else(|| selected_query.iter().next())
+        .and_then(|focused| moodels.iter().position(|entity| *entity == focused));
+    let next = match (current, backwards) {
+        (Some(index), false) => (index + 1) % moodels.len(),
+        (Some(index), true) => (index + moodels.len() - 1) % moodels.len(),
+        (None, false) => 0,
+        (None, true) => moodels.len() - 1,
+    };
+    mode.focused = Some(moodels[next]);
+}
+
+fn select_focused(
+    mut commands: Commands,
+    mut mode: ResMut<KeyboardControlMode>,
+    moodel_query: Query<&Transform, With<AiEntity>>,
+    selected_query: Query<Entity, With<Selected>>,
+) {
+    let Some(focused) = mode.focused else {
+        return;
+    };
+    let Ok(transform) = moodel_query.get(focused) else {
+        mode.focused = None;
+        return;
+    };
+
+    for entity in &selected_query {
+        if entity != focused {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    commands.entity(focused).insert(Selected);
+    mode.reticle = transform.translation.truncate();
+}
+
+fn move_reticle(
+    time: Res<Time>,
+    input: Res<ButtonInput<KeyCode>>,
+    play_area: Res<PlayArea>,
+    mut mode: ResMut<KeyboardControlMode>,
+) {
+    if !mode.active {
+        return;
+    }
+
+    let mut direction = Vec2::ZERO;
+    if input.pressed(KeyCode::ArrowUp) {
+        direction.y += 1.0;
+    }
+    if input.pressed(KeyCode::ArrowDown) {
+        direction.y -= 1.0;
+    }
+    if input.pressed(KeyCode::ArrowLeft) {
+        direction.x -= 1.0;
+    }
+    if input.pressed(KeyCode::ArrowRight) {
+        direction.x += 1.0;
+    }
+    if direction == Vec2::ZERO {
+        return;
+    }
+
+    mode.reticle =
+        play_area.clamp(mode.reticle + direction.normalize() * RETICLE_SPEED * time.delta_secs());
+}
+
+fn command_to_reticle(
+    mut commands: Commands,
+    mode: Res<KeyboardControlMode>,
+    selected_query: Query<Entity, With<Selected>>,
+    mut ev_command: EventWriter<CommandMoodelEvent>,
+) {
+    if !mode.active {
+        return;
+    }
+    command_group(
+        &mut commands,
+        &selected_query,
+        mode.reticle,
+        false,
+        &mut ev_command,
+    );
+}
+
+fn draw_keyboard_cursors(
+    mut gizmos: Gizmos,
+    mode: Res<KeyboardControlMode>,
+    moodel_query: Query<&GlobalTransform, With<AiEntity>>,
+    selected_query: Query<(), With<Selected>>,
+) {
+    if let Some(transform) = mode
+        .focused
+        .and_then(|focused| moodel_query.get(focused).ok())
+    {
+        gizmos.circle_2d(transform.translation().truncate(), 70.0, Color::WHITE);
+    }
+
+    // The reticle only matters while something is selected to receive the command.
+    if !selected_query.is_empty() {
+        let color = Color::srgb(1.0, 0.9, 0.2);
+        gizmos.circle_2d(mode.reticle, 20.0, color);
+        gizmos.line_2d(
+            mode.reticle - Vec2::X * 30.0,
+            mode.reticle + Vec2::X * 30.0,
+            color,
+        );
+        gizmos.line_2d(
+            mode.reticle - Vec2::Y * 30.0,
+            mode.reticle + Vec2::Y * 30.0,
+            color,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn tab_focus_wraps_around() {
+        let mut app = App::new();
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<KeyboardControlMode>();
+        let mut moodels: Vec<Entity> = (0..3)
+            .map(|_| app.world_mut().spawn((AiEntity, Selectable)).id())
+            .collect();
+        moodels.sort();
+        let mut cycle = || {
+            app.world_mut().run_system_once(cycle_focus).unwrap();
+            app.world().resource::<KeyboardControlMode>().focused
+        };
+
+        let forwards: Vec<_> = (0..4).map(|_| cycle()).collect();
+        let expected = [moodels[0], moodels[1], moodels[2], moodels[0]].map(Some);
+        assert_eq!(forwards, expected);
+
+        // Shift+Tab from the first Moodel wraps back to the last.
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::ShiftLeft);
+        app.world_mut().run_system_once(cycle_focus).unwrap();
+        assert_eq!(
+            app.world().resource::<KeyboardControlMode>().focused,
+            Some(moodels[2])
+        );
+    }
+}
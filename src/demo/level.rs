@@ -2,22 +2,45 @@
 //! Includes enhanced visuals, real-time feedback, and robust event-driven zone tracking.
 
 use avian2d::prelude::*;
+use bevy::asset::LoadState;
 use bevy::ecs::system::RunSystemOnce;
+#[cfg(feature = "dev")]
 use bevy::prelude::*;
+use bevy::render::{
+    mesh::{Indices, PrimitiveTopology},
+    render_asset::RenderAssetUsages,
+};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use super::level_library;
+use super::{
+    level_library,
+    level_share::SharedLevel,
+    level_validation::{check_level, report_level_errors},
+};
 use crate::{
-    AppSystems, COLLISION_LAYER_MOODEL, COLLISION_LAYER_OBSTACLE, PausableSystems,
+    AppSystems, COLLISION_LAYER_MOODEL, COLLISION_LAYER_OBSTACLE, COLLISION_LAYER_TRIGGER,
+    PausableSystems,
     asset_tracking::LoadResource,
-    audio::{PlaySound, music},
+    audio::{AudioMix, PlaySound, music},
     demo::{
-        ai::ObstacleCollider,
-        mood::{Mood, MoodAssets, select_mood, spawn_moodel_bundle},
-        movement::PlayArea,
+        ai::{ObstacleCollider, ObstacleHull, ObstacleRadius},
+        campaign::Campaign,
+        mood::{
+            ColorScheme, Mood, MoodAssets, MoodLock, MoodWeights, SetMood, select_mood,
+            spawn_moodel_bundle,
+        },
+        movement::{PlayArea, PlayAreaShape},
+        player::erase_obstacle,
         player_input::handle_background_click,
+        rng::GameRng,
+        stats::GameStats,
     },
+    keybindings::{Action, action_just_pressed},
     screens::Screen,
 };
 use bevy::picking::{
@@ -28,37 +51,108 @@ use bevy::picking::{
 pub(super) fn plugin(app: &mut App) {
     // Register components, resources, and events
     app.register_type::<Obstacle>();
+    app.register_type::<PatrolMovement>();
     app.register_type::<GoalZone>();
+    app.register_type::<HazardZone>();
     app.register_type::<PulseAnimation>();
     app.register_type::<AnimateScale>();
     app.register_type::<LevelAssets>();
+    app.register_type::<LevelPalette>();
     app.add_event::<SpawnLevel>();
+    app.add_event::<SaveLevel>();
+    app.add_event::<ResetLevel>();
+    app.add_event::<SpawnMoodel>();
+    app.add_event::<DespawnMoodel>();
+    app.add_event::<LevelLoadFailed>();
 
     // Resources to manage level state
     app.init_resource::<LevelHandle>();
+    app.register_type::<LevelLoadTimeout>();
+    app.init_resource::<LevelLoadTimeout>();
     app.init_resource::<ActiveLevel>();
+    app.init_resource::<ActiveLevelSource>();
     app.init_resource::<FontHandle>();
+    app.init_resource::<LevelOutcome>();
+    app.register_type::<LevelClock>();
+    app.init_resource::<LevelClock>();
+    app.init_resource::<LevelPalette>();
+    app.init_resource::<SavedClearColor>();
+    app.register_type::<SpawnSchedule>();
+    app.init_resource::<SpawnSchedule>();
     app.load_resource::<LevelAssets>();
 
     // Core systems for level lifecycle
     app.add_systems(Update, handle_spawn_requests);
+    app.add_systems(
+        Update,
+        save_level
+            .run_if(on_event::<SaveLevel>)
+            .run_if(in_state(Screen::Gameplay)),
+    );
     app.add_systems(
         Update,
         process_loaded_level.run_if(resource_exists::<LevelLoadingState>),
     );
+    app.add_systems(
+        Update,
+        (
+            request_level_reset
+                .run_if(action_just_pressed(Action::Restart))
+                .in_set(AppSystems::RecordInput),
+            reset_level
+                .run_if(on_event::<ResetLevel>)
+                .in_set(AppSystems::Update),
+        )
+            .run_if(in_state(Screen::Gameplay)),
+    );
+    #[cfg(feature = "dev")]
+    app.add_systems(
+        Update,
+        skip_level
+            .run_if(in_state(Screen::Gameplay).and(action_just_pressed(Action::SkipLevel)))
+            .in_set(AppSystems::RecordInput),
+    );
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level_entities);
-    app.add_systems(OnExit(Screen::Gameplay), teardown_level);
+    app.add_systems(OnEnter(Screen::Title), reset_active_level_source);
+    app.add_systems(
+        OnExit(Screen::Gameplay),
+        (
+            teardown_level,
+            restore_default_theme,
+            restore_default_audio_mix,
+        ),
+    );
+
+    // Swap in Bevy's default font if the custom one is missing
+    app.add_systems(Update, fallback_missing_font);
+    // Same for a level's own music
+    app.add_systems(
+        Update,
+        fallback_missing_level_music.run_if(resource_exists::<LevelAssets>),
+    );
 
     // Gameplay logic systems
     app.add_systems(
         Update,
         (
             hot_reload_level,
+            tick_level_clock
+                .run_if(resource_equals(LevelOutcome::InProgress))
+                .in_set(AppSystems::TickTimers)
+                .in_set(PausableSystems),
             (
                 handle_zone_collisions,
                 update_zone_state,
                 update_zone_visuals,
+                handle_hazard_collisions,
+                apply_hazard_effects,
                 check_win_condition,
+                check_lose_condition,
+                announce_level_outcome.run_if(resource_changed::<LevelOutcome>),
+                move_patrolling_obstacles,
+                run_spawn_schedule,
+                handle_spawn_moodel_events,
+                handle_despawn_moodel_events,
                 // Animation systems
                 animate_scale_pop,
                 pulse_zone_animation,
@@ -69,6 +163,13 @@ pub(super) fn plugin(app: &mut App) {
         )
             .run_if(in_state(Screen::Gameplay)),
     );
+    // Not paused, so color scheme changes show behind the settings menu
+    app.add_systems(
+        Update,
+        tint_zones
+            .in_set(AppSystems::Update)
+            .run_if(in_state(Screen::Gameplay)),
+    );
 }
 
 // --- Resources, Components & Events ---
@@ -92,21 +193,188 @@ impl FromWorld for LevelAssets {
 #[derive(Resource, Default)]
 pub struct FontHandle(pub Handle<Font>);
 
+/// Marks level music that was overridden by the level, until it is known to have loaded.
+#[derive(Component)]
+struct CustomLevelMusic;
+
+/// If a level's own music failed to load, fall back to the default track
+fn fallback_missing_level_music(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    level_assets: Res<LevelAssets>,
+    music_query: Query<(Entity, &AudioPlayer), With<CustomLevelMusic>>,
+) {
+    for (entity, player) in &music_query {
+        match asset_server.get_load_state(&player.0) {
+            Some(LoadState::Failed(error)) => {
+                error!("Failed to load level music, using the default track instead: {error}");
+                commands
+                    .entity(entity)
+                    .insert(AudioPlayer::new(level_assets.music.clone()))
+                    .remove::<CustomLevelMusic>();
+            }
+            Some(LoadState::Loaded) => {
+                commands.entity(entity).remove::<CustomLevelMusic>();
+            }
+            _ => {}
+        }
+    }
+}
+
+/// If the custom font failed to load, fall back to Bevy's default font for all text using it
+fn fallback_missing_font(
+    asset_server: Res<AssetServer>,
+    mut font_handle: ResMut<FontHandle>,
+    mut text_query: Query<&mut TextFont>,
+) {
+    let Some(LoadState::Failed(error)) = asset_server.get_load_state(&font_handle.0) else {
+        return;
+    };
+    // Only happens once, since the default font handle is never in a failed state
+    error!("Failed to load custom font, using the default font instead: {error}");
+
+    let failed_font = font_handle.0.id();
+    font_handle.0 = Handle::default();
+    for mut text_font in &mut text_query {
+        if text_font.font.id() == failed_font {
+            text_font.font = Handle::default();
+        }
+    }
+}
+
 /// Resource to track that we're loading a level
 #[derive(Resource)]
-struct LevelLoadingState;
+pub struct LevelLoadingState {
+    /// When loading started, in real time.
+    started: Duration,
+}
 
-#[derive(Event, Clone)]
+/// How long to wait for a level file before giving up on it, for files that never finish
+/// loading without reporting an error.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct LevelLoadTimeout(pub Duration);
+
+impl Default for LevelLoadTimeout {
+    fn default() -> Self {
+        Self(Duration::from_secs(30))
+    }
+}
+
+/// Sent when a level file couldn't be loaded, with the reason.
+#[derive(Event, Debug, Clone)]
+pub struct LevelLoadFailed(pub String);
+
+#[derive(Event, Clone, Debug, Serialize, Deserialize)]
 pub enum SpawnLevel {
     FromFile(String),
     Programmatic(String),
+    /// A level's JSON, in the same format as a level file, e.g. pasted in or shared by link.
+    FromJson(String),
+}
+
+/// Writes the live level, as it currently stands, to a `.level.json` file at the given path.
+#[derive(Event, Clone)]
+pub struct SaveLevel(pub String);
+
+/// Restarts the current level from the already-parsed [`ActiveLevel`], without reloading it.
+#[derive(Event, Clone)]
+pub struct ResetLevel;
+
+/// Adds a Moodel to the running level, wired up like the level's own Moodels.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SpawnMoodel {
+    pub mood: Mood,
+    pub position: Vec2,
+    /// Defaults to [`DEFAULT_MOODEL_MAX_SPEED`].
+    pub max_speed: Option<f32>,
 }
 
+/// Removes a Moodel from the running level, and from any zone it was in.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DespawnMoodel(pub Entity);
+
+/// Top speed of Moodels that don't ask for a different one.
+pub const DEFAULT_MOODEL_MAX_SPEED: f32 = 350.0;
+
+/// The level file being loaded or played, if the level came from a file.
 #[derive(Resource, Default)]
-struct LevelHandle(Handle<Level>);
+pub struct LevelHandle(pub Handle<Level>);
+
+/// The parsed data of the level being played, once it has loaded.
+#[derive(Resource, Default)]
+pub struct ActiveLevel(pub Option<Level>);
+
+/// Where the level being played came from, so it can be retried.
+#[derive(Resource, Debug, Clone, Default)]
+pub enum ActiveLevelSource {
+    /// The campaign's current level.
+    #[default]
+    Campaign,
+    /// Any other level, e.g. one loaded from the console, replayed by sending this again.
+    Other(SpawnLevel),
+}
+
+impl ActiveLevelSource {
+    pub fn is_campaign(&self) -> bool {
+        matches!(self, ActiveLevelSource::Campaign)
+    }
+}
+
+/// How the current level has ended, if it has.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LevelOutcome {
+    #[default]
+    InProgress,
+    Won,
+    Lost,
+}
+
+/// Seconds the current level has been played for.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct LevelClock {
+    pub elapsed: f32,
+}
 
+/// Colors used to draw the current level, resolved from its optional [`LevelTheme`].
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource)]
+pub struct LevelPalette {
+    /// Color of goal zone text and other level highlights.
+    pub accent: Color,
+    /// Fill color of walls.
+    pub wall: Color,
+    /// Border color of walls.
+    pub wall_border: Color,
+    /// Fill alpha of goal zones that are not yet satisfied.
+    pub zone_alpha: f32,
+}
+
+impl Default for LevelPalette {
+    fn default() -> Self {
+        Self {
+            accent: Color::WHITE,
+            wall: Color::srgb(0.4, 0.4, 0.5),
+            wall_border: Color::srgb(0.25, 0.25, 0.3),
+            zone_alpha: 0.2,
+        }
+    }
+}
+
+/// The timed spawns of the current level that haven't happened yet.
+#[derive(Resource, Reflect, Default)]
+#[reflect(Resource)]
+pub struct SpawnSchedule {
+    /// Seconds since the level started.
+    pub elapsed: f32,
+    /// Waiting spawns, soonest first.
+    pub pending: Vec<TimedSpawn>,
+}
+
+/// The app-wide clear color, saved while a level theme overrides it.
 #[derive(Resource, Default)]
-struct ActiveLevel(Option<Level>);
+struct SavedClearColor(Option<Color>);
 
 #[derive(Component)]
 pub struct LevelEntity;
@@ -115,20 +383,125 @@ pub struct LevelEntity;
 #[reflect(Component)]
 pub struct Obstacle;
 
+/// The level data an obstacle was spawned from, so the level can be saved back out.
+#[derive(Component)]
+pub struct ObstacleSource(pub ObstacleData);
+
+/// Moves a kinematic obstacle along its patrol path.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct PatrolMovement {
+    /// The start position followed by the waypoints.
+    pub path: Vec<Vec2>,
+    pub speed: f32,
+    pub loop_mode: LoopMode,
+    /// Index into `path` of the point currently being moved towards.
+    pub target: usize,
+    /// Whether a ping-pong patrol is on its way back to the start.
+    pub reversing: bool,
+}
+
+impl PatrolMovement {
+    fn new(start: Vec2, patrol: &Patrol) -> Self {
+        Self {
+            path: std::iter::once(start)
+                .chain(patrol.waypoints.iter().copied())
+                .collect(),
+            speed: patrol.speed,
+            loop_mode: patrol.loop_mode,
+            target: 1,
+            reversing: false,
+        }
+    }
+
+    /// Moves on to the next point of the path.
+    fn advance(&mut self) {
+        let last = self.path.len() - 1;
+        match self.loop_mode {
+            LoopMode::Loop => self.target = (self.target + 1) % self.path.len(),
+            LoopMode::PingPong => {
+                if self.target == last {
+                    self.reversing = true;
+                } else if self.target == 0 {
+                    self.reversing = false;
+                }
+                self.target = if self.reversing {
+                    self.target - 1
+                } else {
+                    self.target + 1
+                };
+            }
+        }
+    }
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct GoalZone {
-    pub target_mood: Mood,
-    pub required_count: u32,
-    pub current_count: u32,
+    /// How many Moodels of each mood must be inside at once.
+    pub requirements: Vec<(Mood, u32)>,
+    pub size: Vec2,
+    pub hold_duration: Option<f32>,
+    /// How long the requirements have been held without a break, in seconds.
+    pub hold_timer: f32,
+    /// How many Moodels of each required mood are inside, in the same order as `requirements`.
+    pub current_counts: Vec<u32>,
+    /// If set, the listed moods must be kept out of the zone instead.
+    pub forbidden: bool,
     pub is_satisfied: bool,
     pub entities_inside: HashSet<Entity>,
 }
 
+impl GoalZone {
+    /// The zone's fill color: the required mood's color, or neutral white for a mixture.
+    pub fn base_color(&self, scheme: ColorScheme) -> Color {
+        match self.requirements.as_slice() {
+            [(mood, _)] => mood.color_for_scheme(scheme),
+            _ => Color::WHITE,
+        }
+    }
+
+    /// Whether this mood is one the zone is asking for.
+    pub fn wants(&self, mood: Mood) -> bool {
+        self.requirements
+            .iter()
+            .any(|(required_mood, _)| *required_mood == mood)
+    }
+
+    /// Whether every required mood currently has enough Moodels inside, or for a forbidden
+    /// zone, whether none of the listed moods are inside.
+    pub fn requirements_met(&self) -> bool {
+        if self.forbidden {
+            return self.current_counts.iter().all(|count| *count == 0);
+        }
+        self.requirements
+            .iter()
+            .zip(&self.current_counts)
+            .all(|((_, required), current)| current >= required)
+    }
+
+    /// Seconds left before the held count satisfies the zone. Zero for zones without a hold.
+    pub fn hold_remaining(&self) -> f32 {
+        self.hold_duration
+            .map_or(0.0, |duration| (duration - self.hold_timer).max(0.0))
+    }
+}
+
 #[derive(Component, Reflect)]
 #[reflect(Component)]
 pub struct GoalZoneText;
 
+/// A zone that changes the mood of Moodels that stay inside it too long.
+#[derive(Component, Reflect, Default)]
+#[reflect(Component)]
+pub struct HazardZone {
+    pub mood_effect: Mood,
+    pub dwell_time: f32,
+    pub size: Vec2,
+    /// How long each Moodel inside has been there, in seconds.
+    pub entities_inside: HashMap<Entity, f32>,
+}
+
 #[derive(Component, Reflect, Default)]
 #[reflect(Component)]
 pub struct PulseAnimation {
@@ -147,16 +520,114 @@ pub struct AnimateScale {
 #[derive(Asset, TypePath, Debug, Clone, Serialize, Deserialize)]
 pub struct Level {
     pub name: String,
+    #[serde(default)]
+    pub meta: LevelMeta,
     pub play_area: Vec2,
+    /// Round arenas set a circle here; otherwise the whole `play_area` rectangle is used.
+    #[serde(default)]
+    pub play_area_shape: PlayAreaShape,
     pub moodels: Vec<MoodelData>,
+    /// Extra Moodels scattered at random across the play area, on top of `moodels`.
+    #[serde(default)]
+    pub random_moodels: u32,
+    /// How likely each mood is for the random Moodels. Every mood is equally likely if unset.
+    #[serde(default)]
+    pub spawn_weights: Option<MoodWeights>,
     pub obstacles: Vec<ObstacleData>,
     pub goal_zones: Vec<GoalZoneData>,
+    #[serde(default)]
+    pub hazard_zones: Vec<HazardZoneData>,
+    /// Ways to fail the level. The level is lost as soon as any of them is met.
+    #[serde(default)]
+    pub lose_conditions: Vec<LoseCondition>,
+    /// Moodels that join the level after a delay, for escalating difficulty.
+    #[serde(default)]
+    pub spawns: Vec<TimedSpawn>,
+    #[serde(default)]
+    pub theme: Option<LevelTheme>,
+    /// Optional music/SFX balance for this level, multiplied with the player's volume.
+    #[serde(default)]
+    pub audio_mix: Option<AudioMix>,
+    /// Optional background music path, played instead of the default track.
+    #[serde(default)]
+    pub music: Option<String>,
+}
+
+/// Descriptive information about a level, shown in menus.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelMeta {
+    pub author: String,
+    pub difficulty: u8,
+    /// A good completion time to aim for, in seconds.
+    pub par_time_secs: Option<f32>,
+    pub description: String,
+}
+
+/// Optional per-level color overrides. Colors are hex strings such as `"#1d2b53"`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LevelTheme {
+    pub clear_color: Option<String>,
+    pub accent: Option<String>,
+    pub wall_color: Option<String>,
+    pub zone_alpha: Option<f32>,
+}
+
+impl LevelTheme {
+    /// Resolves the overrides on top of the default level palette.
+    fn palette(&self) -> LevelPalette {
+        let default = LevelPalette::default();
+        let wall = parse_theme_color(&self.wall_color).unwrap_or(default.wall);
+        LevelPalette {
+            accent: parse_theme_color(&self.accent).unwrap_or(default.accent),
+            wall,
+            wall_border: if self.wall_color.is_some() {
+                wall.darker(0.15)
+            } else {
+                default.wall_border
+            },
+            zone_alpha: self.zone_alpha.unwrap_or(default.zone_alpha),
+        }
+    }
+}
+
+fn parse_theme_color(hex: &Option<String>) -> Option<Color> {
+    let hex = hex.as_ref()?;
+    match Srgba::hex(hex) {
+        Ok(color) => Some(color.into()),
+        Err(error) => {
+            warn!("Invalid level theme color {:?}: {}", hex, error);
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MoodelData {
     pub mood: Mood,
     pub position: Vec2,
+    /// Whether the Moodel's mood is locked for the whole level.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// A way to fail a level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LoseCondition {
+    /// The level isn't won within this many seconds.
+    TimeLimit { secs: f32 },
+    /// Every Moodel ends up in this mood, e.g. everyone turns Rage.
+    AllMoodelsMood { mood: Mood },
+}
+
+/// A Moodel that appears `delay_secs` after the level starts.
+#[derive(Debug, Clone, Serialize, Deserialize, Reflect)]
+pub struct TimedSpawn {
+    pub mood: Mood,
+    pub position: Vec2,
+    pub delay_secs: f32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -164,37 +635,306 @@ pub struct ObstacleData {
     pub position: Vec2,
     #[serde(flatten)]
     pub kind: ObstacleKind,
+    /// Makes the obstacle move along a path, like a sliding door or a sweeping hazard.
+    #[serde(default)]
+    pub patrol: Option<Patrol>,
+}
+
+/// A path for a moving obstacle. The obstacle starts at its own position, then visits
+/// each waypoint (in world space) in order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Patrol {
+    pub waypoints: Vec<Vec2>,
+    /// Movement speed in world units per second.
+    #[serde(default = "default_patrol_speed")]
+    pub speed: f32,
+    #[serde(default)]
+    pub loop_mode: LoopMode,
+}
+
+fn default_patrol_speed() -> f32 {
+    100.0
+}
+
+/// What a patrolling obstacle does after its last waypoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Reflect)]
+pub enum LoopMode {
+    /// Heads straight back to the start and goes round again.
+    #[default]
+    Loop,
+    /// Retraces the path backwards, then forwards again.
+    PingPong,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum ObstacleKind {
-    Wall { size: Vec2 },
+    Wall {
+        size: Vec2,
+    },
+    /// A round pillar.
+    Circle {
+        radius: f32,
+    },
+    /// An arbitrary shape, relative to the obstacle position. Concave outlines are
+    /// filled in to their convex hull.
+    Polygon {
+        vertices: Vec<Vec2>,
+    },
+}
+
+impl ObstacleData {
+    /// Whether a circle at `center` with `radius` overlaps this obstacle.
+    pub fn overlaps_circle(&self, center: Vec2, radius: f32) -> bool {
+        match &self.kind {
+            ObstacleKind::Wall { size } => {
+                let half_size = *size / 2.0;
+                let closest = center.clamp(self.position - half_size, self.position + half_size);
+                closest.distance_squared(center) < radius * radius
+            }
+            ObstacleKind::Circle {
+                radius: obstacle_radius,
+            } => self.position.distance(center) < obstacle_radius + radius,
+            ObstacleKind::Polygon { vertices } => {
+                let hull = convex_hull(vertices);
+                if hull.len() < 3 {
+                    return false;
+                }
+                let closest = closest_point_on_convex_polygon(&hull, center - self.position);
+                closest.distance_squared(center - self.position) < radius * radius
+            }
+        }
+    }
+}
+
+/// The convex hull of a set of points, in counter-clockwise order.
+/// Returns fewer than three points if the input is degenerate.
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+
+    // Andrew's monotone chain: build the lower hull, then the upper hull.
+    let mut hull: Vec<Vec2> = Vec::with_capacity(sorted.len() * 2);
+    for pass in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2
+                && (hull[hull.len() - 1] - hull[hull.len() - 2])
+                    .perp_dot(point - hull[hull.len() - 2])
+                    <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        // The last point of each half is the first point of the other.
+        hull.pop();
+    }
+    hull
+}
+
+/// The closest point on or inside a counter-clockwise convex polygon to `point`.
+/// Points inside the polygon are their own closest point.
+pub fn closest_point_on_convex_polygon(polygon: &[Vec2], point: Vec2) -> Vec2 {
+    let edges = polygon
+        .iter()
+        .zip(polygon.iter().cycle().skip(1))
+        .map(|(start, end)| (*start, *end));
+    let inside = edges
+        .clone()
+        .all(|(start, end)| (end - start).perp_dot(point - start) >= 0.0);
+    if inside {
+        return point;
+    }
+    edges
+        .map(|(start, end)| {
+            let edge = end - start;
+            let t = ((point - start).dot(edge) / edge.length_squared()).clamp(0.0, 1.0);
+            start + edge * t
+        })
+        .min_by(|a, b| {
+            a.distance_squared(point)
+                .total_cmp(&b.distance_squared(point))
+        })
+        .unwrap_or(point)
+}
+
+/// Builds a flat mesh for a convex polygon as a triangle fan.
+fn convex_polygon_mesh(polygon: &[Vec2]) -> Mesh {
+    let positions: Vec<[f32; 3]> = polygon.iter().map(|v| [v.x, v.y, 0.0]).collect();
+    let normals = vec![[0.0, 0.0, 1.0]; polygon.len()];
+    let (min, max) = polygon
+        .iter()
+        .fold((Vec2::MAX, Vec2::MIN), |(min, max), v| {
+            (min.min(*v), max.max(*v))
+        });
+    let uvs: Vec<[f32; 2]> = polygon
+        .iter()
+        .map(|v| {
+            let uv = (*v - min) / (max - min).max(Vec2::splat(f32::EPSILON));
+            [uv.x, 1.0 - uv.y]
+        })
+        .collect();
+    let indices: Vec<u32> = (1..polygon.len() as u32 - 1)
+        .flat_map(|i| [0, i, i + 1])
+        .collect();
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+    .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Moves every vertex of a convex polygon towards its centroid by `distance`.
+fn shrink_convex_polygon(polygon: &[Vec2], distance: f32) -> Vec<Vec2> {
+    let centroid = polygon.iter().sum::<Vec2>() / polygon.len() as f32;
+    polygon
+        .iter()
+        .map(|v| {
+            let offset = *v - centroid;
+            centroid + offset.normalize_or_zero() * (offset.length() - distance).max(0.0)
+        })
+        .collect()
+}
+
+/// Radius used when checking Moodel spawn positions against obstacles, matching the Moodel collider.
+const MOODEL_SPAWN_RADIUS: f32 = 50.0;
+/// Random Moodels are scattered over this fraction of the play area, keeping them off the edges
+const RANDOM_MOODEL_SPREAD: f32 = 0.9;
+/// Distance between the rings searched when relocating an overlapping spawn.
+const SPAWN_SEARCH_STEP: f32 = 10.0;
+/// Number of candidate positions tried on each search ring.
+const SPAWN_SEARCH_DIRECTIONS: usize = 16;
+
+/// Finds the closest position to `position` where a Moodel doesn't overlap any obstacle and stays
+/// inside the play area. The search is deterministic, walking outwards in rings.
+fn find_free_spawn_position(
+    position: Vec2,
+    obstacles: &[ObstacleData],
+    play_area_size: Vec2,
+) -> Option<Vec2> {
+    let half_area = play_area_size / 2.0 - Vec2::splat(MOODEL_SPAWN_RADIUS);
+    let is_free = |candidate: Vec2| {
+        candidate.abs().cmple(half_area).all()
+            && !obstacles
+                .iter()
+                .any(|obstacle| obstacle.overlaps_circle(candidate, MOODEL_SPAWN_RADIUS))
+    };
+    if is_free(position) {
+        return Some(position);
+    }
+
+    let max_distance = play_area_size.max_element();
+    let mut distance = SPAWN_SEARCH_STEP;
+    while distance <= max_distance {
+        for i in 0..SPAWN_SEARCH_DIRECTIONS {
+            let angle = i as f32 / SPAWN_SEARCH_DIRECTIONS as f32 * std::f32::consts::TAU;
+            let candidate = position + Vec2::from_angle(angle) * distance;
+            if is_free(candidate) {
+                return Some(candidate);
+            }
+        }
+        distance += SPAWN_SEARCH_STEP;
+    }
+    None
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "RawGoalZoneData")]
 pub struct GoalZoneData {
     pub position: Vec2,
     pub size: Vec2,
-    pub target_mood: Mood,
-    pub required_count: u32,
+    /// How many Moodels of each mood must be inside at once, e.g. 2 Happy and 1 Calm.
+    pub requirements: Vec<(Mood, u32)>,
+    /// If set, the requirements must be held for this many seconds before the zone is satisfied.
+    pub hold_duration: Option<f32>,
+    /// If set, the zone is only satisfied while none of the listed moods are inside.
+    pub forbidden: bool,
+}
+
+/// The goal zone format as written in level files. Older levels use a single
+/// `target_mood` and `required_count` instead of a list of `requirements`.
+#[derive(Deserialize)]
+struct RawGoalZoneData {
+    position: Vec2,
+    size: Vec2,
+    #[serde(default)]
+    requirements: Vec<(Mood, u32)>,
+    #[serde(default)]
+    target_mood: Option<Mood>,
+    #[serde(default)]
+    required_count: Option<u32>,
+    #[serde(default)]
+    hold_duration: Option<f32>,
+    #[serde(default)]
+    forbidden: bool,
+}
+
+impl From<RawGoalZoneData> for GoalZoneData {
+    fn from(raw: RawGoalZoneData) -> Self {
+        let mut requirements = raw.requirements;
+        if let Some(target_mood) = raw.target_mood {
+            requirements.push((target_mood, raw.required_count.unwrap_or(1)));
+        }
+        Self {
+            position: raw.position,
+            size: raw.size,
+            requirements,
+            hold_duration: raw.hold_duration,
+            forbidden: raw.forbidden,
+        }
+    }
+}
+
+/// An area that gradually pushes any Moodel lingering in it into `mood_effect`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HazardZoneData {
+    pub position: Vec2,
+    pub size: Vec2,
+    pub mood_effect: Mood,
+    /// Seconds a Moodel must stay inside before its mood changes.
+    #[serde(default = "default_hazard_dwell_time")]
+    pub dwell_time: f32,
+}
+
+pub(super) fn default_hazard_dwell_time() -> f32 {
+    2.0
 }
 
 // --- Level Lifecycle Systems ---
 
-fn teardown_level(mut commands: Commands, query: Query<Entity, With<LevelEntity>>) {
+pub fn teardown_level(
+    mut commands: Commands,
+    query: Query<Entity, With<LevelEntity>>,
+    mut spawn_schedule: ResMut<SpawnSchedule>,
+) {
     info!("Tearing down level...");
+    *spawn_schedule = SpawnSchedule::default();
     for entity in &query {
         commands.entity(entity).despawn();
     }
 }
 
+/// Loads requested levels. The current level stays in place until the new one passes its
+/// checks, so a broken level never leaves an empty arena.
+#[allow(clippy::too_many_arguments)]
 fn handle_spawn_requests(
     mut commands: Commands,
     mut spawn_requests: EventReader<SpawnLevel>,
     asset_server: Res<AssetServer>,
     mut level_handle: ResMut<LevelHandle>,
     mut active_level: ResMut<ActiveLevel>,
+    time: Res<Time<Real>>,
+    campaign: Res<Campaign>,
+    mut ev_failed: EventWriter<LevelLoadFailed>,
 ) {
     if let Some(request) = spawn_requests.read().last().cloned() {
         match request {
@@ -202,36 +942,105 @@ fn handle_spawn_requests(
                 info!("Loading level from file: {}", path);
                 level_handle.0 = asset_server.load(path);
                 active_level.0 = None;
-                commands.insert_resource(LevelLoadingState);
+                commands.insert_resource(LevelLoadingState {
+                    started: time.elapsed(),
+                });
             }
             SpawnLevel::Programmatic(id) => {
                 info!("Loading programmatic level: {}", id);
-                if let Some(level) = level_library::get_level_by_id(&id) {
-                    active_level.0 = Some(level);
-                    level_handle.0 = Handle::default();
-                    // Trigger spawn immediately for programmatic levels
-                    commands.run_system_cached(spawn_level_entities_oneshot);
-                } else {
-                    error!("Could not find programmatic level with ID: {}", id);
+                match level_library::get_level_by_id(&id) {
+                    Some(Ok(level)) => {
+                        if !check_level(&level, Some(&id), &campaign) {
+                            return;
+                        }
+                        active_level.0 = Some(level);
+                        level_handle.0 = Handle::default();
+                        // Trigger spawn immediately for programmatic levels
+                        commands.run_system_cached(teardown_level);
+                        commands.run_system_cached(spawn_level_entities_oneshot);
+                    }
+                    Some(Err(errors)) => report_level_errors(&id, &errors),
+                    None => error!("Could not find programmatic level with ID: {}", id),
+                }
+            }
+            SpawnLevel::FromJson(json) => {
+                info!("Loading level from JSON");
+                // Shared links land here, so show players what went wrong
+                let level = match serde_json::from_str::<Level>(&json) {
+                    Ok(level) => level,
+                    Err(error) => {
+                        error!("Failed to parse level JSON: {}", error);
+                        ev_failed
+                            .write(LevelLoadFailed(format!("The level is malformed: {error}")));
+                        return;
+                    }
+                };
+                if !check_level(&level, None, &campaign) {
+                    ev_failed.write(LevelLoadFailed(format!(
+                        "{} has errors, see the log for details",
+                        level.name
+                    )));
+                    return;
                 }
+                active_level.0 = Some(level);
+                level_handle.0 = Handle::default();
+                commands.run_system_cached(teardown_level);
+                commands.run_system_cached(spawn_level_entities_oneshot);
             }
         }
         spawn_requests.clear();
     }
 }
 
+/// The path a level file was loaded from, as written in the campaign manifest.
+fn level_file_path(handle: &Handle<Level>) -> Option<String> {
+    handle
+        .path()
+        .map(|path| path.path().to_string_lossy().into_owned())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn process_loaded_level(
     mut commands: Commands,
     level_assets: ResMut<Assets<Level>>,
     level_handle: Res<LevelHandle>,
     mut active_level: ResMut<ActiveLevel>,
+    asset_server: Res<AssetServer>,
+    loading: Res<LevelLoadingState>,
+    timeout: Res<LevelLoadTimeout>,
+    time: Res<Time<Real>>,
+    campaign: Res<Campaign>,
+    mut ev_failed: EventWriter<LevelLoadFailed>,
 ) {
     if let Some(loaded_level) = level_assets.get(&level_handle.0) {
         info!("Level asset loaded, processing...");
-        active_level.0 = Some(loaded_level.clone());
         commands.remove_resource::<LevelLoadingState>();
+        let file = level_file_path(&level_handle.0);
+        if !check_level(loaded_level, file.as_deref(), &campaign) {
+            ev_failed.write(LevelLoadFailed(format!(
+                "{} has errors, see the log for details",
+                loaded_level.name
+            )));
+            return;
+        }
+        active_level.0 = Some(loaded_level.clone());
+        commands.run_system_cached(teardown_level);
         commands.run_system_cached(spawn_level_entities_oneshot);
+        return;
     }
+
+    // Missing or malformed files never show up in the assets, so stop waiting for them
+    let reason =
+        if let Some(LoadState::Failed(error)) = asset_server.get_load_state(&level_handle.0) {
+            error.to_string()
+        } else if time.elapsed() - loading.started > timeout.0 {
+            format!("Timed out after {} seconds", timeout.0.as_secs_f32())
+        } else {
+            return;
+        };
+    error!("Failed to load level: {}", reason);
+    commands.remove_resource::<LevelLoadingState>();
+    ev_failed.write(LevelLoadFailed(reason));
 }
 
 // Standard Bevy system for spawning level entities
@@ -246,93 +1055,158 @@ fn spawn_level_entities(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     font_handle: Res<FontHandle>,
+    mut outcome: ResMut<LevelOutcome>,
+    mut palette: ResMut<LevelPalette>,
+    mut clear_color: ResMut<ClearColor>,
+    mut saved_clear_color: ResMut<SavedClearColor>,
+    mut audio_mix: ResMut<AudioMix>,
+    asset_server: Res<AssetServer>,
+    mut rng: ResMut<GameRng>,
 ) {
     let Some(level) = &active_level.0 else {
         error!("Attempted to spawn level, but no active level data was found!");
         return;
     };
+    // Every attempt at a level plays out the same way for a given seed
+    rng.reseed();
 
     info!("Spawning level entities for: {}", level.name);
+    outcome.set_if_neq(LevelOutcome::InProgress);
+    commands.insert_resource(LevelClock::default());
+    commands.insert_resource(GameStats::default());
 
     // Note: Existing level entities are cleaned up by OnExit(Screen::Gameplay)
 
     play_area.center = Vec2::ZERO;
     play_area.size = level.play_area;
+    play_area.shape = level.play_area_shape;
+
+    // Apply the level theme, falling back to the global theme when unset
+    *palette = level
+        .theme
+        .as_ref()
+        .map(LevelTheme::palette)
+        .unwrap_or_default();
+    let level_clear_color = level
+        .theme
+        .as_ref()
+        .and_then(|theme| parse_theme_color(&theme.clear_color));
+    match (level_clear_color, saved_clear_color.0) {
+        (Some(color), saved) => {
+            saved_clear_color.0 = Some(saved.unwrap_or(clear_color.0));
+            clear_color.0 = color;
+        }
+        (None, Some(saved)) => {
+            clear_color.0 = saved;
+            saved_clear_color.0 = None;
+        }
+        (None, None) => {}
+    }
+
+    // Apply the level's audio mix, or the neutral mix if it has none
+    audio_mix.set_if_neq(level.audio_mix.unwrap_or_default());
+
+    // Scatter the random Moodels, which are then placed like authored ones
+    let weights = level.spawn_weights.clone().unwrap_or_default();
+    let half_size = play_area.size / 2.0 * RANDOM_MOODEL_SPREAD;
+    let random_moodels: Vec<MoodelData> = (0..level.random_moodels)
+        .map(|_| MoodelData {
+            mood: Mood::random_weighted(&mut *rng, &weights),
+            position: play_area.clamp(
+                play_area.center
+                    + Vec2::new(
+                        rng.random_range(-half_size.x..=half_size.x),
+                        rng.random_range(-half_size.y..=half_size.y),
+                    ),
+            ),
+            locked: false,
+        })
+        .collect();
 
     // Spawn Moodels
-    for data in &level.moodels {
-        // SIMPLIFIED: Just spawn the bundle. All logic is self-contained.
-        commands
-            .spawn((
-                spawn_moodel_bundle(
-                    data.mood,
-                    &mood_assets,
-                    data.position.extend(0.0),
-                    350.0,
-                    time.elapsed_secs(),
-                ),
-                LevelEntity,
-                StateScoped(Screen::Gameplay),
-            ))
-            .observe(select_mood);
+    for data in level.moodels.iter().chain(&random_moodels) {
+        // Authored positions may overlap walls, which makes the physics solver eject them violently
+        let Some(position) =
+            find_free_spawn_position(data.position, &level.obstacles, level.play_area)
+        else {
+            warn!(
+                "Skipping {:?} Moodel at {:?}: no free spawn position found",
+                data.mood, data.position
+            );
+            continue;
+        };
+        if position != data.position {
+            warn!(
+                "{:?} Moodel at {:?} overlaps an obstacle, moved to {:?}",
+                data.mood, data.position, position
+            );
+        }
+
+        spawn_level_moodel(
+            &mut commands,
+            &mood_assets,
+            data.mood,
+            position,
+            DEFAULT_MOODEL_MAX_SPEED,
+            time.elapsed_secs(),
+            &mut *rng,
+        )
+        .insert_if(MoodLock::permanent(), || data.locked);
     }
 
+    // Queue up the Moodels that arrive later
+    let mut pending = level.spawns.clone();
+    pending.sort_by(|a, b| a.delay_secs.total_cmp(&b.delay_secs));
+    commands.insert_resource(SpawnSchedule {
+        elapsed: 0.0,
+        pending,
+    });
+
     // Spawn Obstacles
     for data in &level.obstacles {
-        match &data.kind {
-            ObstacleKind::Wall { size } => {
-                let border_thickness = 4.0;
-                commands
-                    .spawn((
-                        Name::new("Wall"),
-                        Obstacle,
-                        LevelEntity,
-                        StateScoped(Screen::Gameplay),
-                        // NEW: Add the ObstacleCollider marker for the AI to see
-                        ObstacleCollider,
-                        Transform::from_xyz(data.position.x, data.position.y, 0.0),
-                        RigidBody::Static,
-                        Collider::rectangle(size.x, size.y),
-                        // MODIFIED: Assign to the correct collision layer
-                        CollisionLayers::new(COLLISION_LAYER_OBSTACLE, COLLISION_LAYER_MOODEL),
-                    ))
-                    .with_children(|parent| {
-                        parent.spawn((
-                            Mesh2d(meshes.add(Rectangle::new(size.x, size.y))),
-                            MeshMaterial2d(materials.add(Color::srgb(0.25, 0.25, 0.3))),
-                            Transform::from_xyz(0.0, 0.0, 0.0),
-                        ));
-                        parent.spawn((
-                            Mesh2d(meshes.add(Rectangle::new(
-                                size.x - border_thickness,
-                                size.y - border_thickness,
-                            ))),
-                            MeshMaterial2d(materials.add(Color::srgb(0.4, 0.4, 0.5))),
-                            Transform::from_xyz(0.0, 0.0, 0.1),
-                        ));
-                    });
-            }
-        }
+        spawn_obstacle(&mut commands, &mut meshes, &mut materials, &palette, data);
     }
 
     // Spawn Goal Zones with modern Text API
     for data in &level.goal_zones {
+        let zone_label = data
+            .requirements
+            .iter()
+            .map(|(mood, _)| format!("{mood:?}"))
+            .collect::<Vec<_>>()
+            .join(" + ");
+        let goal_zone = GoalZone {
+            requirements: data.requirements.clone(),
+            current_counts: vec![0; data.requirements.len()],
+            size: data.size,
+            hold_duration: data.hold_duration,
+            forbidden: data.forbidden,
+            ..default()
+        };
+        // Spawned in the default colors, then tinted by `tint_zones`
+        let zone_color = goal_zone.base_color(ColorScheme::Default);
+        let zone_text = goal_zone_text(&goal_zone);
         let zone_entity = commands
             .spawn((
-                Name::new(format!("{:?} Goal Zone", data.target_mood)),
-                GoalZone {
-                    target_mood: data.target_mood,
-                    required_count: data.required_count,
-                    ..default()
-                },
+                Name::new(format!(
+                    "{} {}",
+                    zone_label,
+                    if data.forbidden {
+                        "Forbidden Zone"
+                    } else {
+                        "Goal Zone"
+                    }
+                )),
+                goal_zone,
                 LevelEntity,
                 StateScoped(Screen::Gameplay),
                 Mesh2d(meshes.add(Rectangle::new(data.size.x, data.size.y))),
-                MeshMaterial2d(materials.add(data.target_mood.color().with_alpha(0.2))),
+                MeshMaterial2d(materials.add(zone_color.with_alpha(palette.zone_alpha))),
                 Transform::from_xyz(data.position.x, data.position.y, -1.0),
                 RigidBody::Static,
                 Collider::rectangle(data.size.x, data.size.y),
                 Sensor,
+                CollisionLayers::new(COLLISION_LAYER_TRIGGER, COLLISION_LAYER_MOODEL),
             ))
             .id();
 
@@ -340,26 +1214,57 @@ fn spawn_level_entities(
         commands.entity(zone_entity).with_children(|parent| {
             parent.spawn((
                 GoalZoneText,
-                Text2d::new(format!("0 / {}", data.required_count)),
+                Text2d::new(zone_text),
                 TextFont {
                     font: font_handle.0.clone(),
                     font_size: 40.0,
                     ..default()
                 },
-                TextColor(Color::WHITE.with_alpha(0.6)),
+                TextColor(palette.accent.with_alpha(0.6)),
                 TextLayout::new_with_justify(JustifyText::Center),
                 Transform::from_xyz(0.0, 0.0, 0.1),
             ));
         });
     }
 
+    // Spawn Hazard Zones
+    for data in &level.hazard_zones {
+        let initial_alpha = 0.25;
+        commands.spawn((
+            Name::new(format!("{:?} Hazard Zone", data.mood_effect)),
+            HazardZone {
+                mood_effect: data.mood_effect,
+                dwell_time: data.dwell_time,
+                size: data.size,
+                ..default()
+            },
+            LevelEntity,
+            StateScoped(Screen::Gameplay),
+            Mesh2d(meshes.add(Rectangle::new(data.size.x, data.size.y))),
+            MeshMaterial2d(materials.add(Color::srgba(0.9, 0.15, 0.1, initial_alpha))),
+            PulseAnimation { initial_alpha },
+            Transform::from_xyz(data.position.x, data.position.y, -1.0),
+            RigidBody::Static,
+            Collider::rectangle(data.size.x, data.size.y),
+            Sensor,
+            CollisionLayers::new(COLLISION_LAYER_TRIGGER, COLLISION_LAYER_MOODEL),
+        ));
+    }
+
     // Spawn level music
-    commands.spawn((
+    let mut level_music = commands.spawn((
         Name::new("Level Music"),
-        music(level_assets.music.clone()),
         LevelEntity,
         StateScoped(Screen::Gameplay),
     ));
+    match &level.music {
+        Some(path) => {
+            level_music.insert((music(asset_server.load(path)), CustomLevelMusic));
+        }
+        None => {
+            level_music.insert(music(level_assets.music.clone()));
+        }
+    }
 
     // Spawn a large, pickable background plane for deselection
     commands
@@ -378,23 +1283,319 @@ fn spawn_level_entities(
         .observe(handle_background_click);
 }
 
+/// Spawns an obstacle with its collider and border/fill meshes. Returns `None` if the
+/// obstacle's shape is invalid.
+pub fn spawn_obstacle(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    palette: &LevelPalette,
+    data: &ObstacleData,
+) -> Option<Entity> {
+    let border_thickness = 4.0;
+    // The collider, the border and fill meshes drawn on top of each other, and for
+    // polygons the hull the AI steers around.
+    let (name, collider, border_mesh, fill_mesh, hull) = match &data.kind {
+        ObstacleKind::Wall { size } => (
+            "Wall",
+            Collider::rectangle(size.x, size.y),
+            meshes.add(Rectangle::new(size.x, size.y)),
+            meshes.add(Rectangle::new(
+                size.x - border_thickness,
+                size.y - border_thickness,
+            )),
+            None,
+        ),
+        ObstacleKind::Circle { radius } => (
+            "Pillar",
+            Collider::circle(*radius),
+            meshes.add(Circle::new(*radius)),
+            meshes.add(Circle::new(radius - border_thickness / 2.0)),
+            None,
+        ),
+        ObstacleKind::Polygon { vertices } => {
+            if vertices.len() < 3 {
+                error!(
+                    "Skipping polygon obstacle at {}: it needs at least three vertices, got {}",
+                    data.position,
+                    vertices.len()
+                );
+                return None;
+            }
+            let hull = convex_hull(vertices);
+            let Some(collider) = Collider::convex_hull(hull.clone()).filter(|_| hull.len() >= 3)
+            else {
+                error!(
+                    "Skipping polygon obstacle at {}: its vertices don't enclose an area",
+                    data.position
+                );
+                return None;
+            };
+            (
+                "Polygon Obstacle",
+                collider,
+                meshes.add(convex_polygon_mesh(&hull)),
+                meshes.add(convex_polygon_mesh(&shrink_convex_polygon(
+                    &hull,
+                    border_thickness / 2.0,
+                ))),
+                Some(ObstacleHull(hull)),
+            )
+        }
+    };
+    let mut obstacle = commands.spawn((
+        Name::new(name),
+        Obstacle,
+        LevelEntity,
+        StateScoped(Screen::Gameplay),
+        // NEW: Add the ObstacleCollider marker for the AI to see
+        ObstacleCollider,
+        Transform::from_xyz(data.position.x, data.position.y, 0.0),
+        collider,
+        // MODIFIED: Assign to the correct collision layer
+        CollisionLayers::new(COLLISION_LAYER_OBSTACLE, COLLISION_LAYER_MOODEL),
+        ObstacleSource(data.clone()),
+    ));
+    if let Some(hull) = hull {
+        obstacle.insert(hull);
+    }
+    if let ObstacleKind::Circle { radius } = data.kind {
+        obstacle.insert(ObstacleRadius(radius));
+    }
+    // Patrolling obstacles are kinematic, so they shove Moodels aside without being
+    // pushed back themselves.
+    match &data.patrol {
+        Some(patrol) if !patrol.waypoints.is_empty() => {
+            obstacle.insert((
+                RigidBody::Kinematic,
+                PatrolMovement::new(data.position, patrol),
+            ));
+        }
+        _ => {
+            obstacle.insert(RigidBody::Static);
+        }
+    }
+    // Clicks on the meshes bubble up to the obstacle, for the eraser tool.
+    obstacle.with_children(|parent| {
+        parent.spawn((
+            Mesh2d(border_mesh),
+            MeshMaterial2d(materials.add(palette.wall_border)),
+            Transform::from_xyz(0.0, 0.0, 0.0),
+            Pickable::default(),
+        ));
+        parent.spawn((
+            Mesh2d(fill_mesh),
+            MeshMaterial2d(materials.add(palette.wall)),
+            Transform::from_xyz(0.0, 0.0, 0.1),
+            Pickable::default(),
+        ));
+    });
+    obstacle.observe(erase_obstacle);
+    Some(obstacle.id())
+}
+
+/// Spawns a static wall, the same way walls from level files are spawned.
+pub fn spawn_wall(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    palette: &LevelPalette,
+    position: Vec2,
+    size: Vec2,
+) -> Entity {
+    let data = ObstacleData {
+        position,
+        kind: ObstacleKind::Wall { size },
+        patrol: None,
+    };
+    spawn_obstacle(commands, meshes, materials, palette, &data)
+        .expect("walls always have a valid shape")
+}
+
+/// Spawns a static circular pillar, the same way pillars from level files are spawned.
+pub fn spawn_circle_obstacle(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    palette: &LevelPalette,
+    position: Vec2,
+    radius: f32,
+) -> Entity {
+    let data = ObstacleData {
+        position,
+        kind: ObstacleKind::Circle { radius },
+        patrol: None,
+    };
+    spawn_obstacle(commands, meshes, materials, palette, &data)
+        .expect("circles always have a valid shape")
+}
+
 // One-shot system wrapper for world access
 fn spawn_level_entities_oneshot(world: &mut World) {
     let _ = world.run_system_once(spawn_level_entities);
 }
 
+fn request_level_reset(mut reset_events: EventWriter<ResetLevel>) {
+    reset_events.write(ResetLevel);
+}
+
+/// Tears the level down and spawns it again from the cached data. Spawning resets the
+/// outcome, clock and spawn schedule.
+fn reset_level(
+    mut commands: Commands,
+    mut reset_events: EventReader<ResetLevel>,
+    active_level: Res<ActiveLevel>,
+    loading: Option<Res<LevelLoadingState>>,
+) {
+    reset_events.clear();
+    if active_level.0.is_none() || loading.is_some() {
+        warn!("Can't reset the level before it has loaded");
+        return;
+    }
+    info!("Resetting level");
+    commands.run_system_cached(teardown_level);
+    commands.run_system_cached(spawn_level_entities_oneshot);
+}
+
+/// Jumps straight to the next level of the campaign, for testing.
+#[cfg(feature = "dev")]
+fn skip_level(
+    mut commands: Commands,
+    mut campaign: ResMut<Campaign>,
+    mut source: ResMut<ActiveLevelSource>,
+    mut spawn_events: EventWriter<SpawnLevel>,
+) {
+    if !campaign.has_next() {
+        info!("Can't skip the last level of the campaign");
+        return;
+    }
+    let Some(next_level) = campaign.advance() else {
+        return;
+    };
+    info!("Skipping to {}", next_level);
+    *source = ActiveLevelSource::Campaign;
+    spawn_events.write(SpawnLevel::FromFile(next_level.to_string()));
+    commands.run_system_cached(teardown_level);
+}
+
+/// Rebuilds a `Level` from the live entities, so a level edited in-game can be written back to disk.
+fn save_level(
+    mut save_events: EventReader<SaveLevel>,
+    active_level: Res<ActiveLevel>,
+    play_area: Res<PlayArea>,
+    moodel_query: Query<(&Transform, &Mood, Option<&MoodLock>), With<LevelEntity>>,
+    obstacle_query: Query<(&Transform, &ObstacleSource), With<Obstacle>>,
+    zone_query: Query<(&Transform, &GoalZone)>,
+    hazard_query: Query<(&Transform, &HazardZone)>,
+    spawn_schedule: Res<SpawnSchedule>,
+) {
+    let Some(active_level) = &active_level.0 else {
+        warn!("No active level to save");
+        return;
+    };
+    let level = Level {
+        name: active_level.name.clone(),
+        meta: active_level.meta.clone(),
+        play_area: play_area.size,
+        play_area_shape: play_area.shape,
+        // Random Moodels have spawned by now, so they are saved with the rest
+        random_moodels: 0,
+        spawn_weights: active_level.spawn_weights.clone(),
+        moodels: moodel_query
+            .iter()
+            .map(|(transform, mood, lock)| MoodelData {
+                mood: *mood,
+                position: transform.translation.truncate(),
+                // Timed locks are placed during play, so only permanent ones are level data
+                locked: lock.is_some_and(|lock| lock.remaining.is_none()),
+            })
+            .collect(),
+        obstacles: obstacle_query
+            .iter()
+            .map(|(transform, ObstacleSource(data))| ObstacleData {
+                // A patrolling obstacle's path starts where it was placed, not where it is now.
+                position: if data.patrol.is_some() {
+                    data.position
+                } else {
+                    transform.translation.truncate()
+                },
+                ..data.clone()
+            })
+            .collect(),
+        goal_zones: zone_query
+            .iter()
+            .map(|(transform, zone)| GoalZoneData {
+                position: transform.translation.truncate(),
+                size: zone.size,
+                requirements: zone.requirements.clone(),
+                hold_duration: zone.hold_duration,
+                forbidden: zone.forbidden,
+            })
+            .collect(),
+        hazard_zones: hazard_query
+            .iter()
+            .map(|(transform, hazard)| HazardZoneData {
+                position: transform.translation.truncate(),
+                size: hazard.size,
+                mood_effect: hazard.mood_effect,
+                dwell_time: hazard.dwell_time,
+            })
+            .collect(),
+        theme: active_level.theme.clone(),
+        audio_mix: active_level.audio_mix,
+        music: active_level.music.clone(),
+        lose_conditions: active_level.lose_conditions.clone(),
+        // Only what hasn't spawned yet; the rest are saved as regular Moodels above.
+        spawns: spawn_schedule
+            .pending
+            .iter()
+            .map(|spawn| TimedSpawn {
+                delay_secs: spawn.delay_secs - spawn_schedule.elapsed,
+                ..spawn.clone()
+            })
+            .collect(),
+    };
+
+    let json = match serde_json::to_string_pretty(&level) {
+        Ok(json) => json,
+        Err(error) => {
+            error!("Failed to serialize level: {}", error);
+            return;
+        }
+    };
+
+    for SaveLevel(path) in save_events.read() {
+        #[cfg(not(target_family = "wasm"))]
+        match std::fs::write(path, &json) {
+            Ok(()) => info!("Saved level \"{}\" to {}", level.name, path),
+            Err(error) => error!("Failed to write level to {}: {}", path, error),
+        }
+
+        #[cfg(target_family = "wasm")]
+        info!(
+            "Filesystem writes are unavailable on web, not saving level to {}:\n{}",
+            path, json
+        );
+    }
+}
+
 fn hot_reload_level(
     mut commands: Commands,
     mut asset_events: EventReader<AssetEvent<Level>>,
     level_handle: Res<LevelHandle>,
     mut active_level: ResMut<ActiveLevel>,
     level_assets: Res<Assets<Level>>,
+    campaign: Res<Campaign>,
 ) {
     for event in asset_events.read() {
         if let AssetEvent::Modified { id } = event {
             if *id == level_handle.0.id() {
                 info!("Level asset modified, triggering hot-reload.");
                 if let Some(updated_level) = level_assets.get(*id) {
+                    let file = level_file_path(&level_handle.0);
+                    if !check_level(updated_level, file.as_deref(), &campaign) {
+                        continue;
+                    }
                     active_level.0 = Some(updated_level.clone());
                     commands.run_system_cached(spawn_level_entities_oneshot);
                 }
@@ -405,6 +1606,128 @@ fn hot_reload_level(
 
 // --- Gameplay Logic and Visual Feedback Systems ---
 
+/// Spawns each scheduled Moodel once its delay has passed.
+fn run_spawn_schedule(
+    time: Res<Time>,
+    mut spawn_schedule: ResMut<SpawnSchedule>,
+    mut spawn_events: EventWriter<SpawnMoodel>,
+) {
+    if spawn_schedule.pending.is_empty() {
+        return;
+    }
+    spawn_schedule.elapsed += time.delta_secs();
+    let due = spawn_schedule
+        .pending
+        .iter()
+        .take_while(|spawn| spawn.delay_secs <= spawn_schedule.elapsed)
+        .count();
+    for spawn in spawn_schedule.pending.drain(..due) {
+        info!(
+            "Timed spawn: {:?} Moodel at {:?}",
+            spawn.mood, spawn.position
+        );
+        spawn_events.write(SpawnMoodel {
+            mood: spawn.mood,
+            position: spawn.position,
+            max_speed: None,
+        });
+    }
+}
+
+fn handle_spawn_moodel_events(
+    mut commands: Commands,
+    mut spawn_events: EventReader<SpawnMoodel>,
+    mood_assets: Option<Res<MoodAssets>>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+) {
+    let Some(mood_assets) = mood_assets else {
+        return;
+    };
+    for spawn in spawn_events.read() {
+        spawn_level_moodel(
+            &mut commands,
+            &mood_assets,
+            spawn.mood,
+            spawn.position,
+            spawn.max_speed.unwrap_or(DEFAULT_MOODEL_MAX_SPEED),
+            time.elapsed_secs(),
+            &mut *rng,
+        );
+    }
+}
+
+/// Despawns Moodels and forgets them in every zone right away, rather than relying on a
+/// `CollisionEnded` event for an entity that no longer exists.
+fn handle_despawn_moodel_events(
+    mut commands: Commands,
+    mut despawn_events: EventReader<DespawnMoodel>,
+    moodel_query: Query<(), With<Mood>>,
+    mut zone_query: Query<&mut GoalZone>,
+    mut hazard_query: Query<&mut HazardZone>,
+) {
+    for DespawnMoodel(entity) in despawn_events.read() {
+        if !moodel_query.contains(*entity) {
+            continue;
+        }
+        commands.entity(*entity).despawn();
+        for mut goal_zone in &mut zone_query {
+            goal_zone.entities_inside.remove(entity);
+        }
+        for mut hazard_zone in &mut hazard_query {
+            hazard_zone.entities_inside.remove(entity);
+        }
+    }
+}
+
+/// Spawns a Moodel that belongs to the level: it is torn down with it and can be selected.
+fn spawn_level_moodel<'a>(
+    commands: &'a mut Commands,
+    mood_assets: &MoodAssets,
+    mood: Mood,
+    position: Vec2,
+    max_speed: f32,
+    current_time: f32,
+    rng: &mut impl Rng,
+) -> EntityCommands<'a> {
+    let mut moodel = commands.spawn((
+        spawn_moodel_bundle(
+            mood,
+            mood_assets,
+            position.extend(0.0),
+            max_speed,
+            current_time,
+            rng,
+        ),
+        LevelEntity,
+        StateScoped(Screen::Gameplay),
+    ));
+    moodel.observe(select_mood);
+    moodel
+}
+
+/// Drives patrolling obstacles through their velocity rather than their transform, so the
+/// physics engine can push Moodels out of the way.
+fn move_patrolling_obstacles(
+    time: Res<Time>,
+    mut obstacle_query: Query<(&Transform, &mut PatrolMovement, &mut LinearVelocity)>,
+) {
+    let delta = time.delta_secs();
+    if delta <= 0.0 {
+        return;
+    }
+    for (transform, mut patrol, mut velocity) in &mut obstacle_query {
+        let position = transform.translation.truncate();
+        let step = patrol.speed * delta;
+        if position.distance(patrol.path[patrol.target]) <= step {
+            patrol.advance();
+        }
+        let to_target = patrol.path[patrol.target] - position;
+        // Don't overshoot the waypoint on the last stretch.
+        velocity.0 = to_target.clamp_length_max(step) / delta;
+    }
+}
+
 /// Handles both CollisionStarted and CollisionEnded events for zones.
 fn handle_zone_collisions(
     mut commands: Commands,
@@ -427,9 +1750,11 @@ fn handle_zone_collisions(
         if let Ok((_, mut goal_zone)) = zone_query.get_mut(zone_entity) {
             goal_zone.entities_inside.insert(moodel_entity);
             if let Ok((_, mood, transform)) = moodel_query.get(moodel_entity) {
-                if *mood == goal_zone.target_mood {
+                if goal_zone.wants(*mood) && !goal_zone.forbidden {
                     // Trigger sound effect for correct mood entering zone
-                    sfx_writer.write(PlaySound::CorrectZoneEntry);
+                    sfx_writer.write(PlaySound::CorrectZoneEntry {
+                        position: transform.translation.truncate(),
+                    });
                     commands.entity(moodel_entity).insert(AnimateScale {
                         timer: Timer::from_seconds(0.25, TimerMode::Once),
                         initial_scale: transform.scale,
@@ -439,18 +1764,13 @@ fn handle_zone_collisions(
         }
     }
 
-    // Handle entities leaving the zone
+    // Handle entities leaving the zone. The Moodel may have been despawned already, so only
+    // the zone is looked up.
     for CollisionEnded(entity1, entity2) in ended.read() {
-        let (moodel_entity, zone_entity) =
-            get_moodel_and_zone(*entity1, *entity2, &moodel_query, &zone_query);
-        if moodel_entity.is_none() {
-            continue;
-        }
-        let moodel_entity = moodel_entity.unwrap();
-        let zone_entity = zone_entity.unwrap();
-
-        if let Ok((_, mut goal_zone)) = zone_query.get_mut(zone_entity) {
-            goal_zone.entities_inside.remove(&moodel_entity);
+        for (moodel_entity, zone_entity) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if let Ok((_, mut goal_zone)) = zone_query.get_mut(zone_entity) {
+                goal_zone.entities_inside.remove(&moodel_entity);
+            }
         }
     }
 }
@@ -471,46 +1791,198 @@ fn get_moodel_and_zone(
     }
 }
 
+/// Tracks which Moodels are inside each hazard zone.
+fn handle_hazard_collisions(
+    mut started: EventReader<CollisionStarted>,
+    mut ended: EventReader<CollisionEnded>,
+    moodel_query: Query<(), With<Mood>>,
+    mut hazard_query: Query<&mut HazardZone>,
+) {
+    for CollisionStarted(entity1, entity2) in started.read() {
+        for (moodel, hazard) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if moodel_query.contains(moodel) {
+                if let Ok(mut hazard_zone) = hazard_query.get_mut(hazard) {
+                    hazard_zone.entities_inside.insert(moodel, 0.0);
+                }
+            }
+        }
+    }
+    for CollisionEnded(entity1, entity2) in ended.read() {
+        for (moodel, hazard) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if let Ok(mut hazard_zone) = hazard_query.get_mut(hazard) {
+                hazard_zone.entities_inside.remove(&moodel);
+            }
+        }
+    }
+}
+
+/// Changes the mood of Moodels that have lingered in a hazard zone for its dwell time.
+fn apply_hazard_effects(
+    time: Res<Time>,
+    mut hazard_query: Query<&mut HazardZone>,
+    moodel_query: Query<&Mood>,
+    mut set_mood_events: EventWriter<SetMood>,
+) {
+    for mut hazard_zone in &mut hazard_query {
+        let HazardZone {
+            mood_effect,
+            dwell_time,
+            entities_inside,
+            ..
+        } = &mut *hazard_zone;
+        entities_inside.retain(|entity, _| moodel_query.contains(*entity));
+        for (entity, time_inside) in entities_inside.iter_mut() {
+            // Moodels already in the hazard's mood don't build up any more stress.
+            if moodel_query
+                .get(*entity)
+                .is_ok_and(|mood| mood == mood_effect)
+            {
+                *time_inside = 0.0;
+                continue;
+            }
+            *time_inside += time.delta_secs();
+            if *time_inside >= *dwell_time {
+                *time_inside = 0.0;
+                set_mood_events.write(SetMood {
+                    entity: *entity,
+                    mood: *mood_effect,
+                });
+            }
+        }
+    }
+}
+
 /// Recalculates the score and satisfaction state for each zone.
 /// This system performs the MUTABLE operations on GoalZone.
-fn update_zone_state(mut zone_query: Query<&mut GoalZone>, moodel_query: Query<&Mood>) {
+fn update_zone_state(
+    time: Res<Time>,
+    mut zone_query: Query<&mut GoalZone>,
+    moodel_query: Query<&Mood>,
+) {
     for mut goal_zone in &mut zone_query {
-        let mut current_valid_count = 0;
-        for entity_in_zone in &goal_zone.entities_inside {
-            if let Ok(mood) = moodel_query.get(*entity_in_zone) {
-                if *mood == goal_zone.target_mood {
-                    current_valid_count += 1;
-                }
-            }
+        // Moodels despawned without a `CollisionEnded` would otherwise stay in the set forever
+        goal_zone
+            .entities_inside
+            .retain(|entity| moodel_query.contains(*entity));
+
+        let current_counts: Vec<u32> = goal_zone
+            .requirements
+            .iter()
+            .map(|(required_mood, _)| {
+                goal_zone
+                    .entities_inside
+                    .iter()
+                    .filter(|entity| {
+                        moodel_query
+                            .get(**entity)
+                            .is_ok_and(|mood| mood == required_mood)
+                    })
+                    .count() as u32
+            })
+            .collect();
+        goal_zone.current_counts = current_counts;
+
+        let requirements_met = goal_zone.requirements_met();
+        if requirements_met {
+            goal_zone.hold_timer += time.delta_secs();
+        } else {
+            goal_zone.hold_timer = 0.0;
         }
-        goal_zone.current_count = current_valid_count;
-        goal_zone.is_satisfied = goal_zone.current_count >= goal_zone.required_count;
+        goal_zone.is_satisfied = requirements_met && goal_zone.hold_remaining() <= 0.0;
     }
 }
 
+/// The zone's progress text. A single requirement is shown as "1 / 2", a mixture
+/// gets one line per mood, such as "Happy 1 / 2", and a forbidden zone lists what
+/// to keep out, such as "No Rage (1)".
+fn goal_zone_text(goal_zone: &GoalZone) -> String {
+    let GoalZone {
+        requirements,
+        current_counts,
+        ..
+    } = goal_zone;
+    if goal_zone.forbidden {
+        return requirements
+            .iter()
+            .zip(current_counts)
+            .map(|((mood, _), current)| match current {
+                0 => format!("No {mood:?}"),
+                _ => format!("No {mood:?} ({current})"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+    match requirements.as_slice() {
+        [(_, required)] => format!("{} / {}", current_counts.first().unwrap_or(&0), required),
+        _ => requirements
+            .iter()
+            .zip(current_counts)
+            .map(|((mood, required), current)| format!("{mood:?} {current} / {required}"))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+/// Fill color of a forbidden zone while something it forbids is inside.
+const FORBIDDEN_ZONE_COLOR: Color = Color::srgb(0.9, 0.1, 0.1);
+
 /// Updates the visuals (text, color) based on the pre-calculated state.
 /// This system only performs IMMUTABLE reads of GoalZone.
 fn update_zone_visuals(
     mut commands: Commands,
-    zone_query: Query<(Entity, &GoalZone, &Children), Changed<GoalZone>>,
+    zone_query: Query<
+        (Entity, &GoalZone, &Children, &MeshMaterial2d<ColorMaterial>),
+        Changed<GoalZone>,
+    >,
     mut text_query: Query<(&mut Text2d, &mut TextColor), With<GoalZoneText>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    palette: Res<LevelPalette>,
+    scheme: Res<ColorScheme>,
 ) {
-    for (zone_entity, goal_zone, children) in &zone_query {
+    for (zone_entity, goal_zone, children, material_handle) in &zone_query {
         // Update text
         for child in children.iter() {
             if let Ok((mut text, mut text_color)) = text_query.get_mut(child) {
-                text.0 = format!("{} / {}", goal_zone.current_count, goal_zone.required_count);
+                text.0 = goal_zone_text(goal_zone);
+                // Count down while the requirements are being held
+                let hold_remaining = goal_zone.hold_remaining();
+                if goal_zone.requirements_met() && hold_remaining > 0.0 {
+                    text.0 += &format!(" ({hold_remaining:.1}s)");
+                }
                 text_color.0 = if goal_zone.is_satisfied {
-                    Color::WHITE
+                    palette.accent
                 } else {
-                    Color::WHITE.with_alpha(0.6)
+                    palette.accent.with_alpha(0.6)
                 };
             }
         }
 
+        // A forbidden zone flashes red while something it forbids is inside.
+        if goal_zone.forbidden {
+            let violated = !goal_zone.requirements_met();
+            if let Some(material) = materials.get_mut(&material_handle.0) {
+                material.color = if violated {
+                    FORBIDDEN_ZONE_COLOR.with_alpha(palette.zone_alpha)
+                } else {
+                    goal_zone.base_color(*scheme).with_alpha(palette.zone_alpha)
+                };
+            }
+            if violated {
+                commands.entity(zone_entity).insert(PulseAnimation {
+                    initial_alpha: palette.zone_alpha,
+                });
+            } else {
+                commands.entity(zone_entity).remove::<PulseAnimation>();
+            }
+            continue;
+        }
+
         // Update zone background color (will be handled by pulse animation)
-        let initial_alpha = if goal_zone.is_satisfied { 0.6 } else { 0.2 };
+        let initial_alpha = if goal_zone.is_satisfied {
+            0.6
+        } else {
+            palette.zone_alpha
+        };
 
         if goal_zone.is_satisfied {
             commands
@@ -522,7 +1994,7 @@ fn update_zone_visuals(
     }
 }
 
-fn check_win_condition(zone_query: Query<&GoalZone>, mut level_won: Local<bool>) {
+fn check_win_condition(zone_query: Query<&GoalZone>, mut outcome: ResMut<LevelOutcome>) {
     // If there are no goal zones defined, we can't win the level.
     if zone_query.is_empty() {
         return;
@@ -530,13 +2002,72 @@ fn check_win_condition(zone_query: Query<&GoalZone>, mut level_won: Local<bool>)
 
     let all_satisfied = zone_query.iter().all(|zone| zone.is_satisfied);
 
-    if all_satisfied && !*level_won {
-        *level_won = true;
-        info!("🎉 LEVEL COMPLETE! 🎉");
-        // TODO: Trigger victory screen or next level
+    if all_satisfied && *outcome == LevelOutcome::InProgress {
+        *outcome = LevelOutcome::Won;
+    }
+}
+
+fn check_lose_condition(
+    active_level: Res<ActiveLevel>,
+    clock: Res<LevelClock>,
+    moodel_query: Query<&Mood, With<LevelEntity>>,
+    mut outcome: ResMut<LevelOutcome>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if *outcome != LevelOutcome::InProgress {
+        return;
+    }
+    let Some(level) = &active_level.0 else {
+        return;
+    };
+
+    let lost = level
+        .lose_conditions
+        .iter()
+        .any(|condition| match condition {
+            LoseCondition::TimeLimit { secs } => clock.elapsed >= *secs,
+            LoseCondition::AllMoodelsMood { mood } => {
+                !moodel_query.is_empty()
+                    && moodel_query.iter().all(|moodel_mood| moodel_mood == mood)
+            }
+        });
+    if lost {
+        *outcome = LevelOutcome::Lost;
+        next_screen.set(Screen::Defeat);
+    }
+}
+
+fn tick_level_clock(time: Res<Time>, mut clock: ResMut<LevelClock>) {
+    clock.elapsed += time.delta_secs();
+}
+
+fn announce_level_outcome(outcome: Res<LevelOutcome>) {
+    match *outcome {
+        LevelOutcome::Won => {
+            info!("🎉 LEVEL COMPLETE! 🎉");
+            // The campaign moves on to the next level once the victory celebration finishes
+        }
+        LevelOutcome::Lost => info!("Level failed"),
+        LevelOutcome::InProgress => {}
     }
 }
 
+/// Restores the app-wide clear color and palette when leaving gameplay.
+fn restore_default_theme(
+    mut clear_color: ResMut<ClearColor>,
+    mut saved_clear_color: ResMut<SavedClearColor>,
+    mut palette: ResMut<LevelPalette>,
+) {
+    if let Some(saved) = saved_clear_color.0.take() {
+        clear_color.0 = saved;
+    }
+    *palette = LevelPalette::default();
+}
+
+fn restore_default_audio_mix(mut audio_mix: ResMut<AudioMix>) {
+    audio_mix.set_if_neq(AudioMix::default());
+}
+
 // --- Animation Systems ---
 
 fn animate_scale_pop(
@@ -558,30 +2089,507 @@ fn animate_scale_pop(
     }
 }
 
+/// Tints new zones, and every zone when the color scheme changes, keeping their alpha.
+fn tint_zones(
+    scheme: Res<ColorScheme>,
+    zone_query: Query<(Ref<GoalZone>, &MeshMaterial2d<ColorMaterial>)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (goal_zone, material_handle) in &zone_query {
+        if !goal_zone.is_added() && !scheme.is_changed() {
+            continue;
+        }
+        // A violated forbidden zone stays red
+        if goal_zone.forbidden && !goal_zone.requirements_met() {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&material_handle.0) {
+            let alpha = material.color.alpha();
+            material.color = goal_zone.base_color(*scheme).with_alpha(alpha);
+        }
+    }
+}
+
+/// Pulses every zone in step. The phase only advances while this system runs, so the pulse
+/// freezes while paused, slows down with virtual time and picks up where it left off.
 fn pulse_zone_animation(
     time: Res<Time>,
     query: Query<(&MeshMaterial2d<ColorMaterial>, &PulseAnimation)>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut phase: Local<f32>,
 ) {
+    *phase = (*phase + time.delta_secs() * 3.0) % std::f32::consts::TAU;
     for (material_handle, anim) in &query {
         if let Some(material) = materials.get_mut(&material_handle.0) {
             // Pulse alpha between initial_alpha and initial_alpha + 0.2
-            let pulse = (time.elapsed_secs() * 3.0).sin() * 0.5 + 0.5; // Ranges from 0 to 1
+            let pulse = phase.sin() * 0.5 + 0.5; // Ranges from 0 to 1
             material.color.set_alpha(anim.initial_alpha + pulse * 0.2);
         }
     }
 }
 
+/// Levels started from the title screen belong to the campaign.
+fn reset_active_level_source(mut source: ResMut<ActiveLevelSource>) {
+    *source = ActiveLevelSource::Campaign;
+}
+
 // --- Backwards Compatibility ---
 
-/// Legacy function to spawn the default level
+/// Spawns the current campaign level, or a level shared by link if there is one waiting.
+/// Levels from elsewhere are spawned again, so retrying one doesn't switch to the campaign.
 /// This is called from gameplay.rs OnEnter(Screen::Gameplay)
-pub fn spawn_level(mut ev: EventWriter<SpawnLevel>) {
-    // Load the tutorial level from JSON file
-    ev.write(SpawnLevel::FromFile(
-        "levels/tutorial_1.level.json".to_string(),
-    ));
+pub fn spawn_level(
+    mut ev: EventWriter<SpawnLevel>,
+    campaign: Res<Campaign>,
+    mut shared_level: ResMut<SharedLevel>,
+    mut source: ResMut<ActiveLevelSource>,
+) {
+    if let Some(json) = shared_level.0.take() {
+        *source = ActiveLevelSource::Other(SpawnLevel::FromJson(json));
+    }
+    if let ActiveLevelSource::Other(request) = &*source {
+        ev.write(request.clone());
+        return;
+    }
+    let Some(level) = campaign.current_level() else {
+        error!("The campaign has no level to play");
+        return;
+    };
+    ev.write(SpawnLevel::FromFile(level.to_string()));
 
     // Alternative: Load a programmatic level
     // ev.send(SpawnLevel::Programmatic("tutorial_code".to_string()));
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requirements_met_only_when_every_mood_is_satisfied() {
+        let mut zone = GoalZone {
+            requirements: vec![(Mood::Happy, 2), (Mood::Calm, 1)],
+            current_counts: vec![2, 0],
+            ..default()
+        };
+        assert!(!zone.requirements_met());
+        zone.current_counts = vec![1, 1];
+        assert!(!zone.requirements_met());
+        zone.current_counts = vec![2, 1];
+        assert!(zone.requirements_met());
+        zone.current_counts = vec![3, 2];
+        assert!(zone.requirements_met());
+    }
+
+    /// Runs `update_zone_state` once, `secs` seconds after the previous run.
+    fn update_zone(app: &mut App, zone: Entity, secs: f32) -> &GoalZone {
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(secs));
+        app.world_mut().run_system_once(update_zone_state).unwrap();
+        app.world().get::<GoalZone>(zone).unwrap()
+    }
+
+    #[test]
+    fn hold_timer_resets_when_the_requirement_breaks() {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        let moodel = app.world_mut().spawn(Mood::Happy).id();
+        let zone = app
+            .world_mut()
+            .spawn(GoalZone {
+                requirements: vec![(Mood::Happy, 1)],
+                hold_duration: Some(2.0),
+                entities_inside: HashSet::from([moodel]),
+                ..default()
+            })
+            .id();
+
+        let held = update_zone(&mut app, zone, 1.5);
+        assert_eq!(held.hold_timer, 1.5);
+        assert!(!held.is_satisfied);
+
+        app.world_mut().entity_mut(moodel).insert(Mood::Sad);
+        assert_eq!(update_zone(&mut app, zone, 0.25).hold_timer, 0.0);
+
+        // The hold starts over rather than picking up where it left off.
+        app.world_mut().entity_mut(moodel).insert(Mood::Happy);
+        assert!(!update_zone(&mut app, zone, 1.5).is_satisfied);
+        assert!(update_zone(&mut app, zone, 1.0).is_satisfied);
+    }
+
+    #[test]
+    fn forbidden_mood_inside_blocks_the_zone() {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        let calm = app.world_mut().spawn(Mood::Calm).id();
+        let rage = app.world_mut().spawn(Mood::Rage).id();
+        let zone = app
+            .world_mut()
+            .spawn(GoalZone {
+                requirements: vec![(Mood::Rage, 1)],
+                forbidden: true,
+                entities_inside: HashSet::from([calm, rage]),
+                ..default()
+            })
+            .id();
+        assert!(!update_zone(&mut app, zone, 0.1).is_satisfied);
+
+        app.world_mut()
+            .get_mut::<GoalZone>(zone)
+            .unwrap()
+            .entities_inside
+            .remove(&rage);
+        assert!(update_zone(&mut app, zone, 0.1).is_satisfied);
+    }
+
+    #[test]
+    fn old_goal_zone_format_migrates_to_requirements() {
+        let zone: GoalZoneData = serde_json::from_str(
+            r#"{
+                "position": [0.0, 0.0],
+                "size": [100.0, 100.0],
+                "target_mood": "Happy",
+                "required_count": 3
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(zone.requirements, vec![(Mood::Happy, 3)]);
+    }
+
+    #[test]
+    fn convex_hull_drops_collinear_and_duplicate_points() {
+        let points = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 1.0),
+        ];
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Vec2::new(0.0, 0.0),
+                Vec2::new(2.0, 0.0),
+                Vec2::new(2.0, 2.0),
+                Vec2::new(0.0, 2.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn degenerate_convex_hulls_have_fewer_than_three_points() {
+        assert!(convex_hull(&[]).is_empty());
+        assert_eq!(convex_hull(&[Vec2::ONE, Vec2::ONE]), vec![Vec2::ONE]);
+        let line = [Vec2::ZERO, Vec2::new(1.0, 1.0), Vec2::new(2.0, 2.0)];
+        assert_eq!(convex_hull(&line), vec![Vec2::ZERO, Vec2::new(2.0, 2.0)]);
+    }
+
+    #[test]
+    fn closest_point_on_convex_polygon_projects_outside_points() {
+        let square = convex_hull(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(2.0, 0.0),
+            Vec2::new(2.0, 2.0),
+            Vec2::new(0.0, 2.0),
+        ]);
+        let inside = Vec2::new(1.5, 0.5);
+        assert_eq!(closest_point_on_convex_polygon(&square, inside), inside);
+        assert_eq!(
+            closest_point_on_convex_polygon(&square, Vec2::new(1.0, -3.0)),
+            Vec2::new(1.0, 0.0)
+        );
+        assert_eq!(
+            closest_point_on_convex_polygon(&square, Vec2::new(5.0, 4.0)),
+            Vec2::new(2.0, 2.0)
+        );
+    }
+
+    #[test]
+    fn saved_level_reads_back_unchanged() {
+        let level: Level =
+            serde_json::from_str(include_str!("../../assets/levels/tutorial_1.level.json"))
+                .unwrap();
+        let mut app = App::new();
+        app.add_event::<SaveLevel>()
+            .init_resource::<SpawnSchedule>()
+            .insert_resource(ActiveLevel(Some(level.clone())))
+            .insert_resource(PlayArea {
+                size: level.play_area,
+                shape: level.play_area_shape,
+                ..default()
+            });
+        let at = |position: Vec2| Transform::from_translation(position.extend(0.0));
+        for moodel in &level.moodels {
+            app.world_mut()
+                .spawn((LevelEntity, moodel.mood, at(moodel.position)));
+        }
+        for obstacle in &level.obstacles {
+            app.world_mut().spawn((
+                Obstacle,
+                ObstacleSource(obstacle.clone()),
+                at(obstacle.position),
+            ));
+        }
+        for zone in &level.goal_zones {
+            app.world_mut().spawn((
+                GoalZone {
+                    requirements: zone.requirements.clone(),
+                    size: zone.size,
+                    hold_duration: zone.hold_duration,
+                    forbidden: zone.forbidden,
+                    ..default()
+                },
+                at(zone.position),
+            ));
+        }
+
+        let path = std::env::temp_dir()
+            .join("mood_save_level_test.level.json")
+            .to_string_lossy()
+            .into_owned();
+        app.world_mut().send_event(SaveLevel(path.clone()));
+        app.world_mut().run_system_once(save_level).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let saved: Level = serde_json::from_str(&saved).unwrap();
+        assert_eq!(
+            serde_json::to_value(&saved).unwrap(),
+            serde_json::to_value(&level).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_json_reports_a_load_failure() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .add_event::<SpawnLevel>()
+            .add_event::<LevelLoadFailed>()
+            .init_resource::<LevelHandle>()
+            .init_resource::<Campaign>();
+        let current: Level =
+            serde_json::from_str(include_str!("../../assets/levels/tutorial_1.level.json"))
+                .unwrap();
+        app.insert_resource(ActiveLevel(Some(current)));
+
+        app.world_mut()
+            .send_event(SpawnLevel::FromJson("{ not a level".to_string()));
+        app.world_mut()
+            .run_system_once(handle_spawn_requests)
+            .unwrap();
+
+        let failures = app.world().resource::<Events<LevelLoadFailed>>();
+        let reasons: Vec<&str> = failures
+            .iter_current_update_events()
+            .map(|LevelLoadFailed(reason)| reason.as_str())
+            .collect();
+        assert_eq!(reasons.len(), 1);
+        assert!(reasons[0].starts_with("The level is malformed"));
+        // The level being played is left alone.
+        let active = app.world().resource::<ActiveLevel>();
+        assert_eq!(active.0.as_ref().unwrap().name, "The Walled Garden");
+    }
+
+    #[test]
+    fn ping_pong_patrol_turns_around_at_both_ends() {
+        let patrol = Patrol {
+            waypoints: vec![Vec2::new(100.0, 0.0), Vec2::new(200.0, 0.0)],
+            speed: 100.0,
+            loop_mode: LoopMode::PingPong,
+        };
+        let mut movement = PatrolMovement::new(Vec2::ZERO, &patrol);
+        let targets: Vec<usize> = (0..6)
+            .map(|_| {
+                movement.advance();
+                movement.target
+            })
+            .collect();
+        assert_eq!(targets, vec![2, 1, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn running_out_of_time_loses_the_level() {
+        let mut level: Level =
+            serde_json::from_str(include_str!("../../assets/levels/tutorial_1.level.json"))
+                .unwrap();
+        level.lose_conditions = vec![LoseCondition::TimeLimit { secs: 30.0 }];
+        let mut app = App::new();
+        app.init_resource::<LevelOutcome>()
+            .init_resource::<NextState<Screen>>()
+            .insert_resource(ActiveLevel(Some(level)))
+            .insert_resource(LevelClock { elapsed: 29.5 });
+
+        app.world_mut()
+            .run_system_once(check_lose_condition)
+            .unwrap();
+        assert_eq!(
+            *app.world().resource::<LevelOutcome>(),
+            LevelOutcome::InProgress
+        );
+
+        app.world_mut().resource_mut::<LevelClock>().elapsed = 30.0;
+        app.world_mut()
+            .run_system_once(check_lose_condition)
+            .unwrap();
+        assert_eq!(*app.world().resource::<LevelOutcome>(), LevelOutcome::Lost);
+        assert!(matches!(
+            app.world().resource::<NextState<Screen>>(),
+            NextState::Pending(Screen::Defeat)
+        ));
+    }
+
+    #[test]
+    fn timed_spawns_arrive_in_order() {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_event::<SpawnMoodel>()
+            .insert_resource(SpawnSchedule {
+                elapsed: 0.0,
+                pending: [(Mood::Happy, 1.0), (Mood::Calm, 2.0), (Mood::Sad, 2.5)]
+                    .map(|(mood, delay_secs)| TimedSpawn {
+                        mood,
+                        position: Vec2::ZERO,
+                        delay_secs,
+                    })
+                    .to_vec(),
+            });
+        let mut step = |secs: f32| {
+            app.world_mut()
+                .resource_mut::<Time>()
+                .advance_by(Duration::from_secs_f32(secs));
+            app.world_mut().run_system_once(run_spawn_schedule).unwrap();
+            app.world_mut()
+                .resource_mut::<Events<SpawnMoodel>>()
+                .drain()
+                .map(|spawn| spawn.mood)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(step(0.5).is_empty());
+        assert_eq!(step(1.0), vec![Mood::Happy]);
+        assert_eq!(step(1.5), vec![Mood::Calm, Mood::Sad]);
+        assert!(app.world().resource::<SpawnSchedule>().pending.is_empty());
+    }
+
+    #[test]
+    fn levels_without_meta_still_load() {
+        let level: Level = serde_json::from_str(
+            r#"{
+                "name": "Old Level",
+                "play_area": [800.0, 600.0],
+                "moodels": [],
+                "obstacles": [],
+                "goal_zones": []
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(level.meta.author, "");
+        assert_eq!(level.meta.difficulty, 0);
+        assert_eq!(level.meta.par_time_secs, None);
+        assert_eq!(level.meta.description, "");
+    }
+
+    #[test]
+    fn despawned_moodels_are_pruned_from_zones() {
+        let mut app = App::new();
+        app.init_resource::<Time>();
+        let staying = app.world_mut().spawn(Mood::Happy).id();
+        let leaving = app.world_mut().spawn(Mood::Happy).id();
+        let zone = app
+            .world_mut()
+            .spawn(GoalZone {
+                requirements: vec![(Mood::Happy, 2)],
+                entities_inside: HashSet::from([staying, leaving]),
+                ..default()
+            })
+            .id();
+        app.world_mut().despawn(leaving);
+
+        let zone = update_zone(&mut app, zone, 0.1);
+        assert_eq!(zone.entities_inside, HashSet::from([staying]));
+        assert_eq!(zone.current_counts, vec![1]);
+        assert!(!zone.is_satisfied);
+    }
+
+    #[test]
+    fn missing_font_falls_back_to_the_default() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Font>()
+            .init_asset_loader::<bevy::text::FontLoader>();
+        let missing: Handle<Font> = app
+            .world()
+            .resource::<AssetServer>()
+            .load("fonts/does_not_exist.ttf");
+        app.insert_resource(FontHandle(missing.clone()));
+        let text = app
+            .world_mut()
+            .spawn(TextFont {
+                font: missing.clone(),
+                ..default()
+            })
+            .id();
+
+        // The load fails in the background, so give it a moment.
+        for _ in 0..200 {
+            app.update();
+            let state = app
+                .world()
+                .resource::<AssetServer>()
+                .get_load_state(&missing);
+            if matches!(state, Some(LoadState::Failed(_))) {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        app.world_mut()
+            .run_system_once(fallback_missing_font)
+            .unwrap();
+
+        assert_eq!(app.world().resource::<FontHandle>().0, Handle::default());
+        assert_eq!(
+            app.world().get::<TextFont>(text).unwrap().font,
+            Handle::default()
+        );
+    }
+
+    #[test]
+    fn spawn_moves_clear_of_obstacles() {
+        let play_area = Vec2::new(800.0, 600.0);
+        let pillar = [ObstacleData {
+            position: Vec2::ZERO,
+            kind: ObstacleKind::Circle { radius: 40.0 },
+            patrol: None,
+        }];
+        // Diagonally off the pillar, clear of the circle though not of its bounding box
+        let clear = Vec2::new(70.0, 70.0);
+        assert_eq!(
+            find_free_spawn_position(clear, &pillar, play_area),
+            Some(clear)
+        );
+
+        let start = Vec2::new(10.0, 0.0);
+        let moved = find_free_spawn_position(start, &pillar, play_area).unwrap();
+        assert!(moved.distance(Vec2::ZERO) >= 40.0 + MOODEL_SPAWN_RADIUS);
+        // The search walks outwards, so it settles on the nearest free ring.
+        assert!(moved.distance(start) <= 40.0 + MOODEL_SPAWN_RADIUS + SPAWN_SEARCH_STEP);
+    }
+
+    #[test]
+    fn no_spawn_position_in_a_fully_blocked_area() {
+        let wall = [ObstacleData {
+            position: Vec2::ZERO,
+            kind: ObstacleKind::Wall {
+                size: Vec2::new(1000.0, 1000.0),
+            },
+            patrol: None,
+        }];
+        assert_eq!(
+            find_free_spawn_position(Vec2::ZERO, &wall, Vec2::new(800.0, 600.0)),
+            None
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! A fluent builder for levels defined in code, as an alternative to level files.
+//!
+//! ```ignore
+//! let level = LevelBuilder::new("Two Rooms")
+//!     .play_area(Vec2::new(900.0, 600.0))
+//!     .moodel(Mood::Happy, Vec2::new(-200.0, 0.0))
+//!     .wall(Vec2::ZERO, Vec2::new(20.0, 300.0))
+//!     .goal(Vec2::new(350.0, 0.0), Vec2::splat(200.0), Mood::Happy, 1)
+//!     .build()?;
+//! ```
+//!
+//! [`LevelBuilder::build`] runs the same validation as level files, so mistakes are caught
+//! where the level is written rather than when it's spawned.
+
+use bevy::prelude::*;
+
+use super::{
+    level::{
+        GoalZoneData, HazardZoneData, Level, LevelMeta, LoseCondition, MoodelData, ObstacleData,
+        ObstacleKind, TimedSpawn, default_hazard_dwell_time,
+    },
+    level_validation::{LevelError, validate_level},
+    mood::Mood,
+    movement::PlayAreaShape,
+};
+
+/// Play area used when [`LevelBuilder::play_area`] isn't called.
+const DEFAULT_PLAY_AREA: Vec2 = Vec2::new(900.0, 600.0);
+
+/// Builds a [`Level`] one piece at a time. See the module docs for an example.
+#[derive(Debug, Clone)]
+pub struct LevelBuilder {
+    level: Level,
+}
+
+impl LevelBuilder {
+    /// Starts an empty level with the default play area.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            level: Level {
+                name: name.into(),
+                meta: LevelMeta::default(),
+                play_area: DEFAULT_PLAY_AREA,
+                play_area_shape: PlayAreaShape::Rect,
+                moodels: Vec::new(),
+                random_moodels: 0,
+                spawn_weights: None,
+                obstacles: Vec::new(),
+                goal_zones: Vec::new(),
+                hazard_zones: Vec::new(),
+                lose_conditions: Vec::new(),
+                spawns: Vec::new(),
+                theme: None,
+                audio_mix: None,
+                music: None,
+            },
+        }
+    }
+
+    pub fn meta(mut self, meta: LevelMeta) -> Self {
+        self.level.meta = meta;
+        self
+    }
+
+    /// Sets the size of the rectangular play area, centered on the origin.
+    pub fn play_area(mut self, size: Vec2) -> Self {
+        self.level.play_area = size;
+        self
+    }
+
+    /// Makes the play area a circle instead of a rectangle.
+    pub fn circular_play_area(mut self, radius: f32) -> Self {
+        self.level.play_area = Vec2::splat(radius * 2.0);
+        self.level.play_area_shape = PlayAreaShape::Circle { radius };
+        self
+    }
+
+    pub fn moodel(mut self, mood: Mood, position: Vec2) -> Self {
+        self.level.moodels.push(MoodelData {
+            mood,
+            position,
+            locked: false,
+        });
+        self
+    }
+
+    /// Adds a Moodel whose mood can't change for the whole level.
+    pub fn locked_moodel(mut self, mood: Mood, position: Vec2) -> Self {
+        self.level.moodels.push(MoodelData {
+            mood,
+            position,
+            locked: true,
+        });
+        self
+    }
+
+    /// Scatters this many Moodels in random moods across the play area.
+    #[allow(dead_code)]
+    pub fn random_moodels(mut self, count: u32) -> Self {
+        self.level.random_moodels = count;
+        self
+    }
+
+    /// Adds a Moodel that joins the level after `delay_secs`.
+    pub fn timed_spawn(mut self, mood: Mood, position: Vec2, delay_secs: f32) -> Self {
+        self.level.spawns.push(TimedSpawn {
+            mood,
+            position,
+            delay_secs,
+        });
+        self
+    }
+
+    pub fn wall(mut self, position: Vec2, size: Vec2) -> Self {
+        self.level.obstacles.push(ObstacleData {
+            position,
+            kind: ObstacleKind::Wall { size },
+            patrol: None,
+        });
+        self
+    }
+
+    pub fn pillar(mut self, position: Vec2, radius: f32) -> Self {
+        self.level.obstacles.push(ObstacleData {
+            position,
+            kind: ObstacleKind::Circle { radius },
+            patrol: None,
+        });
+        self
+    }
+
+    /// Adds any obstacle, e.g. a polygon or one that patrols.
+    #[allow(dead_code)]
+    pub fn obstacle(mut self, obstacle: ObstacleData) -> Self {
+        self.level.obstacles.push(obstacle);
+        self
+    }
+
+    /// Adds a goal zone that wants `count` Moodels in `mood`.
+    pub fn goal(mut self, position: Vec2, size: Vec2, mood: Mood, count: u32) -> Self {
+        self.level.goal_zones.push(GoalZoneData {
+            position,
+            size,
+            requirements: vec![(mood, count)],
+            hold_duration: None,
+            forbidden: false,
+        });
+        self
+    }
+
+    /// Adds any goal zone, e.g. one with several requirements or a hold duration.
+    #[allow(dead_code)]
+    pub fn goal_zone(mut self, zone: GoalZoneData) -> Self {
+        self.level.goal_zones.push(zone);
+        self
+    }
+
+    /// Adds a hazard zone that turns Moodels to `mood` after the default dwell time.
+    pub fn hazard(mut self, position: Vec2, size: Vec2, mood: Mood) -> Self {
+        self.level.hazard_zones.push(HazardZoneData {
+            position,
+            size,
+            mood_effect: mood,
+            dwell_time: default_hazard_dwell_time(),
+        });
+        self
+    }
+
+    pub fn lose_condition(mut self, condition: LoseCondition) -> Self {
+        self.level.lose_conditions.push(condition);
+        self
+    }
+
+    /// Finishes the level, returning every problem that would make it unplayable.
+    pub fn build(self) -> Result<Level, Vec<LevelError>> {
+        validate_level(&self.level)?;
+        Ok(self.level)
+    }
+}
@@ -1,38 +1,202 @@
 //! A library of programmatically-defined levels.
 
-use super::level::{GoalZoneData, Level, MoodelData, ObstacleData, ObstacleKind};
+use super::level::{Level, LevelMeta, LoseCondition};
+use super::level_builder::LevelBuilder;
+use super::level_validation::LevelError;
 use crate::demo::mood::Mood;
 use bevy::prelude::*;
 
-/// Returns a programmatically defined `Level` based on a unique string ID.
-pub fn get_level_by_id(id: &str) -> Option<Level> {
-    match id {
-        "tutorial_code" => Some(create_tutorial_from_code()),
-        _ => None,
+/// Every programmatic level, by id. Listing them in one place keeps the lookup and the
+/// enumeration in sync.
+const PROGRAMMATIC_LEVELS: &[(&str, fn() -> Result<Level, Vec<LevelError>>)] = &[
+    ("tutorial_code", create_tutorial_from_code),
+    ("crossroads", create_crossroads),
+    ("calm_circle", create_calm_circle),
+    ("rage_room", create_rage_room),
+];
+
+/// Returns a programmatically defined `Level` based on a unique string ID, or the problems
+/// that make it unplayable.
+pub fn get_level_by_id(id: &str) -> Option<Result<Level, Vec<LevelError>>> {
+    PROGRAMMATIC_LEVELS
+        .iter()
+        .find(|(level_id, _)| *level_id == id)
+        .map(|(_, create)| create())
+}
+
+/// The ids of every programmatic level, for listing them in menus.
+pub fn available_programmatic_levels() -> Vec<&'static str> {
+    PROGRAMMATIC_LEVELS.iter().map(|(id, _)| *id).collect()
+}
+
+fn meta(difficulty: u8, description: &str) -> LevelMeta {
+    LevelMeta {
+        author: "Mood team".to_string(),
+        difficulty,
+        par_time_secs: None,
+        description: description.to_string(),
     }
 }
 
-fn create_tutorial_from_code() -> Level {
-    Level {
-        name: "Programmatic Tutorial".to_string(),
-        play_area: Vec2::new(900.0, 600.0),
-        moodels: vec![
-            MoodelData {
+fn create_tutorial_from_code() -> Result<Level, Vec<LevelError>> {
+    LevelBuilder::new("Programmatic Tutorial")
+        .meta(meta(
+            1,
+            "A level built in code rather than loaded from a file.",
+        ))
+        .play_area(Vec2::new(900.0, 600.0))
+        .moodel(Mood::Happy, Vec2::new(-200.0, 0.0))
+        .wall(Vec2::new(0.0, 0.0), Vec2::new(20.0, 300.0))
+        .pillar(Vec2::new(-200.0, -200.0), 40.0)
+        .goal(
+            Vec2::new(350.0, 0.0),
+            Vec2::new(200.0, 200.0),
+            Mood::Happy,
+            1,
+        )
+        .build()
+}
+
+/// Four rooms joined through a gap in the middle. The Happy Moodels have to cheer up the
+/// Neutral one on their way to the far corner.
+fn create_crossroads() -> Result<Level, Vec<LevelError>> {
+    LevelBuilder::new("Crossroads")
+        .meta(meta(
+            2,
+            "Four rooms meet in the middle. Bring three happy Moodels to the far corner.",
+        ))
+        .play_area(Vec2::new(1000.0, 700.0))
+        .moodel(Mood::Happy, Vec2::new(-350.0, 250.0))
+        .moodel(Mood::Happy, Vec2::new(-350.0, -250.0))
+        .moodel(Mood::Neutral, Vec2::new(0.0, 0.0))
+        .moodel(Mood::Sad, Vec2::new(350.0, -250.0))
+        .wall(Vec2::new(-300.0, 0.0), Vec2::new(400.0, 20.0))
+        .wall(Vec2::new(300.0, 0.0), Vec2::new(400.0, 20.0))
+        .wall(Vec2::new(0.0, 225.0), Vec2::new(20.0, 250.0))
+        .wall(Vec2::new(0.0, -225.0), Vec2::new(20.0, 250.0))
+        .goal(
+            Vec2::new(350.0, 225.0),
+            Vec2::new(200.0, 150.0),
+            Mood::Happy,
+            3,
+        )
+        .build()
+}
+
+/// A round arena with a Rage Moodel in the middle, which has to be calmed down without
+/// setting everyone else off.
+fn create_calm_circle() -> Result<Level, Vec<LevelError>> {
+    LevelBuilder::new("Calm Circle")
+        .meta(meta(
+            3,
+            "A round arena with an angry Moodel at its heart. Gather two calm Moodels at the top.",
+        ))
+        .circular_play_area(350.0)
+        .moodel(Mood::Rage, Vec2::new(0.0, -150.0))
+        .moodel(Mood::Sad, Vec2::new(-150.0, 100.0))
+        .moodel(Mood::Neutral, Vec2::new(150.0, 100.0))
+        .locked_moodel(Mood::Calm, Vec2::new(0.0, 0.0))
+        .pillar(Vec2::new(-120.0, -60.0), 35.0)
+        .pillar(Vec2::new(120.0, -60.0), 35.0)
+        .goal(
+            Vec2::new(0.0, 220.0),
+            Vec2::new(180.0, 100.0),
+            Mood::Calm,
+            2,
+        )
+        .build()
+}
+
+/// Calm Moodels have to slip past a hazard that enrages them. The level is lost if they
+/// all turn Rage.
+fn create_rage_room() -> Result<Level, Vec<LevelError>> {
+    LevelBuilder::new("Rage Room")
+        .meta(meta(
+            3,
+            "Guide the calm Moodels around the rage hazard before they all lose their temper.",
+        ))
+        .play_area(Vec2::new(900.0, 600.0))
+        .moodel(Mood::Calm, Vec2::new(-300.0, 150.0))
+        .moodel(Mood::Calm, Vec2::new(-300.0, -150.0))
+        .moodel(Mood::Happy, Vec2::new(-300.0, 0.0))
+        .timed_spawn(Mood::Calm, Vec2::new(-380.0, 0.0), 10.0)
+        .hazard(Vec2::new(0.0, 0.0), Vec2::new(150.0, 400.0), Mood::Rage)
+        .goal(
+            Vec2::new(330.0, 0.0),
+            Vec2::new(180.0, 180.0),
+            Mood::Calm,
+            2,
+        )
+        .lose_condition(LoseCondition::AllMoodelsMood { mood: Mood::Rage })
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{
+        level::{GoalZoneData, MoodelData, ObstacleData, ObstacleKind},
+        movement::PlayAreaShape,
+    };
+
+    #[test]
+    fn built_tutorial_matches_the_literal_definition() {
+        let literal = Level {
+            name: "Programmatic Tutorial".to_string(),
+            meta: meta(1, "A level built in code rather than loaded from a file."),
+            play_area: Vec2::new(900.0, 600.0),
+            play_area_shape: PlayAreaShape::Rect,
+            moodels: vec![MoodelData {
                 mood: Mood::Happy,
                 position: Vec2::new(-200.0, 0.0),
-            },
-        ],
-        obstacles: vec![ObstacleData {
-            position: Vec2::new(0.0, 0.0),
-            kind: ObstacleKind::Wall {
-                size: Vec2::new(20.0, 300.0),
-            },
-        }],
-        goal_zones: vec![GoalZoneData {
-            position: Vec2::new(350.0, 0.0),
-            size: Vec2::new(200.0, 200.0),
-            target_mood: Mood::Happy,
-            required_count: 1,
-        }],
+                locked: false,
+            }],
+            random_moodels: 0,
+            spawn_weights: None,
+            obstacles: vec![
+                ObstacleData {
+                    position: Vec2::new(0.0, 0.0),
+                    kind: ObstacleKind::Wall {
+                        size: Vec2::new(20.0, 300.0),
+                    },
+                    patrol: None,
+                },
+                ObstacleData {
+                    position: Vec2::new(-200.0, -200.0),
+                    kind: ObstacleKind::Circle { radius: 40.0 },
+                    patrol: None,
+                },
+            ],
+            goal_zones: vec![GoalZoneData {
+                position: Vec2::new(350.0, 0.0),
+                size: Vec2::new(200.0, 200.0),
+                requirements: vec![(Mood::Happy, 1)],
+                hold_duration: None,
+                forbidden: false,
+            }],
+            hazard_zones: Vec::new(),
+            lose_conditions: Vec::new(),
+            spawns: Vec::new(),
+            theme: None,
+            audio_mix: None,
+            music: None,
+        };
+        let built = create_tutorial_from_code().unwrap();
+        assert_eq!(
+            serde_json::to_value(&built).unwrap(),
+            serde_json::to_value(&literal).unwrap()
+        );
+    }
+
+    #[test]
+    fn every_listed_level_resolves() {
+        for id in available_programmatic_levels() {
+            match get_level_by_id(id) {
+                Some(Ok(_)) => {}
+                Some(Err(errors)) => panic!("level {id} is invalid: {errors:?}"),
+                None => panic!("level {id} is listed but can't be found"),
+            }
+        }
+        assert!(get_level_by_id("no_such_level").is_none());
     }
-}
\ No newline at end of file
+}
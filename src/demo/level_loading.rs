@@ -0,0 +1,121 @@
+//! An overlay with a progress bar, shown while a level file and the assets it needs load.
+//!
+//! Most noticeable on web, where a level can take a while to download. If the level can't be
+//! loaded, the overlay gives way to an error panel with a way back to the menu.
+
+use bevy::{prelude::*, ui::Val::*};
+
+use crate::{
+    AppSystems,
+    asset_tracking::ResourceHandles,
+    demo::level::{LevelHandle, LevelLoadFailed, LevelLoadingState},
+    screens::Screen,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            spawn_loading_overlay
+                .run_if(in_state(Screen::Gameplay).and(resource_added::<LevelLoadingState>)),
+            update_loading_progress.run_if(resource_exists::<LevelLoadingState>),
+            despawn_loading_overlay.run_if(resource_removed::<LevelLoadingState>),
+            spawn_load_error_panel.run_if(on_event::<LevelLoadFailed>),
+        )
+            .chain()
+            .in_set(AppSystems::Update),
+    );
+}
+
+const BAR_SIZE: Vec2 = Vec2::new(320.0, 16.0);
+
+/// The overlay covering the play area while a level loads.
+#[derive(Component)]
+struct LoadingOverlay;
+
+/// The filled part of the progress bar.
+#[derive(Component)]
+struct LoadingProgressFill;
+
+fn spawn_loading_overlay(mut commands: Commands, overlay_query: Query<(), With<LoadingOverlay>>) {
+    // Loading another level before the last one finished reuses the overlay.
+    if !overlay_query.is_empty() {
+        return;
+    }
+    commands.spawn((
+        widget::ui_root("Level Loading Overlay"),
+        LoadingOverlay,
+        GlobalZIndex(1),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        StateScoped(Screen::Gameplay),
+        children![
+            widget::label("Loading level..."),
+            (
+                Name::new("Progress Bar"),
+                Node {
+                    width: Px(BAR_SIZE.x),
+                    height: Px(BAR_SIZE.y),
+                    ..default()
+                },
+                BackgroundColor(ui_palette::BUTTON_PRESSED_BACKGROUND),
+                children![(
+                    Name::new("Progress Fill"),
+                    LoadingProgressFill,
+                    Node {
+                        width: Percent(0.0),
+                        height: Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(ui_palette::BUTTON_HOVERED_BACKGROUND),
+                )],
+            ),
+        ],
+    ));
+}
+
+/// Fills the bar by how many of the level file and the tracked resource assets are ready.
+fn update_loading_progress(
+    asset_server: Res<AssetServer>,
+    level_handle: Res<LevelHandle>,
+    resource_handles: Res<ResourceHandles>,
+    mut fill_query: Query<&mut Node, With<LoadingProgressFill>>,
+) {
+    let level_loaded = asset_server.is_loaded_with_dependencies(&level_handle.0);
+    let finished = resource_handles.finished_count() + level_loaded as usize;
+    let total = resource_handles.total_count() + 1;
+    let progress = finished as f32 / total as f32;
+    for mut node in &mut fill_query {
+        node.width = Percent(progress * 100.0);
+    }
+}
+
+fn despawn_loading_overlay(
+    mut commands: Commands,
+    overlay_query: Query<Entity, With<LoadingOverlay>>,
+) {
+    for entity in &overlay_query {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_load_error_panel(mut commands: Commands, mut ev_failed: EventReader<LevelLoadFailed>) {
+    let Some(LevelLoadFailed(reason)) = ev_failed.read().last() else {
+        return;
+    };
+    commands.spawn((
+        widget::ui_root("Level Load Error"),
+        GlobalZIndex(2),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        StateScoped(Screen::Gameplay),
+        children![
+            widget::header("Couldn't load the level"),
+            widget::label(reason.clone()),
+            widget::button("Back to menu", go_to_title),
+        ],
+    ));
+}
+
+fn go_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
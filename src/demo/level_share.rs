@@ -0,0 +1,318 @@
+//! Share codes: a whole level packed into a short, URL-safe string, so custom levels can be
+//! shared by link.
+//!
+//! A code is a format version byte followed by the level's compressed JSON, all encoded as
+//! URL-safe base64. On web, a link ending in `#level=<code>` plays that level when the player
+//! starts the game. The dev console's `share` command prints the code for the current level.
+
+use std::fmt;
+
+use bevy::prelude::*;
+
+use super::level::Level;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<SharedLevel>();
+
+    #[cfg(target_family = "wasm")]
+    app.add_systems(Startup, read_shared_level_from_url);
+}
+
+/// The first byte of every code. Bump it whenever the encoding changes, so old codes can be
+/// told apart from new ones.
+const FORMAT_VERSION: u8 = 1;
+
+/// The URL fragment prefix that marks a share code, as in `#level=<code>`.
+#[cfg_attr(not(any(feature = "dev", target_family = "wasm")), allow(dead_code))]
+pub const URL_FRAGMENT_PREFIX: &str = "#level=";
+
+/// A level that was shared with the player, as JSON, waiting to be played instead of the next
+/// campaign level.
+#[derive(Resource, Debug, Default)]
+pub struct SharedLevel(pub Option<String>);
+
+/// Why a share code couldn't be turned back into a level.
+#[derive(Debug)]
+pub enum ShareCodeError {
+    /// The code has characters that aren't URL-safe base64.
+    InvalidBase64,
+    /// The code was made by a different version of the game.
+    UnknownVersion(u8),
+    /// The code was cut short or altered.
+    Corrupt,
+    /// The code decoded fine, but not to a level.
+    InvalidLevel(serde_json::Error),
+}
+
+impl fmt::Display for ShareCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareCodeError::InvalidBase64 => write!(f, "the code isn't valid base64"),
+            ShareCodeError::UnknownVersion(version) => {
+                write!(f, "the code uses an unknown format (version {version})")
+            }
+            ShareCodeError::Corrupt => write!(f, "the code is incomplete or corrupt"),
+            ShareCodeError::InvalidLevel(error) => write!(f, "the code isn't a level: {error}"),
+        }
+    }
+}
+
+/// Packs a level into a share code.
+#[cfg_attr(not(feature = "dev"), allow(dead_code))]
+pub fn encode_level(level: &Level) -> String {
+    let json = serde_json::to_vec(level).expect("levels always serialize to JSON");
+    let mut bytes = vec![FORMAT_VERSION];
+    bytes.extend(compress(&json));
+    base64_encode(&bytes)
+}
+
+/// Unpacks a level from a share code made by [`encode_level`].
+#[cfg_attr(not(feature = "dev"), allow(dead_code))]
+pub fn decode_level(code: &str) -> Result<Level, ShareCodeError> {
+    let json = decode_json(code)?;
+    serde_json::from_str(&json).map_err(ShareCodeError::InvalidLevel)
+}
+
+/// Unpacks a share code to the level's JSON, without parsing it.
+#[cfg_attr(not(any(feature = "dev", target_family = "wasm")), allow(dead_code))]
+pub fn decode_json(code: &str) -> Result<String, ShareCodeError> {
+    let bytes = base64_decode(code.trim()).ok_or(ShareCodeError::InvalidBase64)?;
+    let (&version, compressed) = bytes.split_first().ok_or(ShareCodeError::Corrupt)?;
+    if version != FORMAT_VERSION {
+        return Err(ShareCodeError::UnknownVersion(version));
+    }
+    let json = decompress(compressed).ok_or(ShareCodeError::Corrupt)?;
+    String::from_utf8(json).map_err(|_| ShareCodeError::Corrupt)
+}
+
+/// Queues the level in the URL fragment, if there is one, to be played when gameplay starts.
+#[cfg(target_family = "wasm")]
+fn read_shared_level_from_url(mut shared_level: ResMut<SharedLevel>) {
+    let Some(hash) = web_sys::window().and_then(|window| window.location().hash().ok()) else {
+        return;
+    };
+    let Some(code) = hash.strip_prefix(URL_FRAGMENT_PREFIX) else {
+        return;
+    };
+    match decode_json(code) {
+        Ok(json) => {
+            info!("Found a shared level in the URL");
+            shared_level.0 = Some(json);
+        }
+        Err(error) => warn!("Ignoring the shared level in the URL: {}", error),
+    }
+}
+
+// --- Compression ---
+//
+// A tiny LZ77 variant. Level JSON repeats the same keys over and over, so back-references
+// alone shrink it a lot. The stream is a sequence of chunks, each starting with a tag byte:
+// - `0..=127`: the next `tag + 1` bytes are copied as-is.
+// - `128..=255`: copy `(tag & 127) + MIN_MATCH` bytes from earlier in the output, starting
+//   the number of bytes back given by the following little-endian `u16`.
+
+const MIN_MATCH: usize = 4;
+const MAX_MATCH: usize = 127 + MIN_MATCH;
+const MAX_LITERALS: usize = 128;
+/// How far back to look for matches. Levels are small, so a plain search is fast enough.
+const WINDOW: usize = 4096;
+
+fn compress(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() / 2);
+    let mut literals_start = 0;
+    let mut i = 0;
+    while i < input.len() {
+        let (length, distance) = longest_match(input, i);
+        if length < MIN_MATCH {
+            i += 1;
+            continue;
+        }
+        push_literals(&mut out, &input[literals_start..i]);
+        out.push(0x80 | (length - MIN_MATCH) as u8);
+        out.extend((distance as u16).to_le_bytes());
+        i += length;
+        literals_start = i;
+    }
+    push_literals(&mut out, &input[literals_start..]);
+    out
+}
+
+/// Finds the longest earlier run of bytes matching those at `position`, returning its length
+/// and how far back it starts.
+fn longest_match(input: &[u8], position: usize) -> (usize, usize) {
+    let mut best = (0, 0);
+    for start in position.saturating_sub(WINDOW)..position {
+        let length = input[position..]
+            .iter()
+            .zip(&input[start..])
+            .take(MAX_MATCH)
+            .take_while(|(a, b)| a == b)
+            .count();
+        if length > best.0 {
+            best = (length, position - start);
+        }
+    }
+    best
+}
+
+fn push_literals(out: &mut Vec<u8>, literals: &[u8]) {
+    for chunk in literals.chunks(MAX_LITERALS) {
+        out.push((chunk.len() - 1) as u8);
+        out.extend(chunk);
+    }
+}
+
+fn decompress(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 2);
+    let mut i = 0;
+    while let Some(&tag) = input.get(i) {
+        i += 1;
+        if tag < 0x80 {
+            let length = tag as usize + 1;
+            out.extend(input.get(i..i + length)?);
+            i += length;
+        } else {
+            let length = (tag & 0x7f) as usize + MIN_MATCH;
+            let distance = u16::from_le_bytes([*input.get(i)?, *input.get(i + 1)?]) as usize;
+            i += 2;
+            if distance == 0 || distance > out.len() {
+                return None;
+            }
+            // Byte by byte, since a match may overlap the bytes it produces.
+            let start = out.len() - distance;
+            for offset in 0..length {
+                out.push(out[start + offset]);
+            }
+        }
+    }
+    Some(out)
+}
+
+// --- Base64 ---
+//
+// The URL-safe alphabet without padding, so codes can go straight into a link.
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | ((byte as u32) << (16 - 8 * i))
+        });
+        // Each input byte spills into one more output character.
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - 6 * i)) & 0x3f;
+            out.push(BASE64_ALPHABET[index as usize] as char);
+        }
+    }
+    out
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    let mut bits = 0u32;
+    let mut bit_count = 0;
+    for character in text.bytes() {
+        let value = BASE64_ALPHABET.iter().position(|&c| c == character)?;
+        bits = (bits << 6) | value as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push((bits >> bit_count) as u8);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{
+        campaign::Campaign, level_library::get_level_by_id, level_validation::check_level,
+    };
+
+    fn test_level() -> Level {
+        get_level_by_id("crossroads")
+            .expect("the level exists")
+            .expect("the level is valid")
+    }
+
+    #[test]
+    fn round_trip() {
+        let level = test_level();
+        let decoded = decode_level(&encode_level(&level)).unwrap();
+        // Levels don't implement `PartialEq`, so compare them as JSON.
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&level).unwrap()
+        );
+    }
+
+    #[test]
+    fn shared_campaign_level_is_accepted() {
+        let json = include_str!("../../assets/levels/tutorial_1.level.json");
+        let level: Level = serde_json::from_str(json).unwrap();
+        // The same steps `SpawnLevel::FromJson` takes, which has no level id to go by
+        let shared = decode_json(&encode_level(&level)).unwrap();
+        let decoded: Level = serde_json::from_str(&shared).unwrap();
+        assert!(check_level(&decoded, None, &Campaign::default()));
+    }
+
+    #[test]
+    fn surrounding_whitespace_is_ignored() {
+        let code = format!("  {}\n", encode_level(&test_level()));
+        assert!(decode_level(&code).is_ok());
+    }
+
+    #[test]
+    fn compression_round_trip() {
+        // Long runs produce matches that overlap the bytes they copy.
+        let input = [
+            b"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".as_slice(),
+            &[0, 1, 2, 255],
+        ]
+        .concat()
+        .repeat(20);
+        let compressed = compress(&input);
+        assert!(compressed.len() < input.len());
+        assert_eq!(decompress(&compressed).unwrap(), input);
+        assert_eq!(decompress(&[]).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        for length in 0..10 {
+            let bytes: Vec<u8> = (0..length).map(|i| (i * 97) as u8).collect();
+            assert_eq!(base64_decode(&base64_encode(&bytes)).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn truncated_code() {
+        let code = encode_level(&test_level());
+        for length in [1, code.len() / 2, code.len() - 3] {
+            assert!(decode_level(&code[..length]).is_err());
+        }
+        assert!(matches!(decode_level(""), Err(ShareCodeError::Corrupt)));
+    }
+
+    #[test]
+    fn unknown_version() {
+        let mut bytes = vec![FORMAT_VERSION + 1];
+        bytes.extend(compress(b"{}"));
+        assert!(matches!(
+            decode_level(&base64_encode(&bytes)),
+            Err(ShareCodeError::UnknownVersion(version)) if version == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn invalid_base64() {
+        assert!(matches!(
+            decode_level("not base64!"),
+            Err(ShareCodeError::InvalidBase64)
+        ));
+    }
+}
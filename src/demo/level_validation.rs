@@ -0,0 +1,309 @@
+//! Sanity checks run on a level before it is spawned, so a broken level file is
+//! reported clearly instead of loading into an unwinnable state.
+
+use bevy::prelude::*;
+use std::fmt;
+
+use super::{
+    campaign::Campaign,
+    level::{Level, ObstacleKind},
+    level_library::available_programmatic_levels,
+    movement::{PlayArea, PlayAreaShape},
+};
+
+/// A problem that makes a level unplayable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LevelError {
+    EmptyName,
+    /// Another level is already known by this level's name.
+    DuplicateName {
+        name: String,
+        other: String,
+    },
+    /// A goal zone lists no moods, so it would be satisfied from the start.
+    NoRequirements {
+        zone: usize,
+    },
+    /// A goal zone asks for more Moodels than the level has in total, counting timed spawns.
+    NotEnoughMoodels {
+        zone: usize,
+        required: u32,
+        available: u32,
+    },
+    /// A Moodel, obstacle or zone is placed outside the play area.
+    OutOfBounds {
+        what: String,
+        position: Vec2,
+    },
+    /// A wall, zone or pillar has no area.
+    ZeroSize {
+        what: String,
+    },
+}
+
+impl fmt::Display for LevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelError::EmptyName => write!(f, "the level has no name"),
+            LevelError::DuplicateName { name, other } => {
+                write!(f, "the name \"{name}\" is already used by {other}")
+            }
+            LevelError::NoRequirements { zone } => {
+                write!(f, "goal zone {zone} has no requirements")
+            }
+            LevelError::NotEnoughMoodels {
+                zone,
+                required,
+                available,
+            } => write!(
+                f,
+                "goal zone {zone} requires {required} Moodels but the level only has {available}"
+            ),
+            LevelError::OutOfBounds { what, position } => {
+                write!(f, "{what} at {position} is outside the play area")
+            }
+            LevelError::ZeroSize { what } => write!(f, "{what} has zero size"),
+        }
+    }
+}
+
+/// Checks a level for mistakes that would make it unplayable, returning all of them at once.
+///
+/// Goal zones are checked against the total number of Moodels rather than the Moodels that
+/// start in the right mood, since converting moods is the point of the game.
+pub fn validate_level(level: &Level) -> Result<(), Vec<LevelError>> {
+    let mut errors = Vec::new();
+
+    if level.name.trim().is_empty() {
+        errors.push(LevelError::EmptyName);
+    }
+
+    if matches!(level.play_area_shape, PlayAreaShape::Circle { radius } if radius <= 0.0) {
+        errors.push(LevelError::ZeroSize {
+            what: "the circular play area".to_string(),
+        });
+    }
+
+    let play_area = PlayArea {
+        center: Vec2::ZERO,
+        size: level.play_area,
+        shape: level.play_area_shape,
+    };
+    let check_bounds = |what: String, position: Vec2, errors: &mut Vec<LevelError>| {
+        if !play_area.contains(position) {
+            errors.push(LevelError::OutOfBounds { what, position });
+        }
+    };
+
+    for (index, moodel) in level.moodels.iter().enumerate() {
+        check_bounds(format!("Moodel {index}"), moodel.position, &mut errors);
+    }
+    for (index, spawn) in level.spawns.iter().enumerate() {
+        check_bounds(format!("timed spawn {index}"), spawn.position, &mut errors);
+    }
+
+    for (index, obstacle) in level.obstacles.iter().enumerate() {
+        let what = format!("obstacle {index}");
+        check_bounds(what.clone(), obstacle.position, &mut errors);
+        let has_area = match &obstacle.kind {
+            ObstacleKind::Wall { size } => size.x > 0.0 && size.y > 0.0,
+            ObstacleKind::Circle { radius } => *radius > 0.0,
+            // Degenerate polygons are reported in detail when they are spawned.
+            ObstacleKind::Polygon { .. } => true,
+        };
+        if !has_area {
+            errors.push(LevelError::ZeroSize { what });
+        }
+    }
+
+    let moodel_count = (level.moodels.len() + level.spawns.len()) as u32 + level.random_moodels;
+    for (index, zone) in level.goal_zones.iter().enumerate() {
+        let what = format!("goal zone {index}");
+        check_bounds(what.clone(), zone.position, &mut errors);
+        if zone.size.x <= 0.0 || zone.size.y <= 0.0 {
+            errors.push(LevelError::ZeroSize { what });
+        }
+        if zone.requirements.is_empty() {
+            errors.push(LevelError::NoRequirements { zone: index });
+        }
+        if zone.forbidden {
+            continue;
+        }
+        let required = zone.requirements.iter().map(|(_, count)| count).sum();
+        if required > moodel_count {
+            errors.push(LevelError::NotEnoughMoodels {
+                zone: index,
+                required,
+                available: moodel_count,
+            });
+        }
+    }
+
+    for (index, hazard) in level.hazard_zones.iter().enumerate() {
+        let what = format!("hazard zone {index}");
+        check_bounds(what.clone(), hazard.position, &mut errors);
+        if hazard.size.x <= 0.0 || hazard.size.y <= 0.0 {
+            errors.push(LevelError::ZeroSize { what });
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that no other level is known by `level`'s name. `known` lists other levels as
+/// `(id, name)` pairs, where the id is a file path or programmatic level id. `id` is the
+/// level's own id, so it isn't compared with itself.
+pub fn validate_level_name<'a>(
+    level: &Level,
+    id: &str,
+    known: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<(), LevelError> {
+    let name = level.name.trim();
+    let duplicate = known.into_iter().find(|(other_id, other_name)| {
+        *other_id != id && other_name.trim().eq_ignore_ascii_case(name)
+    });
+    match duplicate {
+        Some((other_id, _)) => Err(LevelError::DuplicateName {
+            name: level.name.clone(),
+            other: other_id.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// The levels a level's name must not clash with: campaign levels by their manifest name, and
+/// programmatic levels by their id.
+fn known_level_names(campaign: &Campaign) -> impl Iterator<Item = (&str, &str)> {
+    campaign
+        .levels
+        .iter()
+        .map(|level| (level.path.as_str(), level.name.as_str()))
+        .chain(
+            available_programmatic_levels()
+                .into_iter()
+                .map(|id| (id, id)),
+        )
+}
+
+/// Logs every problem with a level, returning whether it is safe to spawn. `id` is the level's
+/// file path or programmatic id, if it has one.
+///
+/// Only levels that ship with the game must have unique names. Shared and saved levels are
+/// often copies of a campaign level, so their names aren't checked.
+pub fn check_level(level: &Level, id: Option<&str>, campaign: &Campaign) -> bool {
+    let mut errors = validate_level(level).err().unwrap_or_default();
+    let shipped_id =
+        id.filter(|id| known_level_names(campaign).any(|(other_id, _)| other_id == *id));
+    if let Some(id) = shipped_id {
+        if let Err(error) = validate_level_name(level, id, known_level_names(campaign)) {
+            errors.push(error);
+        }
+    }
+    if errors.is_empty() {
+        return true;
+    }
+    report_level_errors(&level.name, &errors);
+    false
+}
+
+/// Logs the problems that stop the named level from spawning.
+pub fn report_level_errors(name: &str, errors: &[LevelError]) {
+    error!(
+        "Refusing to spawn level \"{}\", it has {} problem(s):",
+        name,
+        errors.len()
+    );
+    for error in errors {
+        error!("  - {}", error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::{level::GoalZoneData, level_builder::LevelBuilder, mood::Mood};
+
+    fn errors(builder: LevelBuilder) -> Vec<LevelError> {
+        builder.build().expect_err("the level should be invalid")
+    }
+
+    #[test]
+    fn empty_name() {
+        let builder = LevelBuilder::new(" ").moodel(Mood::Happy, Vec2::ZERO);
+        assert_eq!(errors(builder), vec![LevelError::EmptyName]);
+    }
+
+    #[test]
+    fn not_enough_moodels() {
+        let builder = LevelBuilder::new("Test")
+            .moodel(Mood::Happy, Vec2::ZERO)
+            .goal(Vec2::ZERO, Vec2::splat(100.0), Mood::Happy, 2);
+        assert_eq!(
+            errors(builder),
+            vec![LevelError::NotEnoughMoodels {
+                zone: 0,
+                required: 2,
+                available: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn no_requirements() {
+        // Neither `requirements` nor the older `target_mood` is set
+        let zone: GoalZoneData =
+            serde_json::from_str(r#"{ "position": [0.0, 0.0], "size": [100.0, 100.0] }"#).unwrap();
+        assert!(zone.requirements.is_empty());
+        let builder = LevelBuilder::new("Test").goal_zone(zone);
+        assert_eq!(
+            errors(builder),
+            vec![LevelError::NoRequirements { zone: 0 }]
+        );
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let position = Vec2::new(1000.0, 0.0);
+        let builder = LevelBuilder::new("Test").moodel(Mood::Happy, position);
+        assert_eq!(
+            errors(builder),
+            vec![LevelError::OutOfBounds {
+                what: "Moodel 0".to_string(),
+                position,
+            }]
+        );
+    }
+
+    #[test]
+    fn zero_size() {
+        let builder = LevelBuilder::new("Test").wall(Vec2::ZERO, Vec2::new(0.0, 10.0));
+        assert_eq!(
+            errors(builder),
+            vec![LevelError::ZeroSize {
+                what: "obstacle 0".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn duplicate_name() {
+        let level = LevelBuilder::new("Two Rooms").build().unwrap();
+        let known = [("levels/tutorial_2.level.json", "Two Rooms")];
+        assert_eq!(
+            validate_level_name(&level, "programmatic_level", known),
+            Err(LevelError::DuplicateName {
+                name: "Two Rooms".to_string(),
+                other: "levels/tutorial_2.level.json".to_string(),
+            })
+        );
+        // A level doesn't clash with itself
+        assert_eq!(
+            validate_level_name(&level, "levels/tutorial_2.level.json", known),
+            Ok(())
+        );
+    }
+}
@@ -7,21 +7,62 @@ use bevy::prelude::*;
 
 pub mod ai;
 mod animation;
+pub mod camera;
+pub mod campaign;
+pub mod culling;
+pub mod edit_history;
+mod gamepad_control;
+mod keyboard_control;
 pub mod level;
-mod level_library;
+pub mod level_builder;
+pub mod level_library;
+mod level_loading;
+pub mod level_share;
+pub mod level_validation;
 pub mod mood;
-mod movement;
+pub mod mood_badges;
+pub mod mood_labels;
+pub mod mood_rules;
+pub mod movement;
 pub mod player;
-mod player_input;
+pub mod player_input;
+pub mod replay;
+pub mod rng;
+pub mod spatial_grid;
+pub mod stats;
+pub mod timeline;
+pub mod trail;
+pub mod victory;
 
 pub(super) fn plugin(app: &mut App) {
     app.add_plugins((
         ai::plugin,
         animation::plugin,
+        campaign::plugin,
+        culling::plugin,
+        edit_history::plugin,
+        keyboard_control::plugin,
         level::plugin,
         mood::plugin,
+        mood_rules::plugin,
         movement::plugin,
         player::plugin,
         player_input::plugin,
+        spatial_grid::plugin,
+        timeline::plugin,
+        victory::plugin,
+    ));
+    // Plugin tuples hold at most 15 plugins.
+    app.add_plugins((
+        camera::plugin,
+        gamepad_control::plugin,
+        level_loading::plugin,
+        level_share::plugin,
+        mood_badges::plugin,
+        mood_labels::plugin,
+        replay::plugin,
+        rng::plugin,
+        stats::plugin,
+        trail::plugin,
     ));
 }
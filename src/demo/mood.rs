@@ -1,6 +1,7 @@
 //! Mood system for Moodels - defines different emotional states and conversion logic.
 
 use avian2d::prelude::*;
+use bevy::asset::LoadState;
 use bevy::ecs::system::entity_command::observe;
 use bevy::prelude::*;
 use bevy_picking::prelude::{Click, Pickable, Pointer};
@@ -8,76 +9,35 @@ use rand::Rng;
 use std::collections::HashMap;
 
 use crate::demo::ai::{AiAction, AiConfig, AiEntity, AiMagnetism, AiWanderState};
-use crate::demo::movement::{MovementController, MovementSmoothing, PlayAreaBounded};
-use crate::demo::player_input::{Selectable, Selected};
+use crate::demo::mood_rules::MoodRules;
+use crate::demo::movement::{EaseKind, MovementController, MovementSmoothing, PlayAreaBounded};
+use crate::demo::player_input::{CommandMoodelEvent, Selectable, Selected};
+use crate::demo::rng::GameRng;
+use crate::demo::spatial_grid::SpatialGrid;
 use crate::{
-    AppSystems, COLLISION_LAYER_MOODEL, COLLISION_LAYER_OBSTACLE, PausableSystems,
+    AppSystems, COLLISION_LAYER_MOODEL, COLLISION_LAYER_OBSTACLE, COLLISION_LAYER_TRIGGER,
+    PausableSystems,
     asset_tracking::LoadResource,
+    keybindings::{Action, action_just_pressed},
+    screens::Screen,
 };
 
-/// Macro to define mood interactions in a clear, non-repetitive, and correct way.
-///
-/// This macro generates the `get_mood_interaction` function. It takes rules
-/// organized into blocks for maximum clarity and correctness:
-///
-/// - `one_way`: Defines an asymmetrical rule. `(A, B) => (C, D)` will automatically
-///   generate the reverse interaction: `(B, A) => (D, C)`. Use this when one
-///   mood "acts on" another.
-///
-/// - `two_way`: Defines a symmetrical rule for two *different* moods.
-///   `(A, B) => (C, D)` generates rules for both `(A, B)` and `(B, A)` to
-///   produce the same result `(C, D)`. This is for interactions where the
-///   outcome is the same regardless of order.
-///
-/// - `self_interaction`: Defines rules for when a mood interacts with itself.
-///   A rule like `(Rage) => (Rage)` expands to `(Rage, Rage) => (Rage, Rage)`.
-///   This prevents duplicate match arms that would otherwise be generated.
-///
-/// - `neutral_case`: A block to handle the special `(Neutral, Neutral)` interaction.
-macro_rules! define_get_mood_interaction {
-    (
-        $mood_enum:path;
-        one_way { $( ($m1a:ident, $m2a:ident) => ($r1a:ident, $r2a:ident) ),* $(,)? }
-        two_way { $( ($m1s:ident, $m2s:ident) => ($r1s:ident, $r2s:ident) ),* $(,)? }
-        self_interaction { $( ($m_self:ident) => ($r_self:ident) ),* $(,)? }
-        neutral_case $neutral_block:block
-    ) => {
-        /// Determines how two moods interact when they collide.
-        /// Generated by the `define_get_mood_interaction!` macro.
-        fn get_mood_interaction(mood1: $mood_enum, mood2: $mood_enum) -> ($mood_enum, $mood_enum) {
-            use $mood_enum::*;
-            // rand::Rng is already in scope from the top of the file via `use rand::Rng;`
-
-            match (mood1, mood2) {
-                // Expand `one_way` rules: (A, B) => (C, D) also creates (B, A) => (D, C)
-                $(
-                    ($m1a, $m2a) => ($r1a, $r2a),
-                    ($m2a, $m1a) => ($r2a, $r1a),
-                )*
-
-                // Expand `two_way` rules: (A, B) => (C, D) creates both directions.
-                $(
-                    ($m1s, $m2s) => ($r1s, $r2s),
-                    ($m2s, $m1s) => ($r1s, $r2s),
-                )*
-
-                // Expand `self_interaction` rules: (A) => (B) becomes (A, A) => (B, B)
-                $(
-                    ($m_self, $m_self) => ($r_self, $r_self),
-                )*
-
-                // The special case for two Neutral Moodels colliding
-                (Neutral, Neutral) => $neutral_block,
-            }
-        }
-    };
-}
 pub(super) fn plugin(app: &mut App) {
     app.register_type::<Mood>();
     app.register_type::<MoodAssets>();
     app.register_type::<MoodObject>();
+    app.register_type::<MoodSettlingConfig>();
+    app.register_type::<Settled>();
+    app.register_type::<MoodLock>();
+    app.register_type::<MoodContagion>();
+    app.register_type::<ColorScheme>();
+    app.init_resource::<ColorScheme>();
+    app.init_resource::<MoodContagion>();
     app.load_resource::<MoodAssets>();
     app.init_resource::<MoodStatsTimer>();
+    app.init_resource::<MoodSettlingConfig>();
+    app.add_event::<SetMood>();
+    app.add_event::<MoodChanged>();
 
     // Selection logic is now self-contained in spawn_moodel_bundle
 
@@ -86,16 +46,61 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             handle_collision_events,
+            apply_mood_impacts,
             handle_mood_object_collisions,
             handle_isolation_decay,
+            spread_mood_contagion.run_if(contagion_enabled),
+            apply_set_mood_events,
+            disturb_commanded_moodels,
+            fallback_missing_fear_sprite,
             log_mood_statistics,
             // update_mood_natural_progression, // Disabled cyclical progression
         )
             .in_set(AppSystems::Update)
             .in_set(PausableSystems),
     );
+
+    // Keep physical properties in sync with the current mood
+    app.add_systems(
+        Update,
+        sync_mood_physics
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+
+    // Locked moods, which collisions and decay can't change
+    app.add_systems(
+        Update,
+        (
+            toggle_mood_lock
+                .run_if(action_just_pressed(Action::LockMood).and(in_state(Screen::Gameplay)))
+                .in_set(AppSystems::RecordInput),
+            (tick_mood_locks, draw_mood_locks).in_set(AppSystems::Update),
+        )
+            .in_set(PausableSystems),
+    );
+
+    // Tint Moodels by mood, even while paused so color scheme changes show behind the menu
+    app.add_systems(Update, tint_moodels.in_set(AppSystems::Update));
+
+    // Show a glow on Moodels whose mood has settled
+    app.add_systems(
+        Update,
+        update_settled_glow
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
 }
 
+/// Base impulse applied to a Moodel when it is bumped, before scaling by the other mood's force
+const IMPACT_BASE_IMPULSE: f32 = 250.0;
+/// Minimum seconds between two knockbacks received by the same Moodel
+const IMPACT_COOLDOWN_SECS: f32 = 0.3;
+/// Minimum seconds between two collision sounds involving the same Moodel
+const COLLISION_SOUND_COOLDOWN_SECS: f32 = 0.25;
+/// Seconds a mood must last to reach full intensity
+const INTENSITY_RAMP_SECS: f32 = 15.0;
+
 // Selection logic moved to spawn_moodel_bundle for self-contained entity configuration
 
 /// The different emotional states a Moodel can have
@@ -118,6 +123,39 @@ pub enum Mood {
     Happy,
     Rage,
     Sad,
+    Fear,
+}
+
+/// The set of colors moods are drawn in, chosen in the settings menu
+#[derive(Resource, Reflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource)]
+pub enum ColorScheme {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+    HighContrast,
+}
+
+impl ColorScheme {
+    pub fn all() -> [ColorScheme; 4] {
+        [
+            ColorScheme::Default,
+            ColorScheme::Deuteranopia,
+            ColorScheme::Protanopia,
+            ColorScheme::HighContrast,
+        ]
+    }
+
+    /// The name shown in the settings menu
+    pub fn name(self) -> &'static str {
+        match self {
+            ColorScheme::Default => "Default",
+            ColorScheme::Deuteranopia => "Deuteranopia",
+            ColorScheme::Protanopia => "Protanopia",
+            ColorScheme::HighContrast => "High Contrast",
+        }
+    }
 }
 
 impl Mood {
@@ -129,18 +167,168 @@ impl Mood {
             Mood::Happy => 1.0,    // 200 units/second baseline
             Mood::Rage => 1.5,     // 300 units/second baseline
             Mood::Sad => 0.375,    // 75 units/second baseline
+            Mood::Fear => 1.3,     // 260 units/second baseline
         }
     }
 
-    /// Get the color associated with this mood
+    /// Get the color associated with this mood in the default color scheme
     pub fn color(self) -> Color {
+        self.color_for_scheme(ColorScheme::Default)
+    }
+
+    /// Get the color associated with this mood in a color scheme
+    pub fn color_for_scheme(self, scheme: ColorScheme) -> Color {
+        match scheme {
+            ColorScheme::Default => match self {
+                Mood::Neutral => Color::srgb(0.8, 0.8, 0.8), // Gray
+                Mood::Calm => Color::srgb(0.3, 0.6, 1.0),    // Blue
+                Mood::Happy => Color::srgb(1.0, 0.9, 0.2),   // Yellow
+                Mood::Rage => Color::srgb(1.0, 0.2, 0.2),    // Red
+                Mood::Sad => Color::srgb(0.6, 0.4, 0.8),     // Purple
+                Mood::Fear => Color::srgb(0.4, 0.85, 0.5),   // Pale green
+            },
+            // Red and green are avoided, so moods differ in blue-yellow and lightness
+            ColorScheme::Deuteranopia => match self {
+                Mood::Neutral => Color::srgb(0.8, 0.8, 0.8),  // Gray
+                Mood::Calm => Color::srgb(0.0, 0.45, 0.7),    // Blue
+                Mood::Happy => Color::srgb(0.94, 0.89, 0.26), // Yellow
+                Mood::Rage => Color::srgb(0.84, 0.37, 0.0),   // Vermillion
+                Mood::Sad => Color::srgb(0.8, 0.47, 0.65),    // Reddish purple
+                Mood::Fear => Color::srgb(0.34, 0.71, 0.91),  // Sky blue
+            },
+            // Reds look dark to protanopes, so Rage is a bright orange instead
+            ColorScheme::Protanopia => match self {
+                Mood::Neutral => Color::srgb(0.8, 0.8, 0.8),  // Gray
+                Mood::Calm => Color::srgb(0.0, 0.45, 0.7),    // Blue
+                Mood::Happy => Color::srgb(0.94, 0.89, 0.26), // Yellow
+                Mood::Rage => Color::srgb(0.9, 0.6, 0.0),     // Orange
+                Mood::Sad => Color::srgb(0.35, 0.2, 0.55),    // Dark purple
+                Mood::Fear => Color::srgb(0.0, 0.62, 0.45),   // Bluish green
+            },
+            ColorScheme::HighContrast => match self {
+                Mood::Neutral => Color::WHITE,
+                Mood::Calm => Color::srgb(0.0, 0.4, 1.0), // Blue
+                Mood::Happy => Color::srgb(1.0, 1.0, 0.0), // Yellow
+                Mood::Rage => Color::srgb(1.0, 0.0, 0.0), // Red
+                Mood::Sad => Color::srgb(0.6, 0.0, 1.0),  // Violet
+                Mood::Fear => Color::srgb(0.0, 1.0, 0.4), // Green
+            },
+        }
+    }
+
+    /// Get the mood color at an intensity from 0.0 (the base color) to 1.0 (deeper and more saturated)
+    pub fn color_at_intensity(self, scheme: ColorScheme, t: f32) -> Color {
+        let base = Hsla::from(self.color_for_scheme(scheme));
+        let t = t.clamp(0.0, 1.0);
+        Hsla::new(
+            base.hue,
+            base.saturation.lerp(1.0, t * 0.5),
+            base.lightness * (1.0 - t * 0.25),
+            base.alpha,
+        )
+        .into()
+    }
+
+    /// Get how hard a Moodel in this mood shoves others it bumps into
+    pub fn impact_force(self) -> f32 {
+        match self {
+            Mood::Neutral => 1.0,
+            Mood::Calm => 0.5,  // Calm barely nudges
+            Mood::Happy => 1.2, // Happy bumps playfully
+            Mood::Rage => 3.0,  // Rage slams into others
+            Mood::Sad => 0.4,   // Sad just drifts into others
+            Mood::Fear => 0.6,  // Fear flinches away on contact
+        }
+    }
+
+    /// Get the physical mass of a Moodel in this mood
+    pub fn mass(self) -> f32 {
+        match self {
+            Mood::Neutral => 2.0,
+            Mood::Calm => 2.5, // Calm is grounded and hard to push around
+            Mood::Happy => 1.5,
+            Mood::Rage => 3.0, // Rage is heavy so it keeps its momentum
+            Mood::Sad => 2.5,
+            Mood::Fear => 1.5, // Fear is skittish and easily pushed
+        }
+    }
+
+    /// Get the bounciness of a Moodel in this mood
+    pub fn restitution(self) -> f32 {
         match self {
-            Mood::Neutral => Color::srgb(0.8, 0.8, 0.8), // Gray
-            Mood::Calm => Color::srgb(0.3, 0.6, 1.0),    // Blue
-            Mood::Happy => Color::srgb(1.0, 0.9, 0.2),   // Yellow
-            Mood::Rage => Color::srgb(1.0, 0.2, 0.2),    // Red
-            Mood::Sad => Color::srgb(0.6, 0.4, 0.8),     // Purple
+            Mood::Happy => 0.9, // Happy moodels bounce more
+            Mood::Rage => 0.7,  // Rage moodels have harder bounces
+            Mood::Sad => 0.3,   // Sad moodels barely bounce
+            _ => 0.6,           // Default bounce
+        }
+    }
+}
+
+impl Mood {
+    /// Every mood, in declaration order
+    pub fn all() -> [Mood; 6] {
+        [
+            Mood::Neutral,
+            Mood::Calm,
+            Mood::Happy,
+            Mood::Rage,
+            Mood::Sad,
+            Mood::Fear,
+        ]
+    }
+
+    /// Look up a mood by its name, ignoring case
+    pub fn from_name(name: &str) -> Option<Mood> {
+        Mood::all()
+            .into_iter()
+            .find(|mood| format!("{mood:?}").eq_ignore_ascii_case(name))
+    }
+}
+
+/// Relative chances of each mood being picked for a randomized Moodel. Moods missing from
+/// the map are never picked.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct MoodWeights(pub HashMap<Mood, f32>);
+
+impl Default for MoodWeights {
+    /// Every mood is equally likely
+    fn default() -> Self {
+        Self(Mood::all().into_iter().map(|mood| (mood, 1.0)).collect())
+    }
+}
+
+impl MoodWeights {
+    fn weight(&self, mood: Mood) -> f32 {
+        self.0.get(&mood).copied().unwrap_or(0.0).max(0.0)
+    }
+}
+
+impl Mood {
+    /// Pick a mood at random, in proportion to its weight. Falls back to a uniform pick when
+    /// no mood has a positive weight.
+    pub fn random_weighted(rng: &mut impl Rng, weights: &MoodWeights) -> Mood {
+        let total: f32 = Mood::all()
+            .into_iter()
+            .map(|mood| weights.weight(mood))
+            .sum();
+        if total <= 0.0 {
+            return Mood::all()[rng.random_range(0..Mood::all().len())];
+        }
+        let mut roll = rng.random_range(0.0..total);
+        for mood in Mood::all() {
+            let weight = weights.weight(mood);
+            if roll < weight {
+                return mood;
+            }
+            roll -= weight;
         }
+        // Rounding can leave the roll just past the last weight
+        Mood::all()
+            .into_iter()
+            .rev()
+            .find(|mood| weights.weight(*mood) > 0.0)
+            .unwrap_or_default()
     }
 }
 
@@ -164,6 +352,8 @@ pub struct MoodAssets {
     pub rage: Handle<Image>,
     #[dependency]
     pub sad: Handle<Image>,
+    /// Not a dependency: if the sprite is missing, Fear falls back to the Neutral sprite
+    pub fear: Handle<Image>,
 }
 
 impl MoodAssets {
@@ -175,6 +365,7 @@ impl MoodAssets {
             Mood::Happy => self.happy.clone(),
             Mood::Rage => self.rage.clone(),
             Mood::Sad => self.sad.clone(),
+            Mood::Fear => self.fear.clone(),
         }
     }
 }
@@ -188,10 +379,35 @@ impl FromWorld for MoodAssets {
             happy: assets.load("images/MoodelHappy.png"),
             rage: assets.load("images/MoodelRage.png"),
             sad: assets.load("images/MoodelSad.png"),
+            fear: assets.load("images/MoodelFear.png"),
         }
     }
 }
 
+/// Keeps a Moodel's mood from being changed by collisions, mood objects or isolation decay.
+/// Moods forced through [`SetMood`] still apply.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct MoodLock {
+    /// Time left on the lock, which is removed once it runs out. `None` locks the mood for good.
+    pub remaining: Option<Timer>,
+}
+
+impl MoodLock {
+    pub fn permanent() -> Self {
+        Self { remaining: None }
+    }
+
+    pub fn for_secs(secs: f32) -> Self {
+        Self {
+            remaining: Some(Timer::from_seconds(secs, TimerMode::Once)),
+        }
+    }
+}
+
+/// How long a lock placed by the player lasts.
+const PLAYER_LOCK_SECS: f32 = 10.0;
+
 /// Component for entities that can change moods
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component)]
@@ -200,8 +416,14 @@ pub struct MoodEntity {
     pub isolation_timer: Timer,
     /// How long the current mood has been stable (for permanence)
     pub mood_stability: f32,
+    /// How strongly the current mood is felt, from 0.0 to 1.0. Ramps up while the mood is stable
+    pub intensity: f32,
     /// Last time this entity had a social interaction
     pub last_interaction_time: f32,
+    /// Last time this entity received a collision knockback
+    pub last_impact_time: f32,
+    /// Last time a collision involving this entity played a sound
+    pub last_collision_sound_time: f32,
 }
 
 impl Default for MoodEntity {
@@ -209,74 +431,123 @@ impl Default for MoodEntity {
         Self {
             isolation_timer: Timer::from_seconds(3.0, TimerMode::Repeating), // Check for isolation every 3 seconds
             mood_stability: 0.0,
+            intensity: 0.0,
             last_interaction_time: 0.0, // Will be set to current time when entity spawns
+            last_impact_time: f32::NEG_INFINITY,
+            last_collision_sound_time: f32::NEG_INFINITY,
         }
     }
 }
 
-/// Resource for tracking mood statistics logging
-#[derive(Resource)]
-pub struct MoodStatsTimer {
-    timer: Timer,
+/// Lets Neutral Moodels catch the mood of a crowd around them without bumping into it.
+/// Off by default, since it competes with the collision rules.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MoodContagion {
+    pub enabled: bool,
+    /// How close other Moodels must be to count towards the crowd
+    pub radius: f32,
+    /// How many nearby Moodels must share a mood before it can spread
+    pub min_neighbors: usize,
+    /// Chance per second that a Neutral Moodel next to such a crowd catches its mood
+    pub chance_per_sec: f32,
 }
 
-impl Default for MoodStatsTimer {
+impl Default for MoodContagion {
     fn default() -> Self {
         Self {
-            timer: Timer::from_seconds(5.0, TimerMode::Repeating), // Log every 5 seconds
+            enabled: false,
+            radius: 150.0,
+            min_neighbors: 3,
+            chance_per_sec: 0.2,
         }
     }
 }
 
-// Determines how two moods interact when they collide
-define_get_mood_interaction! {
-    Mood; // The enum we are working with
+/// Tuning for how Moodels settle into a mood once it has persisted for a while
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MoodSettlingConfig {
+    /// Seconds a mood must persist before the Moodel settles and stops decaying naturally
+    pub settle_threshold: f32,
+    /// Chance of resisting a natural decay just before settling; it ramps up from zero
+    pub max_decay_resistance: f32,
+    /// Impacts at least this strong (see `Mood::impact_force`) unsettle a Moodel
+    pub disturb_impact_force: f32,
+}
+
+impl Default for MoodSettlingConfig {
+    fn default() -> Self {
+        Self {
+            settle_threshold: 20.0,
+            max_decay_resistance: 0.75,
+            disturb_impact_force: 3.0, // Only a Rage slam is strong enough
+        }
+    }
+}
 
-    // Asymmetrical rules: (A, B) => (C, D) will also create (B, A) => (D, C).
-    one_way {
-        (Rage, Calm) => (Rage, Sad),     // Rage cools to Sad; Calm is unaffected.
-        (Rage, Happy) => (Rage, Sad),   // Rage cools to Sad; Happy is unaffected.
-        (Rage, Neutral) => (Rage, Rage),  // Rage makes Neutral Sad; Rage is unaffected.
-        (Rage, Sad) => (Rage, Sad),
+impl MoodSettlingConfig {
+    /// Whether a Moodel with this much stability has settled
+    pub fn is_settled(&self, mood_stability: f32) -> bool {
+        mood_stability >= self.settle_threshold
     }
 
-    // Symmetrical rules for two DIFFERENT moods. Both (A, B) and (B, A) give the same result.
-    two_way {
-        // Rage interactions
+    /// The chance that a natural decay is ignored, growing the longer the mood persists
+    fn decay_resistance(&self, mood_stability: f32) -> f32 {
+        (mood_stability / self.settle_threshold).clamp(0.0, 1.0) * self.max_decay_resistance
+    }
+}
 
-        // Happy interactions
-        (Happy, Sad) => (Happy, Calm),     // Happy cheers Sad up to Calm.
-        (Happy, Calm) => (Happy, Happy),   // Happy spreads to Calm.
-        (Happy, Neutral) => (Happy, Calm), // Happy lifts Neutral to Calm.
+/// Marker for a Moodel whose mood has settled, pointing at its glow
+#[derive(Component, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Settled {
+    pub glow: Entity,
+}
 
-        // Calm interactions
-        (Calm, Sad) => (Calm, Calm),     // Calm soothes Sad to Calm.
-        (Calm, Neutral) => (Calm, Calm), // Calm influences Neutral.
+/// An event to force a Moodel into a specific mood (used by tools and scripted sequences)
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SetMood {
+    pub entity: Entity,
+    pub mood: Mood,
+}
 
-        // Sad interactions
-        (Sad, Neutral) => (Sad, Sad),     // Sad spreads to Neutral.
-    }
+/// Fired whenever a Moodel's mood actually changes, for systems that react to transitions
+#[derive(Event, Debug, Clone, Copy)]
+pub struct MoodChanged {
+    pub entity: Entity,
+    pub from: Mood,
+    pub to: Mood,
+}
 
-    // Symmetrical rules for a mood interacting with itself.
-    self_interaction {
-        (Rage) => (Rage),
-        (Happy) => (Happy),
-        (Calm) => (Calm),
-        (Sad) => (Sad),
-    }
+/// Resource for tracking mood statistics logging
+#[derive(Resource)]
+pub struct MoodStatsTimer {
+    timer: Timer,
+}
 
-    // Special case for Neutral <-> Neutral, which has random outcomes.
-    neutral_case {
-        let mut rng = rand::rng(); // Use thread_rng for one-off uses
-        match rng.random_range(0..100u8) {
-            r if r < 15 => (Sad, Sad),     // 15% Sad + Sad
-            r if r < 30 => (Rage, Rage),   // 15% Rage + Rage
-            r if r < 65 => (Happy, Happy), // 35% Happy + Happy
-            _ => (Calm, Calm),             // 35% Calm + Calm
+impl Default for MoodStatsTimer {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(5.0, TimerMode::Repeating), // Log every 5 seconds
         }
     }
 }
 
+/// Whether a collision between two Moodels may play a sound, starting both of their cooldowns if so.
+/// A pile-up of Moodels would otherwise trigger the same clip many times a second.
+fn collision_sound_ready(a: &mut MoodEntity, b: &mut MoodEntity, current_time: f32) -> bool {
+    let ready = |entity: &MoodEntity| {
+        current_time - entity.last_collision_sound_time >= COLLISION_SOUND_COOLDOWN_SECS
+    };
+    if !ready(a) || !ready(b) {
+        return false;
+    }
+    a.last_collision_sound_time = current_time;
+    b.last_collision_sound_time = current_time;
+    true
+}
+
 /// Updates mood for a single entity and emits mood change events
 fn update_entity_mood(
     entity: Entity,
@@ -286,19 +557,25 @@ fn update_entity_mood(
     mood_assets: &MoodAssets,
     new_mood: Mood,
     sfx_writer: &mut EventWriter<crate::audio::PlaySound>,
+    mood_changed_writer: &mut EventWriter<MoodChanged>,
 ) {
     if new_mood != *mood {
         let old_mood = *mood;
         *mood = new_mood;
         sprite.image = mood_assets.get_sprite(new_mood);
-        sprite.color = new_mood.color();
         mood_entity.mood_stability = 0.0; // Reset stability on change
+        mood_entity.intensity = 0.0; // A new mood starts out mild
 
         // Emit mood change event for audio system
         sfx_writer.write(crate::audio::PlaySound::MoodChanged {
             from: old_mood,
             to: new_mood,
         });
+        mood_changed_writer.write(MoodChanged {
+            entity,
+            from: old_mood,
+            to: new_mood,
+        });
 
         #[cfg(feature = "dev")]
         info!(
@@ -311,16 +588,27 @@ fn update_entity_mood(
 /// Handle collision events for mood-based social interactions
 fn handle_collision_events(
     mut collision_started: EventReader<CollisionStarted>,
-    // The query now needs AiWanderState to check for charging and update the hit count
+    // The query needs AiWanderState to check for charging and update the hit count
     mut moodel_query: Query<
-        (&mut Mood, &mut MoodEntity, &mut Sprite, &mut AiWanderState),
+        (
+            &mut Mood,
+            &mut MoodEntity,
+            &mut Sprite,
+            &mut AiWanderState,
+            &Transform,
+            &mut ExternalImpulse,
+        ),
         With<AiEntity>,
     >,
     // We get AiConfig as a resource
     config: Res<AiConfig>,
+    rules: Res<MoodRules>,
     mood_assets: Option<Res<MoodAssets>>,
     time: Res<Time>,
     mut sfx_writer: EventWriter<crate::audio::PlaySound>,
+    mut mood_changed_writer: EventWriter<MoodChanged>,
+    mut rng: ResMut<GameRng>,
+    locked_query: Query<(), With<MoodLock>>,
 ) -> Result {
     // Early return if assets aren't loaded yet
     let Some(mood_assets) = mood_assets else {
@@ -336,57 +624,142 @@ fn handle_collision_events(
         if let (Ok(_moodel1), Ok(_moodel2)) =
             (moodel_query.get(*entity1), moodel_query.get(*entity2))
         {
-            // --- NEW: Handle Rage Burnout ---
-            let mut process_burnout = |charger_entity: Entity, _target_entity: Entity| {
-                if let Ok((mood, _, _, mut wander_state)) = moodel_query.get_mut(charger_entity) {
-                    if *mood == Mood::Rage
-                        && matches!(wander_state.action, AiAction::Charging { .. })
-                    {
-                        wander_state.charge_hit_count += 1;
-                        info!(
-                            "Rage Moodel {:?} hit count: {}",
-                            charger_entity, wander_state.charge_hit_count
-                        );
-                        if wander_state.charge_hit_count >= config.rage_max_hits_before_burnout {
-                            info!(
-                                "Rage Moodel {:?} burned out! Becoming Calm.",
-                                charger_entity
-                            );
-                            if let Ok((mut mood, mut mood_entity, mut sprite, mut wander_state)) =
-                                moodel_query.get_mut(charger_entity)
-                            {
-                                // Become calm and reset state
-                                update_entity_mood(
-                                    charger_entity,
-                                    &mut mood,
-                                    &mut mood_entity,
-                                    &mut sprite,
-                                    &mood_assets,
-                                    Mood::Calm,
-                                    &mut sfx_writer,
-                                );
-                                wander_state.action = AiAction::Wandering;
-                                wander_state.charge_hit_count = 0;
-                            }
-                        }
-                    }
+            // --- Rage charge hits take the place of the regular interaction ---
+            let charge_hit = [(*entity1, *entity2), (*entity2, *entity1)]
+                .into_iter()
+                .find(|(charger, _)| {
+                    moodel_query
+                        .get(*charger)
+                        .is_ok_and(|(mood, _, _, wander_state, _, _)| {
+                            *mood == Mood::Rage
+                                && matches!(wander_state.action, AiAction::Charging { .. })
+                        })
+                });
+            if let Some((charger, target)) = charge_hit {
+                let Ok(
+                    [
+                        (
+                            mut charger_mood,
+                            mut charger_mood_entity,
+                            mut charger_sprite,
+                            mut charger_state,
+                            charger_transform,
+                            _,
+                        ),
+                        (
+                            mut target_mood,
+                            mut target_mood_entity,
+                            mut target_sprite,
+                            _,
+                            target_transform,
+                            mut target_impulse,
+                        ),
+                    ],
+                ) = moodel_query.get_many_mut([charger, target])
+                else {
+                    continue;
+                };
+
+                if collision_sound_ready(
+                    &mut charger_mood_entity,
+                    &mut target_mood_entity,
+                    current_time,
+                ) {
+                    sfx_writer.write(crate::audio::PlaySound::MoodCollision {
+                        mood1: *charger_mood,
+                        mood2: *target_mood,
+                        position: target_transform.translation.truncate(),
+                    });
                 }
-            };
 
-            process_burnout(*entity1, *entity2);
-            process_burnout(*entity2, *entity1);
-            // --- End of Burnout Logic ---
+                // Knock the target away along the charge
+                let direction = (target_transform.translation.truncate()
+                    - charger_transform.translation.truncate())
+                .normalize_or_zero();
+                target_impulse.apply_impulse(direction * config.rage_charge_knockback);
+
+                // Timid moods are frightened, the rest get angry
+                let target_new_mood = match *target_mood {
+                    _ if locked_query.contains(target) => *target_mood,
+                    Mood::Calm | Mood::Sad | Mood::Fear => Mood::Fear,
+                    _ => Mood::Rage,
+                };
+                target_mood_entity.last_interaction_time = current_time;
+                update_entity_mood(
+                    target,
+                    &mut target_mood,
+                    &mut target_mood_entity,
+                    &mut target_sprite,
+                    &mood_assets,
+                    target_new_mood,
+                    &mut sfx_writer,
+                    &mut mood_changed_writer,
+                );
+
+                charger_mood_entity.last_interaction_time = current_time;
+                charger_state.charge_hit_count += 1;
+                info!(
+                    "Rage Moodel {:?} hit count: {}",
+                    charger, charger_state.charge_hit_count
+                );
+                if charger_state.charge_hit_count >= config.rage_max_hits_before_burnout
+                    && !locked_query.contains(charger)
+                {
+                    info!("Rage Moodel {:?} burned out! Becoming Calm.", charger);
+                    // Become calm, and wait a long time before charging again
+                    update_entity_mood(
+                        charger,
+                        &mut charger_mood,
+                        &mut charger_mood_entity,
+                        &mut charger_sprite,
+                        &mood_assets,
+                        Mood::Calm,
+                        &mut sfx_writer,
+                        &mut mood_changed_writer,
+                    );
+                    charger_state.action = AiAction::Wandering;
+                    charger_state.charge_hit_count = 0;
+                    charger_state.ability_cooldown.set_duration(
+                        std::time::Duration::from_secs_f32(config.rage_burnout_cooldown),
+                    );
+                    charger_state.ability_cooldown.reset();
+                }
+                continue;
+            }
 
             // Regular mood interaction logic
-            let (mood1, _, _, _) = moodel_query.get(*entity1).unwrap();
-            let (mood2, _, _, _) = moodel_query.get(*entity2).unwrap();
-            let (new_mood1, new_mood2) = get_mood_interaction(*mood1, *mood2);
-
-            // Emit collision event for audio system
-            sfx_writer.write(crate::audio::PlaySound::MoodCollision {
-                mood1: *mood1,
-                mood2: *mood2,
-            });
+            let (mood1, ..) = moodel_query.get(*entity1).unwrap();
+            let (mood2, ..) = moodel_query.get(*entity2).unwrap();
+            let (mut new_mood1, mut new_mood2) = rules.interaction(*mood1, *mood2, &mut *rng);
+            if locked_query.contains(*entity1) {
+                new_mood1 = *mood1;
+            }
+            if locked_query.contains(*entity2) {
+                new_mood2 = *mood2;
+            }
+
+            let (mood1, mood2) = (*mood1, *mood2);
+
+            // Emit collision event for audio system, unless either Moodel just made a sound
+            if let Ok(
+                [
+                    (_, mut mood_entity1, _, _, transform1, _),
+                    (_, mut mood_entity2, _, _, transform2, _),
+                ],
+            ) = moodel_query.get_many_mut([*entity1, *entity2])
+            {
+                if collision_sound_ready(&mut mood_entity1, &mut mood_entity2, current_time) {
+                    let position = transform1
+                        .translation
+                        .truncate()
+                        .midpoint(transform2.translation.truncate());
+                    sfx_writer.write(crate::audio::PlaySound::MoodCollision {
+                        mood1,
+                        mood2,
+                        position,
+                    });
+                }
+            }
 
             #[cfg(feature = "dev")]
             info!(
@@ -394,7 +767,8 @@ fn handle_collision_events(
                 mood1, mood2, new_mood1, new_mood2
             );
 
-            if let Ok((mut mood, mut mood_entity, mut sprite, _)) = moodel_query.get_mut(*entity1) {
+            if let Ok((mut mood, mut mood_entity, mut sprite, ..)) = moodel_query.get_mut(*entity1)
+            {
                 mood_entity.last_interaction_time = current_time;
                 update_entity_mood(
                     *entity1,
@@ -404,9 +778,11 @@ fn handle_collision_events(
                     &mood_assets,
                     new_mood1,
                     &mut sfx_writer,
+                    &mut mood_changed_writer,
                 );
             }
-            if let Ok((mut mood, mut mood_entity, mut sprite, _)) = moodel_query.get_mut(*entity2) {
+            if let Ok((mut mood, mut mood_entity, mut sprite, ..)) = moodel_query.get_mut(*entity2)
+            {
                 mood_entity.last_interaction_time = current_time;
                 update_entity_mood(
                     *entity2,
@@ -416,6 +792,7 @@ fn handle_collision_events(
                     &mood_assets,
                     new_mood2,
                     &mut sfx_writer,
+                    &mut mood_changed_writer,
                 );
             }
         }
@@ -424,14 +801,86 @@ fn handle_collision_events(
     Ok(())
 }
 
+/// Knock Moodels apart on contact, scaled by the mood of the one doing the hitting
+fn apply_mood_impacts(
+    mut collision_started: EventReader<CollisionStarted>,
+    mut moodel_query: Query<
+        (&Mood, &Transform, &mut MoodEntity, &mut ExternalImpulse),
+        With<AiEntity>,
+    >,
+    settling: Res<MoodSettlingConfig>,
+    time: Res<Time>,
+) {
+    let current_time = time.elapsed_secs();
+
+    for CollisionStarted(entity1, entity2) in collision_started.read() {
+        let (Ok((mood1, transform1, _, _)), Ok((mood2, transform2, _, _))) =
+            (moodel_query.get(*entity1), moodel_query.get(*entity2))
+        else {
+            continue;
+        };
+
+        // Moodel colliders are circles, so the line between centers is the contact normal
+        let normal = (transform2.translation.truncate() - transform1.translation.truncate())
+            .normalize_or_zero();
+        if normal == Vec2::ZERO {
+            continue;
+        }
+
+        // Each Moodel is pushed away by the other's impact force
+        let pushes = [
+            (*entity1, -normal, mood2.impact_force()),
+            (*entity2, normal, mood1.impact_force()),
+        ];
+
+        for (victim, direction, force) in pushes {
+            if let Ok((_, _, mut mood_entity, mut impulse)) = moodel_query.get_mut(victim) {
+                if current_time - mood_entity.last_impact_time < IMPACT_COOLDOWN_SECS {
+                    continue;
+                }
+                mood_entity.last_impact_time = current_time;
+                impulse.apply_impulse(direction * IMPACT_BASE_IMPULSE * force);
+
+                // A hard enough hit shakes a Moodel out of its settled mood
+                if force >= settling.disturb_impact_force {
+                    mood_entity.mood_stability = 0.0;
+                }
+            }
+        }
+    }
+}
+
+/// Update mass and bounciness when a Moodel's mood changes
+fn sync_mood_physics(mut moodel_query: Query<(&Mood, &mut Mass, &mut Restitution), Changed<Mood>>) {
+    for (mood, mut mass, mut restitution) in &mut moodel_query {
+        mass.0 = mood.mass();
+        restitution.coefficient = mood.restitution();
+    }
+}
+
+/// Tint Moodels by their mood and its intensity, in the current color scheme
+fn tint_moodels(
+    scheme: Res<ColorScheme>,
+    mut moodel_query: Query<(&Mood, &MoodEntity, &mut Sprite), With<AiEntity>>,
+) {
+    for (mood, mood_entity, mut sprite) in &mut moodel_query {
+        let color = mood.color_at_intensity(*scheme, mood_entity.intensity);
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
+
 /// Handle collisions between Moodels and mood-changing objects
 fn handle_mood_object_collisions(
     mut collision_started: EventReader<CollisionStarted>,
     mut moodel_query: Query<(&mut Mood, &mut MoodEntity, &mut Sprite), With<AiEntity>>,
     mut mood_object_query: Query<&mut MoodObject>,
+    locked_query: Query<(), With<MoodLock>>,
     mood_assets: Option<Res<MoodAssets>>,
     time: Res<Time>,
     mut sfx_writer: EventWriter<crate::audio::PlaySound>,
+    mut mood_changed_writer: EventWriter<MoodChanged>,
 ) -> Result {
     // Early return if assets aren't loaded yet
     let Some(mood_assets) = mood_assets else {
@@ -455,6 +904,9 @@ fn handle_mood_object_collisions(
         } else {
             continue; // Not a Moodel-MoodObject collision
         }
+        if locked_query.contains(moodel_entity) {
+            continue;
+        }
 
         // Get the mood object and check cooldown
         if let Ok(mut mood_object) = mood_object_query.get_mut(mood_object_entity) {
@@ -474,6 +926,7 @@ fn handle_mood_object_collisions(
                             &mood_assets,
                             target_mood,
                             &mut sfx_writer,
+                            &mut mood_changed_writer,
                         );
 
                         // Update interaction time and record hit
@@ -497,9 +950,23 @@ fn handle_mood_object_collisions(
 /// Handle isolation decay - Moodels become calm then neutral when isolated
 fn handle_isolation_decay(
     time: Res<Time>,
+    settling: Res<MoodSettlingConfig>,
+    rules: Res<MoodRules>,
     mood_assets: Option<Res<MoodAssets>>,
-    mut moodel_query: Query<(Entity, &mut Mood, &mut MoodEntity, &mut Sprite), With<AiEntity>>,
+    mut moodel_query: Query<
+        (
+            Entity,
+            &mut Mood,
+            &mut MoodEntity,
+            &mut Sprite,
+            &AiWanderState,
+            Has<MoodLock>,
+        ),
+        With<AiEntity>,
+    >,
     mut sfx_writer: EventWriter<crate::audio::PlaySound>,
+    mut mood_changed_writer: EventWriter<MoodChanged>,
+    mut rng: ResMut<GameRng>,
 ) -> Result {
     // Early return if assets aren't loaded yet
     let Some(mood_assets) = mood_assets else {
@@ -509,36 +976,35 @@ fn handle_isolation_decay(
     let current_time = time.elapsed_secs();
     let delta = time.delta_secs();
 
-    for (entity, mut mood, mut mood_entity, mut sprite) in &mut moodel_query {
+    for (entity, mut mood, mut mood_entity, mut sprite, wander_state, locked) in &mut moodel_query {
         mood_entity.isolation_timer.tick(time.delta());
         mood_entity.mood_stability += delta; // Track how long mood has been stable
 
+        // The longer a mood lasts, the more intensely it shows (see `tint_moodels`)
+        mood_entity.intensity = (mood_entity.mood_stability / INTENSITY_RAMP_SECS).min(1.0);
+
+        // Settled and locked Moodels hold their mood, and so do Moodels the player is
+        // directing, so they don't change their minds halfway to where they were sent
+        let commanded = matches!(
+            wander_state.action,
+            AiAction::MovingToTarget { .. } | AiAction::Held
+        );
+        if locked || commanded || settling.is_settled(mood_entity.mood_stability) {
+            continue;
+        }
+
         // Check if entity has been isolated (no interactions for a while)
         if mood_entity.isolation_timer.just_finished() {
             let time_since_interaction = current_time - mood_entity.last_interaction_time;
-            let mut rng = rand::rng(); // Use thread_rng for one-off uses
 
-            // If isolated for more than 5 seconds (matching spec), start mood decay toward neutral
-            if (time_since_interaction > 2.0 && *mood != Mood::Rage) || time_since_interaction > 6.0
-            {
-                let new_mood = match *mood {
-                    Mood::Rage => Mood::Calm,   // Rage cools to Neutral
-                    Mood::Happy => Mood::Calm,  // Happiness fades to Neutral
-                    Mood::Sad => Mood::Neutral, // Sadness lifts to Neutral
-                    Mood::Calm => match rng.random_bool(1. / 2.) {
-                        // equal chance to change to be happy or neutral
-                        true => Mood::Happy,    // 50% chance to become Happy
-                        false => Mood::Neutral, // 50% chance to become Neutral
-                    },
-                    Mood::Neutral => match rng.random_range(0..100u8) {
-                        // equal chance to change to any mood
-                        r if r < 20 => Mood::Happy, // 20% chance to become Happy
-                        r if r < 40 => Mood::Sad,   // 20% chance to become Sad
-                        r if r < 60 => Mood::Rage,  // 20% chance to become Rage
-                        r if r < 80 => Mood::Calm,  // 20% chance to become Calm
-                        _ => Mood::Neutral,         // Otherwise stay Neutral
-                    },
-                };
+            // The longer a mood persists, the more likely it is to resist decaying
+            if rng.random::<f32>() < settling.decay_resistance(mood_entity.mood_stability) {
+                continue;
+            }
+
+            // If isolated long enough, start mood decay toward neutral
+            if time_since_interaction > rules.isolation_delay_for(*mood) {
+                let new_mood = rules.decay(*mood, &mut *rng);
 
                 if new_mood != *mood {
                     update_entity_mood(
@@ -549,6 +1015,7 @@ fn handle_isolation_decay(
                         &mood_assets,
                         new_mood,
                         &mut sfx_writer,
+                        &mut mood_changed_writer,
                     );
 
                     #[cfg(feature = "dev")]
@@ -563,6 +1030,242 @@ fn handle_isolation_decay(
     Ok(())
 }
 
+fn contagion_enabled(contagion: Res<MoodContagion>) -> bool {
+    contagion.enabled
+}
+
+/// Gives each unlocked Neutral Moodel a chance to take on the most common mood among its
+/// neighbors, once enough of them share it
+fn spread_mood_contagion(
+    time: Res<Time>,
+    contagion: Res<MoodContagion>,
+    grid: Res<SpatialGrid>,
+    mood_assets: Option<Res<MoodAssets>>,
+    mut moodel_query: Query<
+        (
+            Entity,
+            &mut Mood,
+            &mut MoodEntity,
+            &mut Sprite,
+            &Transform,
+            Has<MoodLock>,
+        ),
+        With<AiEntity>,
+    >,
+    mut sfx_writer: EventWriter<crate::audio::PlaySound>,
+    mut mood_changed_writer: EventWriter<MoodChanged>,
+    mut rng: ResMut<GameRng>,
+) -> Result {
+    // Early return if assets aren't loaded yet
+    let Some(mood_assets) = mood_assets else {
+        return Ok(());
+    };
+    let chance = (contagion.chance_per_sec * time.delta_secs()).min(1.0);
+
+    let mut infections = Vec::new();
+    for (entity, mood, _, _, transform, locked) in &moodel_query {
+        if *mood != Mood::Neutral || locked {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let mut counts = [0; 6];
+        for other in grid.neighbors(position, contagion.radius) {
+            if other == entity {
+                continue;
+            }
+            let Ok((_, other_mood, _, _, other_transform, _)) = moodel_query.get(other) else {
+                continue;
+            };
+            if other_transform.translation.truncate().distance(position) <= contagion.radius {
+                if let Some(index) = Mood::all().iter().position(|mood| mood == other_mood) {
+                    counts[index] += 1;
+                }
+            }
+        }
+        // Ties are broken by the order of `Mood::all`, so the outcome only depends on the seed
+        let crowd_mood = Mood::all()
+            .into_iter()
+            .zip(counts)
+            .filter(|(mood, count)| *mood != Mood::Neutral && *count >= contagion.min_neighbors)
+            .max_by_key(|(_, count)| *count);
+        if let Some((crowd_mood, _)) = crowd_mood {
+            if rng.random::<f32>() < chance {
+                infections.push((entity, crowd_mood));
+            }
+        }
+    }
+
+    for (entity, new_mood) in infections {
+        let Ok((_, mut mood, mut mood_entity, mut sprite, ..)) = moodel_query.get_mut(entity)
+        else {
+            continue;
+        };
+        update_entity_mood(
+            entity,
+            &mut mood,
+            &mut mood_entity,
+            &mut sprite,
+            &mood_assets,
+            new_mood,
+            &mut sfx_writer,
+            &mut mood_changed_writer,
+        );
+    }
+
+    Ok(())
+}
+
+/// Apply moods forced through `SetMood` events
+fn apply_set_mood_events(
+    mut set_mood_events: EventReader<SetMood>,
+    mood_assets: Option<Res<MoodAssets>>,
+    mut moodel_query: Query<(&mut Mood, &mut MoodEntity, &mut Sprite), With<AiEntity>>,
+    mut sfx_writer: EventWriter<crate::audio::PlaySound>,
+    mut mood_changed_writer: EventWriter<MoodChanged>,
+) -> Result {
+    // Early return if assets aren't loaded yet
+    let Some(mood_assets) = mood_assets else {
+        return Ok(());
+    };
+
+    for event in set_mood_events.read() {
+        if let Ok((mut mood, mut mood_entity, mut sprite)) = moodel_query.get_mut(event.entity) {
+            update_entity_mood(
+                event.entity,
+                &mut mood,
+                &mut mood_entity,
+                &mut sprite,
+                &mood_assets,
+                event.mood,
+                &mut sfx_writer,
+                &mut mood_changed_writer,
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Locks every selected Moodel's mood for a while, or unlocks them if they are all locked.
+/// Permanent locks placed by the level are left alone, so the player can't undo them.
+fn toggle_mood_lock(
+    mut commands: Commands,
+    selected_query: Query<(Entity, Option<&MoodLock>), (With<Selected>, With<AiEntity>)>,
+) {
+    let toggleable = selected_query
+        .iter()
+        .filter(|(_, lock)| lock.is_none_or(|lock| lock.remaining.is_some()));
+    let all_locked = toggleable.clone().all(|(_, lock)| lock.is_some());
+    for (entity, _) in toggleable {
+        if all_locked {
+            commands.entity(entity).remove::<MoodLock>();
+        } else {
+            commands
+                .entity(entity)
+                .insert(MoodLock::for_secs(PLAYER_LOCK_SECS));
+        }
+    }
+}
+
+/// Removes timed locks once they run out.
+fn tick_mood_locks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut lock_query: Query<(Entity, &mut MoodLock)>,
+) {
+    for (entity, mut lock) in &mut lock_query {
+        let Some(timer) = &mut lock.remaining else {
+            continue;
+        };
+        if timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<MoodLock>();
+        }
+    }
+}
+
+/// Outlines locked Moodels with a square, so it's clear their mood won't change.
+fn draw_mood_locks(
+    mut gizmos: Gizmos,
+    scheme: Res<ColorScheme>,
+    lock_query: Query<(&Transform, &Mood), With<MoodLock>>,
+) {
+    for (transform, mood) in &lock_query {
+        gizmos.rect_2d(
+            Isometry2d::from_translation(transform.translation.truncate()),
+            Vec2::splat(70.0),
+            mood.color_for_scheme(*scheme),
+        );
+    }
+}
+
+/// If the Fear sprite failed to load, use the Neutral sprite instead (Fear is still tinted by its color)
+fn fallback_missing_fear_sprite(
+    asset_server: Res<AssetServer>,
+    mood_assets: Option<ResMut<MoodAssets>>,
+    mut moodel_query: Query<(&Mood, &mut Sprite), With<AiEntity>>,
+) {
+    let Some(mut mood_assets) = mood_assets else {
+        return;
+    };
+    if !matches!(
+        asset_server.get_load_state(&mood_assets.fear),
+        Some(LoadState::Failed(_))
+    ) {
+        return;
+    }
+    warn!("Fear sprite is missing, falling back to the Neutral sprite");
+
+    mood_assets.fear = mood_assets.neutral.clone();
+    for (mood, mut sprite) in &mut moodel_query {
+        if *mood == Mood::Fear {
+            sprite.image = mood_assets.fear.clone();
+        }
+    }
+}
+
+/// Player commands disturb a Moodel, unsettling its mood
+fn disturb_commanded_moodels(
+    mut command_events: EventReader<CommandMoodelEvent>,
+    mut moodel_query: Query<&mut MoodEntity, With<AiEntity>>,
+) {
+    for event in command_events.read() {
+        if let Ok(mut mood_entity) = moodel_query.get_mut(event.entity) {
+            mood_entity.mood_stability = 0.0;
+        }
+    }
+}
+
+/// Add a calm glow behind settled Moodels, and remove it once they are disturbed
+fn update_settled_glow(
+    mut commands: Commands,
+    settling: Res<MoodSettlingConfig>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    moodel_query: Query<(Entity, &MoodEntity, Option<&Settled>), With<AiEntity>>,
+) {
+    for (entity, mood_entity, settled) in &moodel_query {
+        match (settling.is_settled(mood_entity.mood_stability), settled) {
+            (true, None) => {
+                let glow = commands
+                    .spawn((
+                        Name::new("Settled Glow"),
+                        Mesh2d(meshes.add(Circle::new(130.0))),
+                        MeshMaterial2d(materials.add(Color::srgba(0.7, 0.9, 1.0, 0.2))),
+                        Transform::from_xyz(0.0, 0.0, -0.1),
+                        ChildOf(entity),
+                    ))
+                    .id();
+                commands.entity(entity).insert(Settled { glow });
+            }
+            (false, Some(settled)) => {
+                commands.entity(settled.glow).despawn();
+                commands.entity(entity).remove::<Settled>();
+            }
+            _ => {}
+        }
+    }
+}
+
 // Cyclical progression system disabled - only isolation decay and social interactions now determine mood changes
 
 /// Log mood statistics every 5 seconds
@@ -574,7 +1277,7 @@ fn log_mood_statistics(
     stats_timer.timer.tick(time.delta());
 
     if stats_timer.timer.just_finished() {
-        let mut mood_counts = [0; 5]; // [Neutral, Calm, Happy, Rage, Sad]
+        let mut mood_counts = [0; 6]; // [Neutral, Calm, Happy, Rage, Sad, Fear]
         let mut total_count = 0;
 
         // Count each mood type
@@ -586,6 +1289,7 @@ fn log_mood_statistics(
                 Mood::Happy => mood_counts[2] += 1,
                 Mood::Rage => mood_counts[3] += 1,
                 Mood::Sad => mood_counts[4] += 1,
+                Mood::Fear => mood_counts[5] += 1,
             }
         }
 
@@ -596,10 +1300,11 @@ fn log_mood_statistics(
             let happy_pct = (mood_counts[2] as f32 / total_count as f32) * 100.0;
             let rage_pct = (mood_counts[3] as f32 / total_count as f32) * 100.0;
             let sad_pct = (mood_counts[4] as f32 / total_count as f32) * 100.0;
+            let fear_pct = (mood_counts[5] as f32 / total_count as f32) * 100.0;
 
             info!(
-                "MOOD STATS | Total: {} | Neutral: {:.1}% | Calm: {:.1}% | Happy: {:.1}% | Rage: {:.1}% | Sad: {:.1}%",
-                total_count, neutral_pct, calm_pct, happy_pct, rage_pct, sad_pct
+                "MOOD STATS | Total: {} | Neutral: {:.1}% | Calm: {:.1}% | Happy: {:.1}% | Rage: {:.1}% | Sad: {:.1}% | Fear: {:.1}%",
+                total_count, neutral_pct, calm_pct, happy_pct, rage_pct, sad_pct, fear_pct
             );
         }
     }
@@ -667,6 +1372,7 @@ pub struct MoodelBundle {
     pub friction: Friction,
     pub gravity_scale: GravityScale,
     pub external_force: ExternalForce,
+    pub external_impulse: ExternalImpulse,
 }
 
 /// Creates a bundle for spawning a Moodel with a specific mood
@@ -676,6 +1382,7 @@ pub fn spawn_moodel_bundle(
     position: Vec3,
     max_speed: f32,
     current_time: f32,
+    rng: &mut impl Rng,
 ) -> impl Bundle {
     let moodel_tuple = (
         MoodelBundle {
@@ -685,7 +1392,10 @@ pub fn spawn_moodel_bundle(
             mood_entity: MoodEntity {
                 isolation_timer: Timer::from_seconds(3.0, TimerMode::Repeating),
                 mood_stability: 0.0,
+                intensity: 0.0,
                 last_interaction_time: current_time, // Initialize with current game time
+                last_impact_time: f32::NEG_INFINITY,
+                last_collision_sound_time: f32::NEG_INFINITY,
             },
             sprite: Sprite {
                 image: mood_assets.get_sprite(mood),
@@ -722,6 +1432,11 @@ pub fn spawn_moodel_bundle(
                     Mood::Sad => 600.0,    // Sad decelerates slowly
                     _ => 1000.0,
                 },
+                easing: match mood {
+                    Mood::Calm => EaseKind::EaseInOut, // Calm eases in and out of every move
+                    Mood::Rage => EaseKind::EaseOut,   // Rage snaps to speed straight away
+                    _ => EaseKind::Linear,
+                },
                 ..default()
             },
             // Physics components
@@ -731,22 +1446,18 @@ pub fn spawn_moodel_bundle(
             angular_velocity: AngularVelocity::default(),
             collision_layers: CollisionLayers::new(
                 COLLISION_LAYER_MOODEL,
-                COLLISION_LAYER_MOODEL | COLLISION_LAYER_OBSTACLE,
+                COLLISION_LAYER_MOODEL | COLLISION_LAYER_OBSTACLE | COLLISION_LAYER_TRIGGER,
             ),
             collision_events: CollisionEventsEnabled, // Enable collision events
-            restitution: Restitution::new(match mood {
-                Mood::Happy => 0.9, // Happy moodels bounce more
-                Mood::Rage => 0.7,  // Rage moodels have harder bounces
-                Mood::Sad => 0.3,   // Sad moodels barely bounce
-                _ => 0.6,           // Default bounce
-            }),
-            mass: Mass(2.0), // Give Moodels more mass for better collision physics
+            restitution: Restitution::new(mood.restitution()),
+            mass: Mass(mood.mass()), // Heavier moods are harder to knock around
             friction: Friction::new(0.1), // Light friction so they don't stick together
             gravity_scale: GravityScale(0.0), // No gravity for 2D top-down movement
             external_force: ExternalForce::default(), // For applying movement forces
+            external_impulse: ExternalImpulse::default(), // For collision knockback
         },
         // Add the new AI components directly to the entity
-        AiWanderState::new(),
+        AiWanderState::new(rng),
         AiMagnetism {
             vision_radius: 250.0,
             cohesion_strength: 0.05,
@@ -771,7 +1482,7 @@ pub fn select_mood(
     mut commands: Commands,
     q_selected: Query<Entity, With<Selected>>,
 ) {
-    if let Ok(old_selection) = q_selected.single() {
+    for old_selection in &q_selected {
         commands.entity(old_selection).remove::<Selected>();
     }
     // Add the `Selected` component to the entity that was clicked.
@@ -817,6 +1528,11 @@ pub fn create_mood_object_bundle(
             let square = Rectangle::new(30.0, 30.0);
             (meshes.add(square), Color::srgb(0.6, 0.6, 0.6)) // Gray
         }
+        Mood::Fear => {
+            // Pale green diamond for fear
+            let diamond = Rhombus::new(30.0, 45.0);
+            (meshes.add(diamond), Color::srgb(0.4, 0.85, 0.5)) // Pale green
+        }
     };
 
     (
@@ -828,7 +1544,186 @@ pub fn create_mood_object_bundle(
         // Physics components for collision detection
         RigidBody::Static,      // Static objects don't move
         Collider::circle(30.0), // Slightly larger than visual for better collision
-        CollisionLayers::default(),
+        CollisionLayers::new(COLLISION_LAYER_TRIGGER, COLLISION_LAYER_MOODEL),
         CollisionEventsEnabled,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+    use std::time::Duration;
+
+    /// An app with just enough in it to run the mood change systems.
+    fn mood_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .init_resource::<AiConfig>()
+            .init_resource::<MoodRules>()
+            .insert_resource(GameRng::new(1))
+            .insert_resource(MoodAssets {
+                neutral: default(),
+                calm: default(),
+                happy: default(),
+                rage: default(),
+                sad: default(),
+                fear: default(),
+            })
+            .add_event::<CollisionStarted>()
+            .add_event::<SetMood>()
+            .add_event::<MoodChanged>()
+            .add_event::<crate::audio::PlaySound>();
+        app
+    }
+
+    fn spawn_moodel(app: &mut App, mood: Mood, position: Vec2) -> Entity {
+        let wander_state = AiWanderState::new(&mut GameRng::new(1));
+        app.world_mut()
+            .spawn((
+                AiEntity,
+                mood,
+                MoodEntity::default(),
+                Sprite::default(),
+                wander_state,
+                Transform::from_translation(position.extend(0.0)),
+                ExternalImpulse::default(),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn zero_weight_moods_are_never_picked() {
+        let mut rng = GameRng::new(7);
+        let weights = MoodWeights(HashMap::from([
+            (Mood::Happy, 1.0),
+            (Mood::Calm, 3.0),
+            (Mood::Rage, 0.0),
+        ]));
+        let picks: Vec<Mood> = (0..1000)
+            .map(|_| Mood::random_weighted(&mut rng, &weights))
+            .collect();
+        assert!(
+            picks
+                .iter()
+                .all(|mood| matches!(mood, Mood::Happy | Mood::Calm))
+        );
+        let calm = picks.iter().filter(|mood| **mood == Mood::Calm).count();
+        assert!((650..850).contains(&calm), "picked Calm {calm} times");
+    }
+
+    #[test]
+    fn all_zero_weights_pick_uniformly() {
+        let mut rng = GameRng::new(7);
+        let weights = MoodWeights(Mood::all().into_iter().map(|mood| (mood, 0.0)).collect());
+        let mut counts: HashMap<Mood, usize> = HashMap::new();
+        for _ in 0..6000 {
+            *counts
+                .entry(Mood::random_weighted(&mut rng, &weights))
+                .or_default() += 1;
+        }
+        for mood in Mood::all() {
+            let count = counts.get(&mood).copied().unwrap_or(0);
+            assert!(
+                (800..1200).contains(&count),
+                "picked {mood:?} {count} times"
+            );
+        }
+    }
+
+    #[test]
+    fn rage_burns_out_after_enough_charge_hits() {
+        let mut app = mood_app();
+        let config = app.world().resource::<AiConfig>();
+        let (max_hits, burnout) = (
+            config.rage_max_hits_before_burnout,
+            config.rage_burnout_cooldown,
+        );
+        let charger = spawn_moodel(&mut app, Mood::Rage, Vec2::ZERO);
+        let target = spawn_moodel(&mut app, Mood::Happy, Vec2::new(50.0, 0.0));
+
+        for hit in 1..=max_hits {
+            app.world_mut()
+                .get_mut::<AiWanderState>(charger)
+                .unwrap()
+                .action = AiAction::Charging {
+                target_pos: Vec2::new(50.0, 0.0),
+            };
+            app.world_mut()
+                .send_event(CollisionStarted(charger, target));
+            app.world_mut()
+                .run_system_once(handle_collision_events)
+                .unwrap()
+                .unwrap();
+            // Each run reads every event still in the buffer, so only keep the new hit.
+            app.world_mut()
+                .resource_mut::<Events<CollisionStarted>>()
+                .clear();
+
+            let mood = *app.world().get::<Mood>(charger).unwrap();
+            let expected = if hit < max_hits {
+                Mood::Rage
+            } else {
+                Mood::Calm
+            };
+            assert_eq!(mood, expected, "after hit {hit}");
+        }
+
+        let wander_state = app.world().get::<AiWanderState>(charger).unwrap();
+        assert!(matches!(wander_state.action, AiAction::Wandering));
+        assert_eq!(wander_state.charge_hit_count, 0);
+        assert_eq!(
+            wander_state.ability_cooldown.duration(),
+            Duration::from_secs_f32(burnout)
+        );
+    }
+
+    #[test]
+    fn intensity_runs_from_the_base_color_to_the_deepest() {
+        let scheme = ColorScheme::Default;
+        for mood in Mood::all() {
+            let base = mood.color_for_scheme(scheme);
+            let mild = mood.color_at_intensity(scheme, 0.0).to_srgba();
+            assert!(
+                mild.to_vec4().distance(base.to_srgba().to_vec4()) < 1e-3,
+                "{mood:?} should start at its base color"
+            );
+
+            let base = Hsla::from(base);
+            let full = Hsla::from(mood.color_at_intensity(scheme, 1.0));
+            assert!((full.saturation - (base.saturation + 1.0) / 2.0).abs() < 1e-3);
+            assert!((full.lightness - base.lightness * 0.75).abs() < 1e-3);
+            // Intensity is clamped, so anything past 1.0 is the same full color
+            let past_full = Hsla::from(mood.color_at_intensity(scheme, 2.0));
+            assert!((past_full.lightness - full.lightness).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn mood_changed_fires_once_per_actual_change() {
+        let mut app = mood_app();
+        let moodel = spawn_moodel(&mut app, Mood::Neutral, Vec2::ZERO);
+        let mut set_mood = |mood: Mood| {
+            let world = app.world_mut();
+            world.send_event(SetMood {
+                entity: moodel,
+                mood,
+            });
+            world
+                .run_system_once(apply_set_mood_events)
+                .unwrap()
+                .unwrap();
+            world.resource_mut::<Events<SetMood>>().clear();
+            world
+                .resource_mut::<Events<MoodChanged>>()
+                .drain()
+                .map(|changed| (changed.from, changed.to))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(set_mood(Mood::Happy), vec![(Mood::Neutral, Mood::Happy)]);
+        // Setting the mood it already has is a no-op.
+        assert!(set_mood(Mood::Happy).is_empty());
+        assert_eq!(set_mood(Mood::Sad), vec![(Mood::Happy, Mood::Sad)]);
+    }
+}
@@ -0,0 +1,145 @@
+//! A small shape on each Moodel that differs by mood, so moods can be told apart without
+//! relying on color.
+//!
+//! Off by default, and toggled from the settings menu next to the color scheme.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems,
+    demo::{ai::AiEntity, mood::Mood},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ShowMoodBadges>();
+    app.init_resource::<ShowMoodBadges>();
+    app.register_type::<MoodBadge>();
+    app.init_resource::<MoodBadgeAssets>();
+
+    app.add_systems(
+        Update,
+        (
+            remove_mood_badges.run_if(not(mood_badges_shown)),
+            (attach_mood_badges, update_mood_badges)
+                .chain()
+                .run_if(mood_badges_shown),
+        )
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// Where the badge sits on the Moodel's sprite, before the Moodel's own scale.
+const BADGE_OFFSET: Vec3 = Vec3::new(50.0, 80.0, 0.2);
+/// The badge's size, before the Moodel's own scale.
+const BADGE_RADIUS: f32 = 22.0;
+
+/// Whether Moodels show a shape badge for their mood.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ShowMoodBadges(pub bool);
+
+fn mood_badges_shown(show: Res<ShowMoodBadges>) -> bool {
+    show.0
+}
+
+/// The child showing a Moodel's mood as a shape.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MoodBadge;
+
+/// One mesh per mood, plus a single material shared by every badge.
+#[derive(Resource)]
+struct MoodBadgeAssets {
+    neutral: Handle<Mesh>,
+    calm: Handle<Mesh>,
+    happy: Handle<Mesh>,
+    rage: Handle<Mesh>,
+    sad: Handle<Mesh>,
+    fear: Handle<Mesh>,
+    material: Handle<ColorMaterial>,
+}
+
+impl MoodBadgeAssets {
+    fn mesh(&self, mood: Mood) -> Handle<Mesh> {
+        match mood {
+            Mood::Neutral => self.neutral.clone(),
+            Mood::Calm => self.calm.clone(),
+            Mood::Happy => self.happy.clone(),
+            Mood::Rage => self.rage.clone(),
+            Mood::Sad => self.sad.clone(),
+            Mood::Fear => self.fear.clone(),
+        }
+    }
+}
+
+impl FromWorld for MoodBadgeAssets {
+    fn from_world(world: &mut World) -> Self {
+        let mut meshes = world.resource_mut::<Assets<Mesh>>();
+        let r = BADGE_RADIUS;
+        let neutral = meshes.add(Rectangle::from_length(r * 1.5));
+        let calm = meshes.add(RegularPolygon::new(r, 6));
+        let happy = meshes.add(RegularPolygon::new(r, 5));
+        let rage = meshes.add(Triangle2d::new(
+            Vec2::Y * r,
+            Vec2::new(-r, -r * 0.7),
+            Vec2::new(r, -r * 0.7),
+        ));
+        let sad = meshes.add(Circle::new(r * 0.8));
+        let fear = meshes.add(Rhombus::new(r * 1.2, r * 2.0));
+        let material = world
+            .resource_mut::<Assets<ColorMaterial>>()
+            .add(Color::WHITE);
+        Self {
+            neutral,
+            calm,
+            happy,
+            rage,
+            sad,
+            fear,
+            material,
+        }
+    }
+}
+
+fn attach_mood_badges(
+    mut commands: Commands,
+    badge_assets: Res<MoodBadgeAssets>,
+    moodel_query: Query<(Entity, &Mood, Option<&Children>), With<AiEntity>>,
+    badge_query: Query<(), With<MoodBadge>>,
+) {
+    for (entity, mood, children) in &moodel_query {
+        let has_badge = children
+            .is_some_and(|children| children.iter().any(|child| badge_query.contains(child)));
+        if has_badge {
+            continue;
+        }
+        commands.entity(entity).with_child((
+            Name::new("Mood Badge"),
+            MoodBadge,
+            Mesh2d(badge_assets.mesh(*mood)),
+            MeshMaterial2d(badge_assets.material.clone()),
+            Transform::from_translation(BADGE_OFFSET),
+        ));
+    }
+}
+
+/// Swaps the badge's shape when its Moodel's mood changes.
+fn update_mood_badges(
+    badge_assets: Res<MoodBadgeAssets>,
+    moodel_query: Query<(&Mood, &Children), (With<AiEntity>, Changed<Mood>)>,
+    mut badge_query: Query<&mut Mesh2d, With<MoodBadge>>,
+) {
+    for (mood, children) in &moodel_query {
+        for child in children.iter() {
+            if let Ok(mut mesh) = badge_query.get_mut(child) {
+                mesh.0 = badge_assets.mesh(*mood);
+            }
+        }
+    }
+}
+
+fn remove_mood_badges(mut commands: Commands, badge_query: Query<Entity, With<MoodBadge>>) {
+    for entity in &badge_query {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,133 @@
+//! Mood names shown above each Moodel, so moods can be told apart without relying on color.
+//!
+//! Off by default, and toggled from the settings menu.
+
+use bevy::prelude::*;
+
+use crate::{
+    AppSystems,
+    demo::{
+        ai::AiEntity,
+        level::FontHandle,
+        mood::{ColorScheme, Mood},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<ShowMoodLabels>();
+    app.init_resource::<ShowMoodLabels>();
+    app.register_type::<MoodLabel>();
+
+    app.add_systems(
+        Update,
+        (
+            remove_mood_labels.run_if(not(mood_labels_shown)),
+            (
+                attach_mood_labels,
+                update_mood_labels,
+                keep_mood_labels_upright,
+            )
+                .chain()
+                .run_if(mood_labels_shown),
+        )
+            .in_set(AppSystems::Update),
+    );
+}
+
+/// How far above the Moodel's center its label sits, in world units.
+const LABEL_OFFSET: f32 = 70.0;
+const LABEL_FONT_SIZE: f32 = 18.0;
+/// Drawn above the Moodel's sprite.
+const LABEL_Z: f32 = 0.5;
+
+/// Whether Moodels show the name of their mood above them.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ShowMoodLabels(pub bool);
+
+fn mood_labels_shown(show: Res<ShowMoodLabels>) -> bool {
+    show.0
+}
+
+/// The text child showing a Moodel's mood.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct MoodLabel;
+
+fn label_text(mood: Mood) -> String {
+    format!("{mood:?}")
+}
+
+fn attach_mood_labels(
+    mut commands: Commands,
+    font_handle: Res<FontHandle>,
+    scheme: Res<ColorScheme>,
+    moodel_query: Query<(Entity, &Mood, Option<&Children>), With<AiEntity>>,
+    label_query: Query<(), With<MoodLabel>>,
+) {
+    for (entity, mood, children) in &moodel_query {
+        let has_label = children
+            .is_some_and(|children| children.iter().any(|child| label_query.contains(child)));
+        if has_label {
+            continue;
+        }
+        commands.entity(entity).with_child((
+            Name::new("Mood Label"),
+            MoodLabel,
+            Text2d::new(label_text(*mood)),
+            TextFont {
+                font: font_handle.0.clone(),
+                font_size: LABEL_FONT_SIZE,
+                ..default()
+            },
+            TextColor(mood.color_for_scheme(*scheme)),
+            TextLayout::new_with_justify(JustifyText::Center),
+            Transform::from_xyz(0.0, LABEL_OFFSET, LABEL_Z),
+        ));
+    }
+}
+
+/// Relabels Moodels whose mood changed, and recolors every label when the color scheme changes.
+fn update_mood_labels(
+    scheme: Res<ColorScheme>,
+    moodel_query: Query<(Ref<Mood>, &Children), With<AiEntity>>,
+    mut label_query: Query<(&mut Text2d, &mut TextColor), With<MoodLabel>>,
+) {
+    for (mood, children) in &moodel_query {
+        if !mood.is_changed() && !scheme.is_changed() {
+            continue;
+        }
+        for child in children.iter() {
+            if let Ok((mut text, mut color)) = label_query.get_mut(child) {
+                text.0 = label_text(*mood);
+                color.0 = mood.color_for_scheme(*scheme);
+            }
+        }
+    }
+}
+
+/// Undoes the Moodel's rotation and scale, so labels stay level, the same size, and directly
+/// above their Moodel while it tilts and squashes.
+fn keep_mood_labels_upright(
+    moodel_query: Query<&Transform, (With<AiEntity>, Without<MoodLabel>)>,
+    mut label_query: Query<(&ChildOf, &mut Transform), With<MoodLabel>>,
+) {
+    for (child_of, mut transform) in &mut label_query {
+        let Ok(parent) = moodel_query.get(child_of.parent()) else {
+            continue;
+        };
+        let inverse_rotation = parent.rotation.inverse();
+        let inverse_scale = parent.scale.recip();
+        transform.rotation = inverse_rotation;
+        transform.scale = inverse_scale;
+        transform.translation =
+            (inverse_rotation * Vec3::new(0.0, LABEL_OFFSET, 0.0)) * inverse_scale;
+        transform.translation.z = LABEL_Z;
+    }
+}
+
+fn remove_mood_labels(mut commands: Commands, label_query: Query<Entity, With<MoodLabel>>) {
+    for entity in &label_query {
+        commands.entity(entity).despawn();
+    }
+}
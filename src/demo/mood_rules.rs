@@ -0,0 +1,239 @@
+//! Data-driven mood transition rules.
+//!
+//! The rules are loaded from `assets/config/mood.rules.json` (the `.rules.json`
+//! extension follows the `.level.json` convention used for levels) and hot-reload
+//! when the file changes. When the file is missing, the built-in defaults apply.
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::demo::mood::Mood;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<MoodRules>();
+    app.add_systems(Startup, load_mood_rules);
+    app.add_systems(Update, apply_loaded_mood_rules);
+}
+
+const MOOD_RULES_PATH: &str = "config/mood.rules.json";
+
+/// The active mood transition rules, used by the mood conversion systems.
+#[derive(Asset, TypePath, Resource, Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MoodRules {
+    /// What two colliding moods turn into.
+    pub interactions: Vec<InteractionRule>,
+    /// Where an isolated Moodel's mood drifts to.
+    pub isolation_decay: Vec<DecayRule>,
+    /// Seconds without interaction before a Moodel starts decaying.
+    pub isolation_delay: f32,
+    /// Rage holds on longer before it starts decaying.
+    pub rage_isolation_delay: f32,
+}
+
+/// The outcome of two moods colliding. The rule also covers the reverse order, with the
+/// results swapped, unless `symmetric` is set, in which case both orders give the same result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InteractionRule {
+    pub moods: (Mood, Mood),
+    pub outcomes: Vec<Outcome<(Mood, Mood)>>,
+    #[serde(default)]
+    pub symmetric: bool,
+}
+
+/// The moods an isolated Moodel in `from` can decay to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecayRule {
+    pub from: Mood,
+    pub outcomes: Vec<Outcome<Mood>>,
+}
+
+/// A possible result, picked with probability proportional to its weight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Outcome<T> {
+    pub result: T,
+    #[serde(default = "default_weight")]
+    pub weight: u32,
+}
+
+fn default_weight() -> u32 {
+    1
+}
+
+impl<T> Outcome<T> {
+    fn certain(result: T) -> Self {
+        Self { result, weight: 1 }
+    }
+
+    fn weighted(result: T, weight: u32) -> Self {
+        Self { result, weight }
+    }
+}
+
+impl InteractionRule {
+    fn one_way(moods: (Mood, Mood), result: (Mood, Mood)) -> Self {
+        Self {
+            moods,
+            outcomes: vec![Outcome::certain(result)],
+            symmetric: false,
+        }
+    }
+
+    fn two_way(moods: (Mood, Mood), result: (Mood, Mood)) -> Self {
+        Self {
+            symmetric: true,
+            ..Self::one_way(moods, result)
+        }
+    }
+}
+
+impl Default for MoodRules {
+    fn default() -> Self {
+        use Mood::*;
+        Self {
+            interactions: vec![
+                // Rage acts on others
+                InteractionRule::one_way((Rage, Calm), (Rage, Sad)),
+                InteractionRule::one_way((Rage, Happy), (Rage, Sad)),
+                InteractionRule::one_way((Rage, Neutral), (Rage, Rage)),
+                InteractionRule::one_way((Rage, Sad), (Rage, Sad)),
+                // Happy interactions
+                InteractionRule::two_way((Happy, Sad), (Happy, Calm)),
+                InteractionRule::two_way((Happy, Calm), (Happy, Happy)),
+                InteractionRule::two_way((Happy, Neutral), (Happy, Calm)),
+                // Calm interactions
+                InteractionRule::two_way((Calm, Sad), (Calm, Calm)),
+                InteractionRule::two_way((Calm, Neutral), (Calm, Calm)),
+                // Sad interactions
+                InteractionRule::two_way((Sad, Neutral), (Sad, Sad)),
+                // Fear interactions
+                InteractionRule::two_way((Calm, Fear), (Calm, Neutral)),
+                // Two Neutral Moodels have random outcomes
+                InteractionRule {
+                    moods: (Neutral, Neutral),
+                    outcomes: vec![
+                        Outcome::weighted((Sad, Sad), 15),
+                        Outcome::weighted((Rage, Rage), 15),
+                        Outcome::weighted((Happy, Happy), 35),
+                        Outcome::weighted((Calm, Calm), 35),
+                    ],
+                    symmetric: true,
+                },
+            ],
+            isolation_decay: vec![
+                DecayRule {
+                    from: Rage,
+                    outcomes: vec![Outcome::certain(Calm)],
+                },
+                DecayRule {
+                    from: Happy,
+                    outcomes: vec![Outcome::certain(Calm)],
+                },
+                DecayRule {
+                    from: Sad,
+                    outcomes: vec![Outcome::certain(Neutral)],
+                },
+                DecayRule {
+                    from: Fear,
+                    outcomes: vec![Outcome::certain(Neutral)],
+                },
+                DecayRule {
+                    from: Calm,
+                    outcomes: vec![Outcome::certain(Happy), Outcome::certain(Neutral)],
+                },
+                DecayRule {
+                    from: Neutral,
+                    outcomes: Mood::all().into_iter().map(Outcome::certain).collect(),
+                },
+            ],
+            isolation_delay: 2.0,
+            rage_isolation_delay: 6.0,
+        }
+    }
+}
+
+impl MoodRules {
+    /// Determines how two moods interact when they collide. Moods without a rule are unchanged.
+    pub fn interaction(&self, mood1: Mood, mood2: Mood, rng: &mut impl Rng) -> (Mood, Mood) {
+        for rule in &self.interactions {
+            if rule.moods == (mood1, mood2) {
+                return pick_outcome(&rule.outcomes, rng).unwrap_or((mood1, mood2));
+            }
+            if rule.moods == (mood2, mood1) {
+                let Some((result1, result2)) = pick_outcome(&rule.outcomes, rng) else {
+                    return (mood1, mood2);
+                };
+                return if rule.symmetric {
+                    (result1, result2)
+                } else {
+                    (result2, result1)
+                };
+            }
+        }
+        (mood1, mood2)
+    }
+
+    /// The mood an isolated Moodel decays to. Moods without a rule are unchanged.
+    pub fn decay(&self, mood: Mood, rng: &mut impl Rng) -> Mood {
+        self.isolation_decay
+            .iter()
+            .find(|rule| rule.from == mood)
+            .and_then(|rule| pick_outcome(&rule.outcomes, rng))
+            .unwrap_or(mood)
+    }
+
+    /// How long a Moodel in this mood must be isolated before it decays.
+    pub fn isolation_delay_for(&self, mood: Mood) -> f32 {
+        if mood == Mood::Rage {
+            self.rage_isolation_delay
+        } else {
+            self.isolation_delay
+        }
+    }
+}
+
+/// Picks one outcome at random, weighted. Returns `None` if there are no weighted outcomes.
+fn pick_outcome<T: Copy>(outcomes: &[Outcome<T>], rng: &mut impl Rng) -> Option<T> {
+    let total: u32 = outcomes.iter().map(|outcome| outcome.weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.random_range(0..total);
+    for outcome in outcomes {
+        if roll < outcome.weight {
+            return Some(outcome.result);
+        }
+        roll -= outcome.weight;
+    }
+    None
+}
+
+#[derive(Resource)]
+struct MoodRulesHandle(Handle<MoodRules>);
+
+fn load_mood_rules(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(MoodRulesHandle(asset_server.load(MOOD_RULES_PATH)));
+}
+
+/// Copies the rules file into the active rules when it loads or is edited.
+fn apply_loaded_mood_rules(
+    mut asset_events: EventReader<AssetEvent<MoodRules>>,
+    rules_handle: Option<Res<MoodRulesHandle>>,
+    rules_assets: Res<Assets<MoodRules>>,
+    mut rules: ResMut<MoodRules>,
+) {
+    let Some(rules_handle) = rules_handle else {
+        return;
+    };
+    for event in asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } = event {
+            if *id == rules_handle.0.id() {
+                if let Some(loaded_rules) = rules_assets.get(*id) {
+                    info!("Mood rules loaded from {}", MOOD_RULES_PATH);
+                    *rules = loaded_rules.clone();
+                }
+            }
+        }
+    }
+}
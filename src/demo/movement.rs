@@ -10,8 +10,9 @@
 //! - Constrain movement within defined play area boundaries.
 //! - Smooth acceleration and deceleration for fluid movement.
 
-use bevy::{prelude::*, window::PrimaryWindow};
 use avian2d::prelude::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{AppSystems, PausableSystems};
 
@@ -21,11 +22,18 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<PlayAreaBounded>();
     app.register_type::<MovementSmoothing>();
     app.init_resource::<PlayArea>();
+    app.register_type::<MovementLimits>();
+    app.init_resource::<MovementLimits>();
+    // Shows up in the dev inspector, so it can be tuned live.
+    app.register_type::<PlayAreaDrag>();
+    app.init_resource::<PlayAreaDrag>();
 
     app.add_systems(
         Update,
         (
             apply_movement_with_smoothing,
+            apply_play_area_drag,
+            clamp_overspeed,
             apply_play_area_boundaries,
             apply_screen_wrap,
         )
@@ -59,6 +67,38 @@ impl Default for MovementController {
     }
 }
 
+/// Caps how fast physics impulses can push entities with a [`MovementController`].
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct MovementLimits {
+    /// The highest speed allowed, as a multiple of the entity's `max_speed`. Above 1.0 so brief
+    /// knockbacks and throws still read as fast.
+    pub overspeed_factor: f32,
+}
+
+impl Default for MovementLimits {
+    fn default() -> Self {
+        Self {
+            overspeed_factor: 3.0,
+        }
+    }
+}
+
+/// Friction applied to every entity with a [`MovementController`], so idle Moodels settle
+/// instead of drifting forever.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct PlayAreaDrag {
+    /// How much of its velocity an entity loses per second.
+    pub linear: f32,
+}
+
+impl Default for PlayAreaDrag {
+    fn default() -> Self {
+        Self { linear: 0.5 }
+    }
+}
+
 /// Resource defining the playable area boundaries
 #[derive(Resource, Reflect)]
 #[reflect(Resource)]
@@ -67,6 +107,8 @@ pub struct PlayArea {
     pub center: Vec2,
     /// Size of the play area (width, height)
     pub size: Vec2,
+    /// The outline of the play area within `size`
+    pub shape: PlayAreaShape,
 }
 
 impl Default for PlayArea {
@@ -74,10 +116,46 @@ impl Default for PlayArea {
         Self {
             center: Vec2::ZERO,
             size: Vec2::new(800.0, 600.0), // Default play area size
+            shape: PlayAreaShape::Rect,
+        }
+    }
+}
+
+impl PlayArea {
+    /// Whether `position` is inside the play area.
+    pub fn contains(&self, position: Vec2) -> bool {
+        match self.shape {
+            PlayAreaShape::Rect => {
+                Rect::from_center_size(self.center, self.size).contains(position)
+            }
+            PlayAreaShape::Circle { radius } => position.distance(self.center) <= radius,
+        }
+    }
+
+    /// The closest point to `position` inside the play area.
+    pub fn clamp(&self, position: Vec2) -> Vec2 {
+        match self.shape {
+            PlayAreaShape::Rect => {
+                let half_size = self.size / 2.0;
+                position.clamp(self.center - half_size, self.center + half_size)
+            }
+            PlayAreaShape::Circle { radius } => {
+                self.center + (position - self.center).clamp_length_max(radius)
+            }
         }
     }
 }
 
+/// The outline of the play area. Round arenas keep Moodels inside a circle around the center.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum PlayAreaShape {
+    #[default]
+    Rect,
+    Circle {
+        radius: f32,
+    },
+}
+
 /// Component for entities that should be constrained to the play area
 #[derive(Component, Reflect)]
 #[reflect(Component)]
@@ -102,19 +180,53 @@ pub struct MovementSmoothing {
     pub acceleration: f32,
     /// Deceleration rate (how quickly to stop)
     pub deceleration: f32,
+    /// Shape of the speed ramp between the current and target velocity
+    pub easing: EaseKind,
+}
+
+/// How a velocity change is spread over its ramp.
+#[derive(Reflect, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EaseKind {
+    /// Constant rate of change
+    #[default]
+    Linear,
+    /// Starts gently and speeds up
+    EaseIn,
+    /// Starts quickly and settles gently
+    EaseOut,
+    /// Gentle at both ends
+    EaseInOut,
+}
+
+/// Lowest fraction of the base rate an eased ramp moves at, so it never stalls at its ends.
+const MIN_EASE_SLOPE: f32 = 0.1;
+
+impl EaseKind {
+    /// Slope of the eased curve at `progress` (0 to 1), as a multiplier on the base rate.
+    /// Each curve averages to 1, so a full ramp takes as long as a linear one.
+    pub fn slope(self, progress: f32) -> f32 {
+        let p = progress.clamp(0.0, 1.0);
+        let slope = match self {
+            EaseKind::Linear => 1.0,
+            EaseKind::EaseIn => 2.0 * p,
+            EaseKind::EaseOut => 2.0 * (1.0 - p),
+            EaseKind::EaseInOut => 6.0 * p * (1.0 - p),
+        };
+        slope.max(MIN_EASE_SLOPE)
+    }
 }
 
 impl Default for MovementSmoothing {
     fn default() -> Self {
         Self {
             current_velocity: Vec2::ZERO,
-            acceleration: 800.0, // Units per second squared
+            acceleration: 800.0,  // Units per second squared
             deceleration: 1200.0, // Units per second squared
+            easing: EaseKind::Linear,
         }
     }
 }
 
-
 fn apply_movement_with_smoothing(
     time: Res<Time>,
     mut movement_query: Query<(
@@ -125,30 +237,38 @@ fn apply_movement_with_smoothing(
     )>,
 ) -> Result {
     let delta = time.delta_secs();
-    
+
     for (controller, current_velocity, mut force, smoothing) in &mut movement_query {
         // Clear existing forces to prevent accumulation
         force.clear();
-        
+
         if let Some(mut smoothing) = smoothing {
             // Calculate target velocity
             let target_velocity = controller.max_speed * controller.intent;
-            
+
             // Apply acceleration or deceleration
             let diff = target_velocity - smoothing.current_velocity;
             let diff_magnitude = diff.length();
-            
+
             if diff_magnitude > 0.01 {
                 // Determine if we're accelerating or decelerating
-                let is_accelerating = target_velocity.length() > smoothing.current_velocity.length();
+                let is_accelerating =
+                    target_velocity.length() > smoothing.current_velocity.length();
                 let rate = if is_accelerating {
                     smoothing.acceleration
                 } else {
                     smoothing.deceleration
                 };
-                
+
+                // How far along the ramp we are, measured against the larger of the two speeds
+                let span = target_velocity
+                    .length()
+                    .max(smoothing.current_velocity.length())
+                    .max(diff_magnitude);
+                let progress = 1.0 - diff_magnitude / span;
+
                 // Apply smooth acceleration
-                let max_change = rate * delta;
+                let max_change = rate * smoothing.easing.slope(progress) * delta;
                 if diff_magnitude <= max_change {
                     smoothing.current_velocity = target_velocity;
                 } else {
@@ -158,7 +278,7 @@ fn apply_movement_with_smoothing(
             } else {
                 smoothing.current_velocity = target_velocity;
             }
-            
+
             // Calculate force needed to reach desired velocity
             let velocity_diff = smoothing.current_velocity - current_velocity.0;
             force.apply_force(velocity_diff * 20.0); // Even gentler force to allow proper collision physics
@@ -172,6 +292,37 @@ fn apply_movement_with_smoothing(
     Ok(())
 }
 
+/// Adds a damping force against each entity's velocity. Runs after the movement forces are
+/// reset for the frame, so it adds to them rather than being cleared.
+fn apply_play_area_drag(
+    drag: Res<PlayAreaDrag>,
+    mut movement_query: Query<
+        (&LinearVelocity, &mut ExternalForce, Option<&Mass>),
+        With<MovementController>,
+    >,
+) {
+    if drag.linear <= 0.0 {
+        return;
+    }
+    for (velocity, mut force, mass) in &mut movement_query {
+        // Scaled by mass so every entity slows down at the same rate.
+        let mass = mass.map_or(1.0, |mass| mass.0);
+        force.apply_force(-velocity.0 * drag.linear * mass);
+    }
+}
+
+fn clamp_overspeed(
+    limits: Res<MovementLimits>,
+    mut movement_query: Query<(&MovementController, &mut LinearVelocity)>,
+) {
+    for (controller, mut velocity) in &mut movement_query {
+        let max_speed = controller.max_speed * limits.overspeed_factor;
+        if velocity.0.length_squared() > max_speed * max_speed {
+            velocity.0 = velocity.0.clamp_length_max(max_speed);
+        }
+    }
+}
+
 fn apply_play_area_boundaries(
     play_area: Res<PlayArea>,
     mut bounded_query: Query<(
@@ -179,44 +330,66 @@ fn apply_play_area_boundaries(
         &mut LinearVelocity,
         Option<&mut MovementSmoothing>,
         &PlayAreaBounded,
+        Option<&ScreenWrap>,
     )>,
 ) -> Result {
     let half_size = play_area.size / 2.0;
     let min_bounds = play_area.center - half_size;
     let max_bounds = play_area.center + half_size;
-    
-    for (mut transform, mut velocity, smoothing, bounded) in &mut bounded_query {
+
+    for (mut transform, mut velocity, smoothing, bounded, wrap) in &mut bounded_query {
         let mut position = transform.translation.xy();
         let mut vel = velocity.0;
         let mut bounced = false;
-        
+
+        if let PlayAreaShape::Circle { radius } = play_area.shape {
+            let offset = position - play_area.center;
+            if offset.length() > radius {
+                // Put it back on the circumference and reflect the outward part of its velocity
+                let normal = offset.normalize();
+                position = play_area.center + normal * radius;
+                let outward_speed = vel.dot(normal);
+                if outward_speed > 0.0 {
+                    vel -= normal * outward_speed * (1.0 + bounded.restitution);
+                }
+                transform.translation = position.extend(transform.translation.z);
+                velocity.0 = vel;
+                if let Some(mut smoothing) = smoothing {
+                    smoothing.current_velocity = vel;
+                }
+            }
+            continue;
+        }
+        // Axes that wrap around are left to `apply_screen_wrap`
+        let (bound_x, bound_y) = wrap.map_or((true, true), |wrap| (!wrap.wrap_x, !wrap.wrap_y));
+
         // Check X bounds
-        if position.x < min_bounds.x {
+        if bound_x && position.x < min_bounds.x {
             position.x = min_bounds.x;
             vel.x = vel.x.abs() * bounded.restitution;
             bounced = true;
-        } else if position.x > max_bounds.x {
+        } else if bound_x && position.x > max_bounds.x {
             position.x = max_bounds.x;
             vel.x = -vel.x.abs() * bounded.restitution;
             bounced = true;
         }
-        
+
         // Check Y bounds
-        if position.y < min_bounds.y {
+        if bound_y && position.y < min_bounds.y {
             position.y = min_bounds.y;
             vel.y = vel.y.abs() * bounded.restitution;
             bounced = true;
-        } else if position.y > max_bounds.y {
+        } else if bound_y && position.y > max_bounds.y {
             position.y = max_bounds.y;
             vel.y = -vel.y.abs() * bounded.restitution;
             bounced = true;
         }
-        
+
         // Apply changes
         if bounced {
             transform.translation = position.extend(transform.translation.z);
             velocity.0 = vel;
-            
+
             // Update smoothing component if present
             if let Some(mut smoothing) = smoothing {
                 smoothing.current_velocity = vel;
@@ -226,20 +399,147 @@ fn apply_play_area_boundaries(
     Ok(())
 }
 
+/// Wraps an entity that leaves the play area around to the opposite edge, on either or both axes.
+/// In a round play area the entity reappears on the far side of the circle, and both axes wrap.
 #[derive(Component, Reflect)]
 #[reflect(Component)]
-pub struct ScreenWrap;
+pub struct ScreenWrap {
+    pub wrap_x: bool,
+    pub wrap_y: bool,
+}
+
+impl Default for ScreenWrap {
+    fn default() -> Self {
+        Self {
+            wrap_x: true,
+            wrap_y: true,
+        }
+    }
+}
 
 fn apply_screen_wrap(
-    window: Single<&Window, With<PrimaryWindow>>,
-    mut wrap_query: Query<&mut Transform, With<ScreenWrap>>,
+    play_area: Res<PlayArea>,
+    mut wrap_query: Query<(&mut Transform, &ScreenWrap)>,
 ) -> Result {
-    let size = window.size() + 256.0;
-    let half_size = size / 2.0;
-    for mut transform in &mut wrap_query {
-        let position = transform.translation.xy();
-        let wrapped = (position + half_size).rem_euclid(size) - half_size;
-        transform.translation = wrapped.extend(transform.translation.z);
+    for (mut transform, wrap) in &mut wrap_query {
+        let position = wrap_position(&play_area, transform.translation.xy(), wrap);
+        transform.translation = position.extend(transform.translation.z);
     }
     Ok(())
 }
+
+/// Where an entity at `position` ends up once wrapped around the play area.
+fn wrap_position(play_area: &PlayArea, position: Vec2, wrap: &ScreenWrap) -> Vec2 {
+    match play_area.shape {
+        PlayAreaShape::Rect => {
+            let min_bounds = play_area.center - play_area.size / 2.0;
+            let wrapped = (position - min_bounds).rem_euclid(play_area.size) + min_bounds;
+            Vec2::new(
+                if wrap.wrap_x { wrapped.x } else { position.x },
+                if wrap.wrap_y { wrapped.y } else { position.y },
+            )
+        }
+        PlayAreaShape::Circle { radius } => {
+            let offset = position - play_area.center;
+            if offset.length() <= radius {
+                return position;
+            }
+            play_area.center - offset.normalize() * radius
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn round_play_area() -> PlayArea {
+        PlayArea {
+            center: Vec2::ZERO,
+            size: Vec2::splat(200.0),
+            shape: PlayAreaShape::Circle { radius: 100.0 },
+        }
+    }
+
+    #[test]
+    fn wrap_to_the_far_side_of_a_circle() {
+        let play_area = round_play_area();
+        let wrap = ScreenWrap::default();
+        // Just past the edge on a diagonal, inside the bounding square
+        let position = Vec2::splat(80.0);
+        let wrapped = wrap_position(&play_area, position, &wrap);
+        assert!((wrapped + position.normalize() * 100.0).length() < 1e-3);
+        // Positions inside the circle stay put
+        let inside = Vec2::new(50.0, -20.0);
+        assert_eq!(wrap_position(&play_area, inside, &wrap), inside);
+    }
+
+    #[test]
+    fn clamp_projects_onto_the_circle() {
+        let play_area = round_play_area();
+        // Inside the bounding square but outside the circle
+        let clamped = play_area.clamp(Vec2::new(90.0, 90.0));
+        assert!((clamped.length() - 100.0).abs() < 1e-3);
+        assert!((clamped.normalize() - Vec2::new(90.0, 90.0).normalize()).length() < 1e-5);
+        let inside = Vec2::new(-30.0, 60.0);
+        assert_eq!(play_area.clamp(inside), inside);
+    }
+
+    #[test]
+    fn ease_slopes_at_the_ends() {
+        assert_eq!(EaseKind::Linear.slope(0.0), 1.0);
+        assert_eq!(EaseKind::Linear.slope(1.0), 1.0);
+        assert_eq!(EaseKind::EaseIn.slope(0.0), MIN_EASE_SLOPE);
+        assert_eq!(EaseKind::EaseIn.slope(1.0), 2.0);
+        assert_eq!(EaseKind::EaseOut.slope(0.0), 2.0);
+        assert_eq!(EaseKind::EaseOut.slope(1.0), MIN_EASE_SLOPE);
+        assert_eq!(EaseKind::EaseInOut.slope(0.0), MIN_EASE_SLOPE);
+        assert_eq!(EaseKind::EaseInOut.slope(1.0), MIN_EASE_SLOPE);
+        // Progress outside 0 to 1 is clamped.
+        assert_eq!(EaseKind::EaseIn.slope(2.0), 2.0);
+    }
+
+    #[test]
+    fn eased_ramps_always_move_forward() {
+        let steps: Vec<f32> = (0..=100).map(|i| i as f32 / 100.0).collect();
+        for kind in [
+            EaseKind::Linear,
+            EaseKind::EaseIn,
+            EaseKind::EaseOut,
+            EaseKind::EaseInOut,
+        ] {
+            // A positive slope everywhere means the eased value only ever increases.
+            assert!(steps.iter().all(|p| kind.slope(*p) >= MIN_EASE_SLOPE));
+        }
+        for pair in steps.windows(2) {
+            assert!(EaseKind::EaseIn.slope(pair[1]) >= EaseKind::EaseIn.slope(pair[0]));
+            assert!(EaseKind::EaseOut.slope(pair[1]) <= EaseKind::EaseOut.slope(pair[0]));
+        }
+    }
+
+    #[test]
+    fn overspeed_is_capped_to_a_multiple_of_max_speed() {
+        let mut app = App::new();
+        app.init_resource::<MovementLimits>();
+        let controller = || MovementController {
+            max_speed: 100.0,
+            ..default()
+        };
+        let fast = app
+            .world_mut()
+            .spawn((controller(), LinearVelocity(Vec2::new(600.0, 800.0))))
+            .id();
+        let slow = app
+            .world_mut()
+            .spawn((controller(), LinearVelocity(Vec2::new(120.0, 160.0))))
+            .id();
+        app.world_mut().run_system_once(clamp_overspeed).unwrap();
+
+        let velocity = |entity| app.world().get::<LinearVelocity>(entity).unwrap().0;
+        // Capped at three times the max speed, in the same direction
+        assert!((velocity(fast) - Vec2::new(180.0, 240.0)).length() < 1e-3);
+        // Already under the cap, so left alone
+        assert_eq!(velocity(slow), Vec2::new(120.0, 160.0));
+    }
+}
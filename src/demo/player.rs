@@ -1,10 +1,18 @@
 //! Player-specific behavior.
 
-use bevy::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
 
 use crate::{
     AppSystems, PausableSystems,
     asset_tracking::LoadResource,
+    demo::{
+        edit_history::{EditAction, EditHistory},
+        level::{LevelPalette, ObstacleSource, spawn_circle_obstacle, spawn_wall},
+        movement::{PlayArea, PlayAreaShape},
+        player_input::cursor_world_position,
+    },
+    keybindings::{Action, ActionInput},
+    screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -13,6 +21,11 @@ pub(super) fn plugin(app: &mut App) {
     app.register_type::<PlayerAssets>();
     app.load_resource::<PlayerAssets>();
 
+    app.register_type::<ShapeTool>();
+    app.init_resource::<ShapeTool>();
+    app.register_type::<GridSnap>();
+    app.init_resource::<GridSnap>();
+
     // Shape tool input systems
     app.add_systems(
         Update,
@@ -20,54 +33,391 @@ pub(super) fn plugin(app: &mut App) {
             handle_line_tool_input,
             handle_box_tool_input,
             handle_circle_tool_input,
+            handle_eraser_tool_input,
+            toggle_grid_snap,
         )
             .in_set(AppSystems::RecordInput)
             .in_set(PausableSystems),
     );
+    app.add_systems(
+        Update,
+        (draw_shape_tool_input, preview_shape_tool, draw_snap_grid)
+            .chain()
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), reset_shape_tool);
+}
+
+/// Walls drawn with the box tool smaller than this on either side are discarded.
+const MIN_BOX_SIZE: f32 = 10.0;
+/// Pillars drawn with the circle tool smaller than this radius are discarded.
+const MIN_CIRCLE_RADIUS: f32 = 10.0;
+
+/// The editor tools for placing and removing obstacles.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawTool {
+    Box,
+    Circle,
+    /// Clicking an obstacle removes it.
+    Eraser,
+}
+
+/// State of the editor tools.
+#[derive(Resource, Reflect, Debug, Default)]
+#[reflect(Resource)]
+pub struct ShapeTool {
+    pub active: Option<DrawTool>,
+    /// Where the current drag started, in world coordinates.
+    pub anchor: Option<Vec2>,
+}
+
+impl ShapeTool {
+    /// Switches to `tool`, or back to no tool if it was already selected.
+    fn toggle(&mut self, tool: DrawTool) {
+        self.active = if self.active == Some(tool) {
+            None
+        } else {
+            Some(tool)
+        };
+        self.anchor = None;
+        info!("Selected draw tool: {:?}", self.active);
+    }
+}
+
+/// Snaps editor tool placement to a grid, for tidy and symmetric levels.
+#[derive(Resource, Reflect, Debug)]
+#[reflect(Resource)]
+pub struct GridSnap {
+    pub enabled: bool,
+    /// The width of a grid cell, in world units.
+    pub size: f32,
+}
+
+impl Default for GridSnap {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            size: 25.0,
+        }
+    }
+}
+
+impl GridSnap {
+    /// Snaps `pos` to the grid if snapping is enabled.
+    pub fn apply(&self, pos: Vec2) -> Vec2 {
+        if self.enabled {
+            snap_to_grid(pos, self.size)
+        } else {
+            pos
+        }
+    }
+}
+
+/// Rounds `pos` to the nearest multiple of `size` on each axis.
+pub fn snap_to_grid(pos: Vec2, size: f32) -> Vec2 {
+    if size <= 0.0 {
+        return pos;
+    }
+    (pos / size).round() * size
+}
+
+/// How many halvings [`box_in_play_area`] takes to fit a box inside a round play area.
+const BOX_FIT_STEPS: usize = 16;
+
+/// The obstacle a drag would produce, already clamped to the play area.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DrawnShape {
+    Box(Rect),
+    Circle { center: Vec2, radius: f32 },
+}
+
+impl DrawnShape {
+    /// Returns `None` for tools that don't draw shapes.
+    fn new(tool: DrawTool, anchor: Vec2, cursor: Vec2, play_area: &PlayArea) -> Option<Self> {
+        match tool {
+            DrawTool::Box => Some(DrawnShape::Box(box_in_play_area(anchor, cursor, play_area))),
+            DrawTool::Circle => {
+                let center = play_area.clamp(anchor);
+                Some(DrawnShape::Circle {
+                    center,
+                    radius: circle_radius(center, cursor, play_area),
+                })
+            }
+            DrawTool::Eraser => None,
+        }
+    }
+
+    fn is_big_enough(&self) -> bool {
+        match self {
+            DrawnShape::Box(rect) => rect.width() >= MIN_BOX_SIZE && rect.height() >= MIN_BOX_SIZE,
+            DrawnShape::Circle { radius, .. } => *radius >= MIN_CIRCLE_RADIUS,
+        }
+    }
+}
+
+/// The box dragged from `anchor` to `cursor`, cut down so it stays inside the play area. In a
+/// round play area the box shrinks towards `anchor` until its corners fit inside the circle.
+fn box_in_play_area(anchor: Vec2, cursor: Vec2, play_area: &PlayArea) -> Rect {
+    if play_area.shape == PlayAreaShape::Rect {
+        let bounds = Rect::from_center_size(play_area.center, play_area.size);
+        return Rect::from_corners(anchor, cursor).intersect(bounds);
+    }
+    let anchor = play_area.clamp(anchor);
+    let box_to = |t: f32| Rect::from_corners(anchor, anchor.lerp(cursor, t));
+    let fits = |rect: Rect| {
+        [
+            rect.min,
+            rect.max,
+            Vec2::new(rect.min.x, rect.max.y),
+            Vec2::new(rect.max.x, rect.min.y),
+        ]
+        .into_iter()
+        .all(|corner| play_area.contains(corner))
+    };
+    if fits(box_to(1.0)) {
+        return box_to(1.0);
+    }
+    // Search for the largest box that still fits
+    let (mut inside, mut outside) = (0.0, 1.0);
+    for _ in 0..BOX_FIT_STEPS {
+        let middle = (inside + outside) / 2.0;
+        if fits(box_to(middle)) {
+            inside = middle;
+        } else {
+            outside = middle;
+        }
+    }
+    box_to(inside)
+}
+
+/// The radius of a circle dragged from `center` to `cursor`, shrunk so it stays inside the
+/// play area.
+fn circle_radius(center: Vec2, cursor: Vec2, play_area: &PlayArea) -> f32 {
+    let to_edge = match play_area.shape {
+        PlayAreaShape::Rect => {
+            let bounds = Rect::from_center_size(play_area.center, play_area.size);
+            (center - bounds.min).min(bounds.max - center).min_element()
+        }
+        PlayAreaShape::Circle { radius } => radius - center.distance(play_area.center),
+    };
+    center.distance(cursor).min(to_edge.max(0.0))
 }
 
 /// The player marker (no longer a visual entity).
 pub fn player() -> impl Bundle {
-    (
-        Name::new("Player"),
-        Player,
-    )
+    (Name::new("Player"), Player)
 }
 
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
 #[reflect(Component)]
 struct Player;
 
-/// Handle Line Tool input (Q key)
-fn handle_line_tool_input(
-    input: Res<ButtonInput<KeyCode>>,
-) {
-    if input.just_pressed(KeyCode::KeyQ) {
+/// Handle Line Tool input (Q key by default)
+fn handle_line_tool_input(input: ActionInput) {
+    if input.just_pressed(Action::ToolLine) {
         // TODO: Implement line tool
         info!("Line tool activated!");
     }
 }
 
-/// Handle Box Tool input (W key)
-fn handle_box_tool_input(
-    input: Res<ButtonInput<KeyCode>>,
+/// Handle Box Tool input (W key by default)
+fn handle_box_tool_input(input: ActionInput, mut shape_tool: ResMut<ShapeTool>) {
+    if input.just_pressed(Action::ToolBox) {
+        shape_tool.toggle(DrawTool::Box);
+    }
+}
+
+/// Handle Circle Tool input (E key by default)
+fn handle_circle_tool_input(input: ActionInput, mut shape_tool: ResMut<ShapeTool>) {
+    if input.just_pressed(Action::ToolCircle) {
+        shape_tool.toggle(DrawTool::Circle);
+    }
+}
+
+/// Handle Eraser Tool input (X key by default)
+fn handle_eraser_tool_input(input: ActionInput, mut shape_tool: ResMut<ShapeTool>) {
+    if input.just_pressed(Action::ToolEraser) {
+        shape_tool.toggle(DrawTool::Eraser);
+    }
+}
+
+/// An observer on obstacles that removes them when clicked with the eraser tool.
+pub fn erase_obstacle(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    shape_tool: Res<ShapeTool>,
+    mut history: ResMut<EditHistory>,
+    obstacle_query: Query<&ObstacleSource>,
 ) {
-    if input.just_pressed(KeyCode::KeyW) {
-        // TODO: Implement box tool
-        info!("Box tool activated!");
+    if shape_tool.active != Some(DrawTool::Eraser)
+        || trigger.event().button != PointerButton::Primary
+    {
+        return;
     }
+    let entity = trigger.target();
+    let Ok(source) = obstacle_query.get(entity) else {
+        return;
+    };
+    // Despawning takes the collider with it, so the AI stops avoiding it right away.
+    commands.entity(entity).despawn();
+    history.record(EditAction::RemovedObstacle(source.0.clone()));
+    info!("Erased obstacle {:?}", entity);
 }
 
-/// Handle Circle Tool input (E key)
-fn handle_circle_tool_input(
-    input: Res<ButtonInput<KeyCode>>,
+/// Toggle grid snapping (G key by default)
+fn toggle_grid_snap(input: ActionInput, mut grid_snap: ResMut<GridSnap>) {
+    if input.just_pressed(Action::ToggleGridSnap) {
+        grid_snap.enabled = !grid_snap.enabled;
+        info!(
+            "Grid snapping {}",
+            if grid_snap.enabled {
+                "enabled"
+            } else {
+                "disabled"
+            }
+        );
+    }
+}
+
+/// Left-mouse drag with a shape tool active draws an obstacle.
+#[allow(clippy::too_many_arguments)]
+fn draw_shape_tool_input(
+    mut commands: Commands,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    play_area: Res<PlayArea>,
+    palette: Res<LevelPalette>,
+    grid_snap: Res<GridSnap>,
+    mut shape_tool: ResMut<ShapeTool>,
+    mut history: ResMut<EditHistory>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    if input.just_pressed(KeyCode::KeyE) {
-        // TODO: Implement circle tool
-        info!("Circle tool activated!");
+    let Some(tool) = shape_tool.active else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_world_position(&window, camera, camera_transform)
+        .map(|cursor| grid_snap.apply(cursor))
+    else {
+        return;
+    };
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        shape_tool.anchor = Some(cursor);
+    }
+    if mouse_buttons.just_released(MouseButton::Left) {
+        let Some(anchor) = shape_tool.anchor.take() else {
+            return;
+        };
+        let Some(shape) =
+            DrawnShape::new(tool, anchor, cursor, &play_area).filter(DrawnShape::is_big_enough)
+        else {
+            return;
+        };
+        let obstacle = match shape {
+            DrawnShape::Box(rect) => {
+                info!("Drew a {} wall at {}", rect.size(), rect.center());
+                spawn_wall(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    &palette,
+                    rect.center(),
+                    rect.size(),
+                )
+            }
+            DrawnShape::Circle { center, radius } => {
+                info!("Drew a pillar of radius {} at {}", radius, center);
+                spawn_circle_obstacle(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    &palette,
+                    center,
+                    radius,
+                )
+            }
+        };
+        history.record(EditAction::SpawnedObstacle(obstacle));
+    }
+}
+
+/// Outlines the obstacle being dragged out, in red while it is too small to keep.
+fn preview_shape_tool(
+    mut gizmos: Gizmos,
+    shape_tool: Res<ShapeTool>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    play_area: Res<PlayArea>,
+    grid_snap: Res<GridSnap>,
+) {
+    let (Some(tool), Some(anchor)) = (shape_tool.active, shape_tool.anchor) else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_world_position(&window, camera, camera_transform)
+        .map(|cursor| grid_snap.apply(cursor))
+    else {
+        return;
+    };
+    let Some(shape) = DrawnShape::new(tool, anchor, cursor, &play_area) else {
+        return;
+    };
+    let color = if shape.is_big_enough() {
+        Color::WHITE
+    } else {
+        Color::srgb(0.9, 0.2, 0.2)
+    };
+    match shape {
+        DrawnShape::Box(rect) => gizmos.rect_2d(rect.center(), rect.size(), color),
+        DrawnShape::Circle { center, radius } => {
+            gizmos.circle_2d(center, radius, color);
+        }
+    }
+}
+
+/// Draws faint grid lines across the play area while an editor tool is in use.
+fn draw_snap_grid(
+    mut gizmos: Gizmos,
+    shape_tool: Res<ShapeTool>,
+    grid_snap: Res<GridSnap>,
+    play_area: Res<PlayArea>,
+) {
+    if shape_tool.active.is_none() || !grid_snap.enabled || grid_snap.size <= 0.0 {
+        return;
+    }
+    let bounds = Rect::from_center_size(play_area.center, play_area.size);
+    let color = Color::srgba(1.0, 1.0, 1.0, 0.08);
+    // Lines sit on multiples of the cell size, matching `snap_to_grid`.
+    let first = (bounds.min / grid_snap.size).ceil() * grid_snap.size;
+    let mut x = first.x;
+    while x <= bounds.max.x {
+        gizmos.line_2d(
+            Vec2::new(x, bounds.min.y),
+            Vec2::new(x, bounds.max.y),
+            color,
+        );
+        x += grid_snap.size;
+    }
+    let mut y = first.y;
+    while y <= bounds.max.y {
+        gizmos.line_2d(
+            Vec2::new(bounds.min.x, y),
+            Vec2::new(bounds.max.x, y),
+            color,
+        );
+        y += grid_snap.size;
     }
 }
 
+fn reset_shape_tool(mut shape_tool: ResMut<ShapeTool>) {
+    *shape_tool = ShapeTool::default();
+}
+
 #[derive(Resource, Asset, Clone, Reflect)]
 #[reflect(Resource)]
 pub struct PlayerAssets {
@@ -91,3 +441,57 @@ impl FromWorld for PlayerAssets {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_to_grid_rounds_to_the_nearest_cell() {
+        assert_eq!(
+            snap_to_grid(Vec2::new(-23.0, -7.0), 10.0),
+            Vec2::new(-20.0, -10.0)
+        );
+        // Halfway points round away from zero on both sides of the origin
+        assert_eq!(
+            snap_to_grid(Vec2::new(15.0, -15.0), 10.0),
+            Vec2::new(20.0, -20.0)
+        );
+        assert_eq!(
+            snap_to_grid(Vec2::new(-5.0, 5.0), 10.0),
+            Vec2::new(-10.0, 10.0)
+        );
+        // Without a cell size there's nothing to snap to
+        assert_eq!(snap_to_grid(Vec2::new(3.0, 4.0), 0.0), Vec2::new(3.0, 4.0));
+    }
+    #[test]
+    fn box_stays_inside_a_round_play_area() {
+        let play_area = PlayArea {
+            center: Vec2::ZERO,
+            size: Vec2::splat(200.0),
+            shape: PlayAreaShape::Circle { radius: 100.0 },
+        };
+        // Dragging to the corner of the bounding square would leave the circle
+        let rect = box_in_play_area(Vec2::ZERO, Vec2::splat(100.0), &play_area);
+        assert!(play_area.contains(rect.max));
+        assert!(rect.width() > 60.0);
+        // A drag that already fits is left alone
+        let small = box_in_play_area(Vec2::ZERO, Vec2::splat(50.0), &play_area);
+        assert_eq!(small, Rect::from_corners(Vec2::ZERO, Vec2::splat(50.0)));
+    }
+    #[test]
+    fn circle_stays_inside_a_round_play_area() {
+        let play_area = PlayArea {
+            center: Vec2::ZERO,
+            size: Vec2::splat(200.0),
+            shape: PlayAreaShape::Circle { radius: 100.0 },
+        };
+        // Started in the corner of the bounding square, outside the circle
+        let Some(DrawnShape::Circle { center, radius }) =
+            DrawnShape::new(DrawTool::Circle, Vec2::splat(90.0), Vec2::ZERO, &play_area)
+        else {
+            panic!("the circle tool draws circles");
+        };
+        assert!(center.length() + radius <= 100.0 + 1e-3);
+    }
+}
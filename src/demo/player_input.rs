@@ -1,19 +1,47 @@
-//! Handles player input for commanding Moodels. Selection is handled by observers in mood.rs.
+//! Handles player input for commanding Moodels. Clicking a Moodel selects it through observers
+//! in mood.rs, and dragging selects every Moodel inside the box.
+//!
+//! Mouse, touch and gamepad input all become [`PlayerIntent`]s, so they share one path to
+//! [`CommandMoodelEvent`]: on touch screens a tap selects and a long-press commands, and
+//! gamepads are handled in gamepad_control.rs.
 
-use bevy::prelude::*;
-use bevy_picking::prelude::{Click, Pointer};
+use avian2d::prelude::*;
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_picking::prelude::{Click, DragStart, Pointer, PointerButton};
+use std::collections::{HashMap, VecDeque};
 
 use crate::demo::ai::{AiAction, AiWanderState};
+use crate::demo::mood::Mood;
+use crate::demo::movement::{MovementSmoothing, PlayArea};
+use crate::demo::player::ShapeTool;
+use crate::keybindings::{Action, ActionInput, action_just_pressed};
+use crate::screens::Screen;
 use crate::{AppSystems, PausableSystems};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_event::<CommandMoodelEvent>();
+    app.add_event::<PlayerIntent>();
+    app.init_resource::<BoxSelection>();
+    app.init_resource::<TouchPresses>();
+    app.init_resource::<HeldMoodel>();
+    app.add_observer(grab_moodel);
     app.add_systems(
         Update,
         (
-            handle_command_input,
+            (
+                handle_box_selection,
+                draw_box_selection,
+                select_by_mood,
+                deselect_all.run_if(action_just_pressed(Action::Deselect)),
+                update_held_moodel,
+            )
+                .chain()
+                .run_if(in_state(Screen::Gameplay)),
+            (handle_command_input, handle_touch_input),
+            apply_player_intents,
             apply_moodel_command,
             manage_selection_visuals,
+            draw_queued_paths,
         )
             .chain()
             .in_set(AppSystems::Update)
@@ -29,24 +57,62 @@ pub struct Selectable;
 #[derive(Component)]
 pub struct Selected;
 
+/// Where the current box-selection drag started, in world coordinates.
+#[derive(Resource, Default)]
+pub struct BoxSelection {
+    pub start: Option<Vec2>,
+}
+
+/// Drags smaller than this on both axes are treated as clicks rather than box selections.
+const BOX_SELECT_MIN_SIZE: f32 = 8.0;
+
 /// A marker for the visual selection ring entity.
 #[derive(Component)]
 pub struct SelectionRing;
 
+/// Something the player wants done, whichever input it came from: mouse, touch or
+/// gamepad.
+#[derive(Event, Debug, Clone, Copy)]
+pub enum PlayerIntent {
+    /// Select the Moodel nearest to this world position, replacing the selection.
+    Select(Vec2),
+    /// Send the selected Moodels to this world position.
+    Command { position: Vec2, queued: bool },
+}
+
 /// An event fired when the player issues a move command.
 #[derive(Event)]
-struct CommandMoodelEvent {
-    entity: Entity,
-    destination: Vec2,
+pub struct CommandMoodelEvent {
+    pub entity: Entity,
+    pub destination: Vec2,
+    /// Append the destination to the Moodel's waypoints instead of replacing them.
+    pub queued: bool,
+}
+
+/// Converts the window's cursor position into world coordinates, if the cursor is in the window.
+pub fn cursor_world_position(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let cursor_pos = window.cursor_position()?;
+    camera
+        .viewport_to_world_2d(camera_transform, cursor_pos)
+        .ok()
 }
 
 /// An observer that runs when the background is clicked, clearing any selection.
 pub fn handle_background_click(
     _trigger: Trigger<Pointer<Click>>,
     mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
     selected_q: Query<Entity, With<Selected>>,
 ) {
-    if let Ok(selected_entity) = selected_q.single() {
+    // Shift keeps the selection, so it can be added to with a box selection.
+    if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    for selected_entity in &selected_q {
         info!(
             "Background clicked. Deselecting entity {:?}",
             selected_entity
@@ -55,51 +121,443 @@ pub fn handle_background_click(
     }
 }
 
-/// System that handles right-click commands for the selected Moodel.
-fn handle_command_input(
+/// Clears the selection (D key by default).
+fn deselect_all(mut commands: Commands, selected_q: Query<Entity, With<Selected>>) {
+    for entity in &selected_q {
+        commands.entity(entity).remove::<Selected>();
+    }
+}
+
+/// Left-mouse drag selects every Moodel inside the dragged rectangle. Holding Shift adds to the
+/// current selection instead of replacing it.
+fn handle_box_selection(
     mut commands: Commands,
     mouse_buttons: Res<ButtonInput<MouseButton>>,
+    keys: Res<ButtonInput<KeyCode>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    shape_tool: Res<ShapeTool>,
+    held: Res<HeldMoodel>,
+    mut box_selection: ResMut<BoxSelection>,
+    selectable_q: Query<(Entity, &Transform, Has<Selected>), With<Selectable>>,
+) {
+    // Left-drags belong to the editor tools while one is in use, or move a grabbed Moodel.
+    if shape_tool.active.is_some() || held.entity.is_some() {
+        box_selection.start = None;
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    if mouse_buttons.just_pressed(MouseButton::Left) {
+        box_selection.start = Some(cursor);
+    }
+    if !mouse_buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    let Some(start) = box_selection.start.take() else {
+        return;
+    };
+    let rect = Rect::from_corners(start, cursor);
+    if rect.width() < BOX_SELECT_MIN_SIZE && rect.height() < BOX_SELECT_MIN_SIZE {
+        return;
+    }
+
+    let additive = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    for (entity, transform, is_selected) in &selectable_q {
+        let inside = rect.contains(transform.translation.truncate());
+        if inside && !is_selected {
+            commands.entity(entity).insert(Selected);
+        } else if !inside && is_selected && !additive {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+}
+
+/// Replaces the selection with every Moodel currently in the mood whose select action was
+/// pressed (the number keys by default). Moods keep changing, so this is a snapshot rather
+/// than a lasting filter.
+fn select_by_mood(
+    mut commands: Commands,
+    input: ActionInput,
+    selectable_q: Query<(Entity, &Mood, Has<Selected>), With<Selectable>>,
+) {
+    let Some(target_mood) = Mood::all()
+        .into_iter()
+        .find(|mood| input.just_pressed(Action::select_mood(*mood)))
+    else {
+        return;
+    };
+
+    let mut count = 0;
+    for (entity, mood, is_selected) in &selectable_q {
+        if *mood == target_mood {
+            count += 1;
+            if !is_selected {
+                commands.entity(entity).insert(Selected);
+            }
+        } else if is_selected {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+    info!("Selected {} {:?} Moodel(s)", count, target_mood);
+}
+
+/// The Moodel the player is dragging around, with recent cursor positions for throwing it.
+#[derive(Resource, Default)]
+pub struct HeldMoodel {
+    pub entity: Option<Entity>,
+    /// Timestamped cursor positions from the last [`THROW_SAMPLE_WINDOW`] seconds.
+    samples: VecDeque<(f32, Vec2)>,
+}
+
+/// How far back cursor movement counts towards the throw velocity, in seconds.
+const THROW_SAMPLE_WINDOW: f32 = 0.1;
+/// The fastest a Moodel can be thrown, in world units per second.
+const MAX_THROW_SPEED: f32 = 1500.0;
+
+/// An observer that picks up a Moodel when the player starts dragging it.
+///
+/// While held, the Moodel is kinematic, so forces from its AI and collisions can't move it.
+fn grab_moodel(
+    trigger: Trigger<Pointer<DragStart>>,
+    mut commands: Commands,
+    shape_tool: Res<ShapeTool>,
+    mut held: ResMut<HeldMoodel>,
+    mut moodel_q: Query<(&mut AiWanderState, &mut LinearVelocity), With<Selectable>>,
+) {
+    if trigger.event().button != PointerButton::Primary
+        || shape_tool.active.is_some()
+        || held.entity.is_some()
+    {
+        return;
+    }
+    let entity = trigger.target();
+    let Ok((mut ai_state, mut velocity)) = moodel_q.get_mut(entity) else {
+        return;
+    };
+    ai_state.action = AiAction::Held;
+    velocity.0 = Vec2::ZERO;
+    commands.entity(entity).insert(RigidBody::Kinematic);
+    held.entity = Some(entity);
+    held.samples.clear();
+}
+
+/// Moves the held Moodel with the cursor, and throws it when the mouse button is released.
+fn update_held_moodel(
+    mut commands: Commands,
+    time: Res<Time>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    play_area: Res<PlayArea>,
+    mut held: ResMut<HeldMoodel>,
+    mut moodel_q: Query<(
+        &mut Transform,
+        &mut AiWanderState,
+        &mut ExternalImpulse,
+        &Mass,
+        Option<&mut MovementSmoothing>,
+    )>,
+) {
+    let Some(entity) = held.entity else {
+        return;
+    };
+    let Ok((mut transform, mut ai_state, mut impulse, mass, smoothing)) = moodel_q.get_mut(entity)
+    else {
+        // The Moodel was despawned while held.
+        *held = HeldMoodel::default();
+        return;
+    };
+
+    let now = time.elapsed_secs();
+    let (camera, camera_transform) = *camera;
+    if let Some(cursor) = cursor_world_position(&window, camera, camera_transform) {
+        let position = play_area.clamp(cursor);
+        transform.translation = position.extend(transform.translation.z);
+        held.samples.push_back((now, position));
+    }
+    while held
+        .samples
+        .front()
+        .is_some_and(|(sampled_at, _)| now - sampled_at > THROW_SAMPLE_WINDOW)
+    {
+        held.samples.pop_front();
+    }
+
+    if !mouse_buttons.just_released(MouseButton::Left) {
+        return;
+    }
+    let throw_velocity = throw_velocity(&held.samples);
+    commands.entity(entity).insert(RigidBody::Dynamic);
+    ai_state.action = AiAction::Wandering;
+    impulse.apply_impulse(throw_velocity * mass.0);
+    // Let the throw carry instead of having the movement controller brake it straight away.
+    if let Some(mut smoothing) = smoothing {
+        smoothing.current_velocity = throw_velocity;
+    }
+    *held = HeldMoodel::default();
+}
+
+/// The average cursor velocity over the samples, capped to [`MAX_THROW_SPEED`].
+fn throw_velocity(samples: &VecDeque<(f32, Vec2)>) -> Vec2 {
+    let (Some((start_time, start)), Some((end_time, end))) = (samples.front(), samples.back())
+    else {
+        return Vec2::ZERO;
+    };
+    let elapsed = end_time - start_time;
+    if elapsed <= 0.0 {
+        return Vec2::ZERO;
+    }
+    ((end - start) / elapsed).clamp_length_max(MAX_THROW_SPEED)
+}
+
+/// Outlines the box selection being dragged out.
+fn draw_box_selection(
+    mut gizmos: Gizmos,
+    box_selection: Res<BoxSelection>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+) {
+    let Some(start) = box_selection.start else {
+        return;
+    };
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+    let rect = Rect::from_corners(start, cursor);
+    if rect.width() < BOX_SELECT_MIN_SIZE && rect.height() < BOX_SELECT_MIN_SIZE {
+        return;
+    }
+    gizmos.rect_2d(rect.center(), rect.size(), Color::srgba(0.4, 0.8, 1.0, 0.8));
+}
+
+/// System that turns right-clicks (or whatever Command is bound to) into commands for the
+/// selected Moodels.
+fn handle_command_input(
+    input: ActionInput,
+    keys: Res<ButtonInput<KeyCode>>,
     q_windows: Query<&Window>,
     q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut ev_intent: EventWriter<PlayerIntent>,
+) {
+    if input.just_pressed(Action::Command) {
+        let Ok((camera, camera_transform)) = q_camera.single() else {
+            return;
+        };
+        let Ok(window) = q_windows.single() else {
+            return;
+        };
+
+        // Shift queues the destination as a waypoint and keeps the selection for planning routes.
+        let queued = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+        if let Some(world_pos) = cursor_world_position(window, camera, camera_transform) {
+            ev_intent.write(PlayerIntent::Command {
+                position: world_pos,
+                queued,
+            });
+        }
+    }
+}
+
+/// A tap shorter than this selects, holding longer issues a move command.
+const LONG_PRESS_SECS: f32 = 0.5;
+/// Touches that move further than this, in screen pixels, are drags rather than taps or presses.
+const TOUCH_SLOP: f32 = 20.0;
+/// How far from a tap a Moodel can be and still get selected, in world units.
+const TOUCH_SELECT_RADIUS: f32 = 60.0;
+
+/// When each active touch started, and whether it has already fired a long-press command.
+#[derive(Resource, Default)]
+struct TouchPresses(HashMap<u64, (f32, bool)>);
+
+/// System that turns taps into selections and long-presses into commands.
+fn handle_touch_input(
+    time: Res<Time>,
+    touches: Res<Touches>,
+    q_camera: Query<(&Camera, &GlobalTransform)>,
+    mut presses: ResMut<TouchPresses>,
+    mut ev_intent: EventWriter<PlayerIntent>,
+) {
+    let Ok((camera, camera_transform)) = q_camera.single() else {
+        return;
+    };
+    let now = time.elapsed_secs();
+    let to_world = |position: Vec2| camera.viewport_to_world_2d(camera_transform, position).ok();
+
+    for touch in touches.iter_just_pressed() {
+        presses.0.insert(touch.id(), (now, false));
+    }
+
+    for touch in touches.iter() {
+        let Some((started, fired)) = presses.0.get_mut(&touch.id()) else {
+            continue;
+        };
+        let held_still = touch.distance().length() < TOUCH_SLOP;
+        if !*fired && held_still && now - *started >= LONG_PRESS_SECS {
+            *fired = true;
+            if let Some(position) = to_world(touch.position()) {
+                ev_intent.write(PlayerIntent::Command {
+                    position,
+                    queued: false,
+                });
+            }
+        }
+    }
+
+    for touch in touches.iter_just_released() {
+        let Some((_, fired)) = presses.0.remove(&touch.id()) else {
+            continue;
+        };
+        if !fired && touch.distance().length() < TOUCH_SLOP {
+            if let Some(position) = to_world(touch.position()) {
+                ev_intent.write(PlayerIntent::Select(position));
+            }
+        }
+    }
+    for touch in touches.iter_just_canceled() {
+        presses.0.remove(&touch.id());
+    }
+}
+
+/// Carries out selections and commands, whichever input they came from.
+fn apply_player_intents(
+    mut commands: Commands,
+    mut ev_intent: EventReader<PlayerIntent>,
+    selectable_q: Query<(Entity, &Transform), With<Selectable>>,
     selected_q: Query<Entity, With<Selected>>,
     mut ev_command: EventWriter<CommandMoodelEvent>,
 ) {
-    if mouse_buttons.just_pressed(MouseButton::Right) {
-        if let Ok(selected_entity) = selected_q.single() {
-            let Ok((camera, camera_transform)) = q_camera.single() else {
-                return;
-            };
-            let Ok(window) = q_windows.single() else {
-                return;
-            };
-
-            if let Some(cursor_pos) = window.cursor_position() {
-                if let Ok(world_pos) = camera.viewport_to_world_2d(camera_transform, cursor_pos) {
-                    info!(
-                        "Commanding entity {:?} to move to {:?}",
-                        selected_entity, world_pos
-                    );
-                    ev_command.write(CommandMoodelEvent {
-                        entity: selected_entity,
-                        destination: world_pos,
-                    });
-                    commands.entity(selected_entity).remove::<Selected>();
+    for intent in ev_intent.read() {
+        match *intent {
+            PlayerIntent::Select(position) => {
+                let nearest = selectable_q
+                    .iter()
+                    .map(|(entity, transform)| {
+                        (entity, transform.translation.truncate().distance(position))
+                    })
+                    .filter(|(_, distance)| *distance <= TOUCH_SELECT_RADIUS)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(entity, _)| entity);
+                for entity in &selected_q {
+                    if Some(entity) != nearest {
+                        commands.entity(entity).remove::<Selected>();
+                    }
+                }
+                if let Some(entity) = nearest {
+                    commands.entity(entity).insert(Selected);
                 }
             }
+            PlayerIntent::Command { position, queued } => {
+                command_group(
+                    &mut commands,
+                    &selected_q,
+                    position,
+                    queued,
+                    &mut ev_command,
+                );
+            }
         }
     }
 }
 
+/// Sends every selected Moodel to its own slot in a formation around `center`. Unless the
+/// command is queued, the selection is cleared afterwards.
+pub fn command_group(
+    commands: &mut Commands,
+    selected_q: &Query<Entity, With<Selected>>,
+    center: Vec2,
+    queued: bool,
+    ev_command: &mut EventWriter<CommandMoodelEvent>,
+) {
+    // Sort so each Moodel keeps the same slot between commands.
+    let mut selected: Vec<Entity> = selected_q.iter().collect();
+    selected.sort();
+    let slots = formation_slots(center, selected.len());
+    for (entity, destination) in selected.into_iter().zip(slots) {
+        info!(
+            "Commanding entity {:?} to move to {:?}",
+            entity, destination
+        );
+        ev_command.write(CommandMoodelEvent {
+            entity,
+            destination,
+            queued,
+        });
+        if !queued {
+            commands.entity(entity).remove::<Selected>();
+        }
+    }
+}
+
+/// Distance between neighbouring slots in a group move formation.
+const FORMATION_SPACING: f32 = 60.0;
+
+/// `count` destinations around `center`: the first at the center, the rest in rings around it
+/// so the Moodels don't pile onto one spot.
+pub fn formation_slots(center: Vec2, count: usize) -> Vec<Vec2> {
+    let mut slots = Vec::with_capacity(count);
+    if count == 0 {
+        return slots;
+    }
+    slots.push(center);
+    let mut ring = 1;
+    while slots.len() < count {
+        // Each ring holds six more slots than the last, keeping the spacing roughly even.
+        let ring_capacity = 6 * ring;
+        let in_ring = ring_capacity.min(count - slots.len());
+        let radius = FORMATION_SPACING * ring as f32;
+        for i in 0..in_ring {
+            let angle = std::f32::consts::TAU * i as f32 / ring_capacity as f32;
+            slots.push(center + Vec2::from_angle(angle) * radius);
+        }
+        ring += 1;
+    }
+    slots
+}
+
 /// Applies the move command to the AI state of the commanded Moodel.
 fn apply_moodel_command(
     mut ev_command: EventReader<CommandMoodelEvent>,
     mut ai_q: Query<&mut AiWanderState>,
 ) {
     for ev in ev_command.read() {
-        if let Ok(mut ai_state) = ai_q.get_mut(ev.entity) {
-            ai_state.action = AiAction::MovingToTarget {
-                destination: ev.destination,
-            };
+        let Ok(mut ai_state) = ai_q.get_mut(ev.entity) else {
+            continue;
+        };
+        match &mut ai_state.action {
+            AiAction::MovingToTarget { waypoints } if ev.queued => {
+                waypoints.push_back(ev.destination);
+            }
+            action => {
+                *action = AiAction::MovingToTarget {
+                    waypoints: VecDeque::from([ev.destination]),
+                };
+            }
+        }
+    }
+}
+
+/// Draws the remaining route of each selected Moodel that is following commanded waypoints.
+fn draw_queued_paths(
+    mut gizmos: Gizmos,
+    selected_q: Query<(&Transform, &AiWanderState), With<Selected>>,
+) {
+    let color = Color::srgba(0.4, 0.8, 1.0, 0.6);
+    for (transform, ai_state) in &selected_q {
+        let AiAction::MovingToTarget { waypoints } = &ai_state.action else {
+            continue;
+        };
+        let start = transform.translation.truncate();
+        gizmos.linestrip_2d(
+            std::iter::once(start).chain(waypoints.iter().copied()),
+            color,
+        );
+        for waypoint in waypoints {
+            gizmos.circle_2d(*waypoint, 6.0, color);
         }
     }
 }
@@ -141,3 +599,74 @@ fn manage_selection_visuals(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keybindings::{Binding, KeyBindings, KeyboardCaptured};
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn formation_slots_are_distinct() {
+        let center = Vec2::new(30.0, -20.0);
+        for count in [0, 1, 2, 7, 8, 25] {
+            let slots = formation_slots(center, count);
+            assert_eq!(slots.len(), count);
+            for (i, a) in slots.iter().enumerate() {
+                for b in &slots[i + 1..] {
+                    assert!(a.distance(*b) > FORMATION_SPACING / 2.0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn throw_follows_the_cursor() {
+        let samples = VecDeque::from([
+            (0.0, Vec2::new(0.0, 0.0)),
+            (0.05, Vec2::new(5.0, -2.0)),
+            (0.1, Vec2::new(10.0, -5.0)),
+        ]);
+        let velocity = throw_velocity(&samples);
+        assert!((velocity - Vec2::new(100.0, -50.0)).length() < 1e-3);
+
+        // A flick faster than the cap keeps its direction
+        let flick = VecDeque::from([(0.0, Vec2::ZERO), (0.01, Vec2::new(-1000.0, 0.0))]);
+        let velocity = throw_velocity(&flick);
+        assert!((velocity.length() - MAX_THROW_SPEED).abs() < 1e-2);
+        assert!(velocity.normalize().abs_diff_eq(Vec2::NEG_X, 1e-5));
+
+        assert_eq!(
+            throw_velocity(&VecDeque::from([(0.0, Vec2::ONE)])),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn mood_key_selects_only_that_mood() {
+        let mut app = App::new();
+        app.init_resource::<KeyBindings>()
+            .init_resource::<KeyboardCaptured>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>();
+        let calm = app.world_mut().spawn((Mood::Calm, Selectable)).id();
+        let happy = app
+            .world_mut()
+            .spawn((Mood::Happy, Selectable, Selected))
+            .id();
+        let unselectable = app.world_mut().spawn(Mood::Calm).id();
+
+        let Binding::Key(key) = KeyBindings::default().get(Action::select_mood(Mood::Calm)) else {
+            panic!("mood selection should default to a key");
+        };
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        app.world_mut().run_system_once(select_by_mood).unwrap();
+
+        let selected = |entity| app.world().entity(entity).contains::<Selected>();
+        assert!(selected(calm));
+        assert!(!selected(happy));
+        assert!(!selected(unselectable));
+    }
+}
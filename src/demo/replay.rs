@@ -0,0 +1,356 @@
+//! Recording and playback of the player's input, for reproducing bugs and balancing runs.
+//!
+//! While recording, level spawns, resets, selections and move commands are logged with the
+//! frame they happened on, and the log is exported as JSON when gameplay ends. Playing a
+//! replay back reseeds the game RNG with the recorded seed and re-injects the same events
+//! on the same frames.
+//!
+//! Frames only count while a level is loaded and the game isn't paused, and the frame time is
+//! fixed while recording or playing, so both runs step the simulation identically. Moodels
+//! are identified by their position, since entity ids differ between runs.
+
+use std::time::Duration;
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    AppSystems, PausableSystems, Pause,
+    demo::{
+        level::{ActiveLevel, ResetLevel, SpawnLevel, teardown_level},
+        player_input::{CommandMoodelEvent, Selectable, Selected},
+        rng::GameRng,
+    },
+    save,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<ReplayRecorder>();
+    app.init_resource::<ReplayPlayer>();
+
+    app.add_systems(
+        Update,
+        (
+            sync_replay_frame_time
+                .run_if(resource_changed::<ReplayRecorder>.or(resource_changed::<ReplayPlayer>)),
+            play_replay_events
+                .run_if(replay_playing)
+                .in_set(AppSystems::RecordInput)
+                .in_set(PausableSystems),
+        ),
+    );
+    // Recording happens after `Update`, so every event written during it is seen on the
+    // frame it was written.
+    app.add_systems(
+        PostUpdate,
+        (
+            (
+                record_level_events,
+                (record_commands, record_selection).run_if(replay_started),
+            )
+                .run_if(replay_recording),
+            advance_replay_frames,
+        )
+            .chain()
+            .run_if(in_state(Screen::Gameplay).and(in_state(Pause(false)))),
+    );
+    app.add_systems(OnExit(Screen::Gameplay), export_replay);
+}
+
+/// The simulated time of a frame while recording or playing back.
+const REPLAY_FRAME_TIME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// A recorded run: the seed it was played with and the player's input, frame by frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub entries: Vec<ReplayEntry>,
+}
+
+/// A single event in a [`Replay`], with the frame it happened on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayEntry {
+    pub frame: u32,
+    #[serde(flatten)]
+    pub event: ReplayEvent,
+}
+
+/// The kinds of input recorded in a [`Replay`]. Moodels are stored as their positions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ReplayEvent {
+    SpawnLevel {
+        level: SpawnLevel,
+    },
+    ResetLevel,
+    Select {
+        moodels: Vec<Vec2>,
+    },
+    Command {
+        moodel: Vec2,
+        destination: Vec2,
+        queued: bool,
+    },
+}
+
+/// Records the player's input into a [`Replay`]. Disabled by default.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    /// Whether input is recorded at all. The replay starts at the next level spawn.
+    pub enabled: bool,
+    /// Where the replay is written when gameplay ends.
+    pub export_path: String,
+    replay: Option<Replay>,
+    frame: u32,
+}
+
+impl Default for ReplayRecorder {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            export_path: "replay.json".to_string(),
+            replay: None,
+            frame: 0,
+        }
+    }
+}
+
+impl ReplayRecorder {
+    fn record(&mut self, event: ReplayEvent) {
+        let frame = self.frame;
+        if let Some(replay) = &mut self.replay {
+            replay.entries.push(ReplayEntry { frame, event });
+        }
+    }
+
+    /// Writes the recorded replay to [`Self::export_path`] and starts over.
+    pub fn export(&mut self) {
+        let Some(replay) = self.replay.take() else {
+            return;
+        };
+        self.frame = 0;
+        match serde_json::to_string_pretty(&replay) {
+            Ok(json) => {
+                save::write(&self.export_path, &json);
+                info!(
+                    "Exported a replay of {} events to {}",
+                    replay.entries.len(),
+                    self.export_path
+                );
+            }
+            Err(error) => error!("Failed to serialize the replay: {}", error),
+        }
+    }
+}
+
+/// Re-injects the events of a [`Replay`] on the frames they were recorded on.
+#[derive(Resource, Default)]
+pub struct ReplayPlayer {
+    replay: Option<Replay>,
+    /// Index of the next entry to play.
+    next: usize,
+    frame: u32,
+}
+
+impl ReplayPlayer {
+    /// Starts playing `replay` from its first frame. The caller is responsible for seeding
+    /// the game RNG with [`Replay::seed`].
+    pub fn play(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+        self.next = 0;
+        self.frame = 0;
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.replay.is_some()
+    }
+}
+
+fn replay_recording(recorder: Res<ReplayRecorder>) -> bool {
+    recorder.enabled
+}
+
+fn replay_started(recorder: Res<ReplayRecorder>) -> bool {
+    recorder.replay.is_some()
+}
+
+fn replay_playing(player: Res<ReplayPlayer>) -> bool {
+    player.is_playing()
+}
+
+/// Fixes the frame time while recording or playing back, so both runs step the same way.
+fn sync_replay_frame_time(
+    recorder: Res<ReplayRecorder>,
+    player: Res<ReplayPlayer>,
+    mut strategy: ResMut<TimeUpdateStrategy>,
+    mut was_fixed: Local<bool>,
+) {
+    let fixed = recorder.enabled || player.is_playing();
+    if fixed == *was_fixed {
+        return;
+    }
+    *was_fixed = fixed;
+    *strategy = if fixed {
+        TimeUpdateStrategy::ManualDuration(REPLAY_FRAME_TIME)
+    } else {
+        TimeUpdateStrategy::Automatic
+    };
+}
+
+/// Starts the replay at a level spawn, and logs spawns and resets after that.
+fn record_level_events(
+    mut recorder: ResMut<ReplayRecorder>,
+    rng: Res<GameRng>,
+    mut spawn_events: EventReader<SpawnLevel>,
+    mut reset_events: EventReader<ResetLevel>,
+) {
+    for request in spawn_events.read() {
+        if recorder.replay.is_none() {
+            info!("Recording a replay, starting with {:?}", request);
+            recorder.frame = 0;
+            recorder.replay = Some(Replay {
+                seed: rng.seed,
+                entries: Vec::new(),
+            });
+        }
+        recorder.record(ReplayEvent::SpawnLevel {
+            level: request.clone(),
+        });
+    }
+    for _ in reset_events.read() {
+        recorder.record(ReplayEvent::ResetLevel);
+    }
+}
+
+fn record_commands(
+    mut recorder: ResMut<ReplayRecorder>,
+    mut command_events: EventReader<CommandMoodelEvent>,
+    moodel_query: Query<&Transform>,
+) {
+    for command in command_events.read() {
+        let Ok(transform) = moodel_query.get(command.entity) else {
+            continue;
+        };
+        recorder.record(ReplayEvent::Command {
+            moodel: transform.translation.truncate(),
+            destination: command.destination,
+            queued: command.queued,
+        });
+    }
+}
+
+/// Logs the whole selection whenever it changes.
+fn record_selection(
+    mut recorder: ResMut<ReplayRecorder>,
+    added: Query<(), Added<Selected>>,
+    mut removed: RemovedComponents<Selected>,
+    selected_query: Query<&Transform, With<Selected>>,
+) {
+    let removed_any = removed.read().count() > 0;
+    if added.is_empty() && !removed_any {
+        return;
+    }
+    let moodels = selected_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    recorder.record(ReplayEvent::Select { moodels });
+}
+
+/// Counts the frames a level has been live for, for both the recorder and the player.
+fn advance_replay_frames(
+    active_level: Res<ActiveLevel>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut player: ResMut<ReplayPlayer>,
+) {
+    // Loading a level from file takes a varying number of frames, which mustn't count.
+    if active_level.0.is_none() {
+        return;
+    }
+    if recorder.replay.is_some() {
+        recorder.frame += 1;
+    }
+    if player.is_playing() {
+        player.frame += 1;
+    }
+}
+
+fn play_replay_events(
+    mut commands: Commands,
+    mut player: ResMut<ReplayPlayer>,
+    mut spawn_events: EventWriter<SpawnLevel>,
+    mut reset_events: EventWriter<ResetLevel>,
+    mut command_events: EventWriter<CommandMoodelEvent>,
+    moodel_query: Query<(Entity, &Transform, Has<Selected>), With<Selectable>>,
+) {
+    let player = &mut *player;
+    let Some(replay) = &player.replay else {
+        return;
+    };
+    let nearest_moodel = |position: Vec2| {
+        moodel_query
+            .iter()
+            .min_by(|(_, a, _), (_, b, _)| {
+                let a = a.translation.truncate().distance_squared(position);
+                let b = b.translation.truncate().distance_squared(position);
+                a.total_cmp(&b)
+            })
+            .map(|(entity, _, _)| entity)
+    };
+
+    while let Some(entry) = replay.entries.get(player.next) {
+        if entry.frame > player.frame {
+            break;
+        }
+        player.next += 1;
+        match &entry.event {
+            ReplayEvent::SpawnLevel { level } => {
+                // Spawning doesn't clean up the level it replaces outside of screen changes
+                commands.run_system_cached(teardown_level);
+                spawn_events.write(level.clone());
+            }
+            ReplayEvent::ResetLevel => {
+                reset_events.write(ResetLevel);
+            }
+            ReplayEvent::Select { moodels } => {
+                let selection: Vec<Entity> = moodels
+                    .iter()
+                    .filter_map(|&position| nearest_moodel(position))
+                    .collect();
+                for (entity, _, is_selected) in &moodel_query {
+                    let selected = selection.contains(&entity);
+                    if selected && !is_selected {
+                        commands.entity(entity).insert(Selected);
+                    } else if !selected && is_selected {
+                        commands.entity(entity).remove::<Selected>();
+                    }
+                }
+            }
+            ReplayEvent::Command {
+                moodel,
+                destination,
+                queued,
+            } => {
+                let Some(entity) = nearest_moodel(*moodel) else {
+                    warn!("Replay commands a Moodel at {}, but there are none", moodel);
+                    continue;
+                };
+                command_events.write(CommandMoodelEvent {
+                    entity,
+                    destination: *destination,
+                    queued: *queued,
+                });
+            }
+        }
+    }
+
+    if player.next >= replay.entries.len() {
+        info!("Replay finished");
+        player.replay = None;
+    }
+}
+
+fn export_replay(mut recorder: ResMut<ReplayRecorder>) {
+    recorder.export();
+}
@@ -0,0 +1,90 @@
+//! The game's seeded random number generator, so runs can be reproduced.
+//!
+//! The seed comes from the `--seed <number>` command line argument or the `MOOD_SEED`
+//! environment variable, and is picked at random otherwise. It is logged at startup, and
+//! the generator is reseeded whenever a level spawns, so a level plays out the same way
+//! every time it is started with the same seed.
+
+use bevy::prelude::*;
+use rand::{Rng, RngCore, SeedableRng, rngs::StdRng};
+
+pub(super) fn plugin(app: &mut App) {
+    let seed = seed_override().unwrap_or_else(|| rand::rng().random());
+    info!("Random seed: {seed} (pass --seed {seed} to replay this run)");
+    app.insert_resource(GameRng::new(seed));
+}
+
+/// The environment variable that can set the seed.
+const SEED_ENV_VAR: &str = "MOOD_SEED";
+
+/// Randomness for anything that affects gameplay. Purely cosmetic randomness, like sound
+/// variation, can keep using the thread-local generator.
+#[derive(Resource)]
+pub struct GameRng {
+    /// The seed the generator starts from when reseeded.
+    pub seed: u64,
+    rng: StdRng,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Restarts the sequence from the seed.
+    pub fn reseed(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.rng.fill_bytes(dst);
+    }
+}
+
+/// The seed from the command line, or failing that the environment.
+fn seed_override() -> Option<u64> {
+    let args: Vec<String> = std::env::args().collect();
+    let from_args = args
+        .windows(2)
+        .find(|pair| pair[0] == "--seed")
+        .map(|pair| pair[1].clone());
+    let value = from_args.or_else(|| std::env::var(SEED_ENV_VAR).ok())?;
+    match value.parse() {
+        Ok(seed) => Some(seed),
+        Err(error) => {
+            warn!("Ignoring invalid seed \"{value}\": {error}");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_gives_the_same_sequence() {
+        let sequence = |rng: &mut GameRng| (0..8).map(|_| rng.next_u64()).collect::<Vec<_>>();
+        let mut first = GameRng::new(42);
+        let mut second = GameRng::new(42);
+        let expected = sequence(&mut first);
+        assert_eq!(sequence(&mut second), expected);
+        assert_ne!(sequence(&mut GameRng::new(43)), expected);
+        // Reseeding replays the sequence from the start.
+        first.reseed();
+        assert_eq!(sequence(&mut first), expected);
+    }
+}
@@ -0,0 +1,290 @@
+//! A uniform spatial grid bucketing Moodels by position.
+//!
+//! The grid is kept up to date incrementally: each frame only Moodels whose
+//! transform changed are looked at, and only those that crossed into a new
+//! cell are moved between buckets. When the population changes a lot in one
+//! frame (level load, mass spawns) the grid is rebuilt from scratch instead.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::ai::{AiConfig, AiEntity},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.insert_resource(SpatialGrid::new(DEFAULT_CELL_SIZE));
+    app.add_systems(
+        Update,
+        update_spatial_grid
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Default cell size, before it is matched to the largest Moodel vision radius.
+const DEFAULT_CELL_SIZE: f32 = 250.0;
+
+/// Fraction of the population that may be added or removed in a single frame
+/// before the grid is rebuilt from scratch rather than patched.
+const REBUILD_POPULATION_CHANGE: f32 = 0.25;
+
+/// Buckets entities into square cells so neighbor lookups don't scan every entity.
+#[derive(Resource, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    /// The cell each entity is currently stored in.
+    entity_cells: HashMap<Entity, IVec2>,
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            entity_cells: HashMap::new(),
+        }
+    }
+
+    /// The cell containing a world position.
+    pub fn cell_of(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Side length of each cell.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Changes the cell size. The grid must be rebuilt afterwards.
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size;
+        self.cells.clear();
+        self.entity_cells.clear();
+    }
+
+    /// Entities in every cell within `radius` of `position`. With a cell size of at least
+    /// `radius` this is the 3×3 block of cells around the position. Callers still need to
+    /// check exact distances, as cells contain entities up to a cell beyond the radius.
+    pub fn neighbors(&self, position: Vec2, radius: f32) -> impl Iterator<Item = Entity> + '_ {
+        let min = self.cell_of(position - Vec2::splat(radius));
+        let max = self.cell_of(position + Vec2::splat(radius));
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+
+    /// The number of entities in each occupied cell.
+    pub fn cell_counts(&self) -> impl Iterator<Item = (IVec2, usize)> + '_ {
+        self.cells
+            .iter()
+            .map(|(cell, bucket)| (*cell, bucket.len()))
+    }
+
+    /// Number of entities stored in the grid.
+    pub fn entity_count(&self) -> usize {
+        self.entity_cells.len()
+    }
+
+    /// Clears the grid and re-inserts every entity.
+    pub fn rebuild(&mut self, positions: impl IntoIterator<Item = (Entity, Vec2)>) {
+        self.cells.clear();
+        self.entity_cells.clear();
+        for (entity, position) in positions {
+            self.insert(entity, self.cell_of(position));
+        }
+    }
+
+    /// Moves only the entities that crossed into a new cell. Entities not yet in
+    /// the grid are inserted. Returns how many entities changed bucket.
+    pub fn update(&mut self, changed_positions: impl IntoIterator<Item = (Entity, Vec2)>) -> usize {
+        let mut moved = 0;
+        for (entity, position) in changed_positions {
+            let cell = self.cell_of(position);
+            match self.entity_cells.get(&entity).copied() {
+                Some(old_cell) if old_cell == cell => {}
+                Some(old_cell) => {
+                    self.remove_from_cell(entity, old_cell);
+                    self.insert(entity, cell);
+                    moved += 1;
+                }
+                None => {
+                    self.insert(entity, cell);
+                    moved += 1;
+                }
+            }
+        }
+        moved
+    }
+
+    /// Removes an entity from the grid, if present.
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(cell) = self.entity_cells.remove(&entity) {
+            self.remove_from_cell(entity, cell);
+        }
+    }
+
+    fn insert(&mut self, entity: Entity, cell: IVec2) {
+        self.cells.entry(cell).or_default().push(entity);
+        self.entity_cells.insert(entity, cell);
+    }
+
+    fn remove_from_cell(&mut self, entity: Entity, cell: IVec2) {
+        if let Some(bucket) = self.cells.get_mut(&cell) {
+            if let Some(index) = bucket.iter().position(|other| *other == entity) {
+                bucket.swap_remove(index);
+            }
+            if bucket.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+}
+
+/// Keeps the grid in sync with Moodel positions, preferring the incremental path.
+pub fn update_spatial_grid(
+    config: Res<AiConfig>,
+    mut grid: ResMut<SpatialGrid>,
+    all_moodels: Query<(Entity, &Transform), With<AiEntity>>,
+    changed_moodels: Query<(Entity, &Transform), (With<AiEntity>, Changed<Transform>)>,
+    mut removed: RemovedComponents<AiEntity>,
+) {
+    for entity in removed.read() {
+        grid.remove(entity);
+    }
+
+    // Cells as large as the farthest vision radius keep neighbor lookups to a 3×3 block
+    if config.is_changed() {
+        let max_vision_radius = config
+            .vision_radius
+            .values()
+            .copied()
+            .fold(DEFAULT_CELL_SIZE, f32::max);
+        if max_vision_radius != grid.cell_size() {
+            grid.set_cell_size(max_vision_radius);
+        }
+    }
+
+    let population = all_moodels.iter().count();
+    let population_change = population.abs_diff(grid.entity_count()) as f32;
+    if population_change > population.max(grid.entity_count()) as f32 * REBUILD_POPULATION_CHANGE {
+        grid.rebuild(
+            all_moodels
+                .iter()
+                .map(|(entity, transform)| (entity, transform.translation.truncate())),
+        );
+    } else {
+        grid.update(
+            changed_moodels
+                .iter()
+                .map(|(entity, transform)| (entity, transform.translation.truncate())),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::demo::rng::GameRng;
+    use rand::Rng;
+    use std::{collections::HashSet, time::Instant};
+
+    #[test]
+    fn neighbors_match_a_brute_force_scan() {
+        let mut rng = GameRng::new(3);
+        let mut random_position = || {
+            Vec2::new(
+                rng.random_range(-600.0..600.0),
+                rng.random_range(-400.0..400.0),
+            )
+        };
+        let mut positions: Vec<(Entity, Vec2)> = (0..300)
+            .map(|i| (Entity::from_raw(i), random_position()))
+            .collect();
+        let mut grid = SpatialGrid::new(100.0);
+        grid.rebuild(positions.iter().copied());
+        // Move some of them so the incremental path is covered too.
+        for (_, position) in positions.iter_mut().step_by(3) {
+            *position = random_position();
+        }
+        grid.update(positions.iter().copied());
+
+        for _ in 0..50 {
+            let center = random_position();
+            let radius = 100.0;
+            let found: Vec<Entity> = grid
+                .neighbors(center, radius)
+                .filter(|entity| positions[entity.index() as usize].1.distance(center) <= radius)
+                .collect();
+            let expected: HashSet<Entity> = positions
+                .iter()
+                .filter(|(_, position)| position.distance(center) <= radius)
+                .map(|(entity, _)| *entity)
+                .collect();
+            assert_eq!(
+                found.len(),
+                expected.len(),
+                "no entity should be found twice"
+            );
+            assert_eq!(found.into_iter().collect::<HashSet<_>>(), expected);
+        }
+    }
+
+    /// Compares full rebuilds with incremental updates for a large crowd that mostly stays
+    /// inside its cells. Timings are only meaningful in an optimized build, so run it with
+    /// `cargo test --release -- --ignored incremental_update_beats_rebuild`.
+    #[test]
+    #[ignore = "timing benchmark"]
+    fn incremental_update_beats_rebuild() {
+        const ENTITIES: u32 = 2000;
+        const FRAMES: usize = 200;
+        let mut rng = GameRng::new(5);
+        let start_positions: Vec<(Entity, Vec2)> = (0..ENTITIES)
+            .map(|i| {
+                let position = Vec2::new(
+                    rng.random_range(-2000.0..2000.0),
+                    rng.random_range(-2000.0..2000.0),
+                );
+                (Entity::from_raw(i), position)
+            })
+            .collect();
+        // Small steps each frame, so only a few Moodels cross into a new cell.
+        let mut positions = start_positions.clone();
+        let frames: Vec<Vec<(Entity, Vec2)>> = (0..FRAMES)
+            .map(|_| {
+                for (_, position) in &mut positions {
+                    *position +=
+                        Vec2::new(rng.random_range(-2.0..2.0), rng.random_range(-2.0..2.0));
+                }
+                positions.clone()
+            })
+            .collect();
+
+        let mut rebuilt = SpatialGrid::new(DEFAULT_CELL_SIZE);
+        let started = Instant::now();
+        for frame in &frames {
+            rebuilt.rebuild(frame.iter().copied());
+        }
+        let rebuild_time = started.elapsed();
+
+        let mut updated = SpatialGrid::new(DEFAULT_CELL_SIZE);
+        updated.rebuild(start_positions);
+        let started = Instant::now();
+        let mut moved = 0;
+        for frame in &frames {
+            moved += updated.update(frame.iter().copied());
+        }
+        let update_time = started.elapsed();
+
+        println!(
+            "{ENTITIES} entities over {FRAMES} frames: rebuild {rebuild_time:?}, \
+             incremental {update_time:?} ({moved} cell changes)"
+        );
+        assert_eq!(updated.entity_count(), rebuilt.entity_count());
+        assert!(update_time < rebuild_time);
+    }
+}
@@ -0,0 +1,105 @@
+//! Running totals for the current level: mood transitions, collisions, and how long
+//! Moodels spent in each mood. Shown on the victory screen and in the inspector.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        ai::AiEntity,
+        level::{LevelClock, LevelOutcome},
+        mood::{Mood, MoodChanged},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<GameStats>();
+    app.init_resource::<GameStats>();
+
+    app.add_systems(
+        Update,
+        (
+            count_mood_changes,
+            count_collisions,
+            accumulate_mood_time,
+            record_completion_time.run_if(resource_changed::<LevelOutcome>),
+        )
+            .run_if(in_state(Screen::Gameplay))
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// Statistics for the level being played. Replaced with a fresh copy whenever a level spawns.
+#[derive(Resource, Reflect, Debug, Default, Clone)]
+#[reflect(Resource)]
+pub struct GameStats {
+    /// Mood changes, by the mood changed from and to.
+    pub mood_changes: HashMap<(Mood, Mood), u32>,
+    /// Collisions between two Moodels.
+    pub collisions: u32,
+    /// Seconds spent in each mood, summed over every Moodel.
+    pub mood_time_secs: HashMap<Mood, f32>,
+    /// How long the level took to win, once it has been won.
+    pub completion_time_secs: Option<f32>,
+}
+
+impl GameStats {
+    /// Total number of mood changes of any kind.
+    pub fn total_mood_changes(&self) -> u32 {
+        self.mood_changes.values().sum()
+    }
+
+    /// The most common mood change, if there has been one.
+    pub fn most_common_change(&self) -> Option<((Mood, Mood), u32)> {
+        self.mood_changes
+            .iter()
+            .max_by_key(|(_, count)| **count)
+            .map(|(change, count)| (*change, *count))
+    }
+}
+
+fn count_mood_changes(mut stats: ResMut<GameStats>, mut mood_changes: EventReader<MoodChanged>) {
+    for change in mood_changes.read() {
+        *stats
+            .mood_changes
+            .entry((change.from, change.to))
+            .or_default() += 1;
+    }
+}
+
+fn count_collisions(
+    mut stats: ResMut<GameStats>,
+    mut collision_started: EventReader<CollisionStarted>,
+    moodel_query: Query<(), With<AiEntity>>,
+) {
+    for CollisionStarted(entity1, entity2) in collision_started.read() {
+        if moodel_query.contains(*entity1) && moodel_query.contains(*entity2) {
+            stats.collisions += 1;
+        }
+    }
+}
+
+fn accumulate_mood_time(
+    time: Res<Time>,
+    mut stats: ResMut<GameStats>,
+    moodel_query: Query<&Mood, With<AiEntity>>,
+) {
+    let delta = time.delta_secs();
+    for mood in &moodel_query {
+        *stats.mood_time_secs.entry(*mood).or_default() += delta;
+    }
+}
+
+fn record_completion_time(
+    outcome: Res<LevelOutcome>,
+    clock: Res<LevelClock>,
+    mut stats: ResMut<GameStats>,
+) {
+    if *outcome == LevelOutcome::Won {
+        stats.completion_time_secs = Some(clock.elapsed);
+    }
+}
@@ -0,0 +1,221 @@
+//! An opt-in timeline of significant gameplay events, for balancing and bug analysis.
+//!
+//! Unlike raw input recording, the timeline stores semantic events (mood changes,
+//! zone satisfaction, commands, level outcome) and exports them as JSON when the
+//! level ends.
+
+use bevy::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        level::{GoalZone, LevelOutcome},
+        mood::{Mood, MoodChanged},
+        player_input::CommandMoodelEvent,
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<Timeline>();
+
+    app.add_systems(OnEnter(Screen::Gameplay), clear_timeline);
+    app.add_systems(
+        Update,
+        (
+            record_mood_changes,
+            record_zone_changes,
+            record_commands,
+            record_level_outcome.run_if(resource_changed::<LevelOutcome>),
+        )
+            .run_if(timeline_enabled)
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// A capped log of gameplay events. Disabled by default.
+#[derive(Resource)]
+pub struct Timeline {
+    /// Whether events are recorded at all.
+    pub enabled: bool,
+    /// Events beyond this count are dropped.
+    pub max_events: usize,
+    /// Where the timeline is written when the level ends.
+    pub export_path: String,
+    events: Vec<TimelineEntry>,
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_events: 10_000,
+            export_path: "timeline.json".to_string(),
+            events: Vec::new(),
+        }
+    }
+}
+
+impl Timeline {
+    /// Appends an event, unless recording is disabled or the log is full.
+    pub fn record_event(&mut self, time: f32, event: TimelineEvent) {
+        if !self.enabled || self.events.len() >= self.max_events {
+            return;
+        }
+        self.events.push(TimelineEntry { time, event });
+    }
+
+    /// Serializes the recorded events to pretty JSON.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&self.events)
+    }
+}
+
+/// A single timestamped entry in the [`Timeline`].
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEntry {
+    /// Seconds since app start.
+    pub time: f32,
+    #[serde(flatten)]
+    pub event: TimelineEvent,
+}
+
+/// The kinds of events recorded in the [`Timeline`]. Entities are stored as their raw bits.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type")]
+pub enum TimelineEvent {
+    MoodChanged {
+        entity: u64,
+        from: Mood,
+        to: Mood,
+    },
+    ZoneSatisfied {
+        zone: u64,
+        requirements: Vec<(Mood, u32)>,
+    },
+    ZoneUnsatisfied {
+        zone: u64,
+        requirements: Vec<(Mood, u32)>,
+    },
+    CommandIssued {
+        entity: u64,
+        destination: Vec2,
+    },
+    LevelWon,
+    LevelLost,
+}
+
+fn timeline_enabled(timeline: Res<Timeline>) -> bool {
+    timeline.enabled
+}
+
+fn clear_timeline(mut timeline: ResMut<Timeline>) {
+    timeline.events.clear();
+}
+
+fn record_mood_changes(
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    mut mood_changes: EventReader<MoodChanged>,
+) {
+    for change in mood_changes.read() {
+        timeline.record_event(
+            time.elapsed_secs(),
+            TimelineEvent::MoodChanged {
+                entity: change.entity.to_bits(),
+                from: change.from,
+                to: change.to,
+            },
+        );
+    }
+}
+
+fn record_zone_changes(
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    zone_query: Query<(Entity, &GoalZone), Changed<GoalZone>>,
+    mut last_satisfied: Local<HashMap<Entity, bool>>,
+) {
+    for (entity, zone) in &zone_query {
+        let was_satisfied = last_satisfied
+            .insert(entity, zone.is_satisfied)
+            .unwrap_or(false);
+        if was_satisfied == zone.is_satisfied {
+            continue;
+        }
+        let event = if zone.is_satisfied {
+            TimelineEvent::ZoneSatisfied {
+                zone: entity.to_bits(),
+                requirements: zone.requirements.clone(),
+            }
+        } else {
+            TimelineEvent::ZoneUnsatisfied {
+                zone: entity.to_bits(),
+                requirements: zone.requirements.clone(),
+            }
+        };
+        timeline.record_event(time.elapsed_secs(), event);
+    }
+}
+
+fn record_commands(
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    mut command_events: EventReader<CommandMoodelEvent>,
+) {
+    for command in command_events.read() {
+        timeline.record_event(
+            time.elapsed_secs(),
+            TimelineEvent::CommandIssued {
+                entity: command.entity.to_bits(),
+                destination: command.destination,
+            },
+        );
+    }
+}
+
+fn record_level_outcome(
+    time: Res<Time>,
+    mut timeline: ResMut<Timeline>,
+    outcome: Res<LevelOutcome>,
+) {
+    let event = match *outcome {
+        LevelOutcome::InProgress => return,
+        LevelOutcome::Won => TimelineEvent::LevelWon,
+        LevelOutcome::Lost => TimelineEvent::LevelLost,
+    };
+    timeline.record_event(time.elapsed_secs(), event);
+    export_timeline(&timeline);
+}
+
+fn export_timeline(timeline: &Timeline) {
+    let json = match timeline.to_json() {
+        Ok(json) => json,
+        Err(error) => {
+            error!("Failed to serialize timeline: {}", error);
+            return;
+        }
+    };
+
+    #[cfg(not(target_family = "wasm"))]
+    match std::fs::write(&timeline.export_path, json) {
+        Ok(()) => info!(
+            "Exported {} timeline events to {}",
+            timeline.events.len(),
+            timeline.export_path
+        ),
+        Err(error) => error!(
+            "Failed to write timeline to {}: {}",
+            timeline.export_path, error
+        ),
+    }
+
+    #[cfg(target_family = "wasm")]
+    info!(
+        "Filesystem writes are unavailable on web, timeline:\n{}",
+        json
+    );
+}
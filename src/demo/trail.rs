@@ -0,0 +1,137 @@
+//! Fading trails behind fast Moodels, so charges read as motion rather than jumps.
+//!
+//! Rage Moodels always leave a trail, and any other Moodel does while it moves faster
+//! than [`TRAIL_SPEED_THRESHOLD`]. Trails are drawn with gizmos in the Moodel's mood color.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::{
+    AppSystems, PausableSystems,
+    demo::{
+        ai::AiEntity,
+        culling::Culled,
+        mood::{ColorScheme, Mood},
+    },
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<Trail>();
+
+    app.add_systems(
+        Update,
+        (attach_trails, record_trail_points)
+            .chain()
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+    app.add_systems(Update, draw_trails.in_set(AppSystems::Update));
+}
+
+/// Moodels moving faster than this leave a trail, in world units per second.
+const TRAIL_SPEED_THRESHOLD: f32 = 400.0;
+/// How opaque the newest end of a trail is.
+const TRAIL_ALPHA: f32 = 0.6;
+
+/// Recent positions of a moving Moodel, drawn as a fading line.
+#[derive(Component, Reflect, Debug)]
+#[reflect(Component)]
+pub struct Trail {
+    /// Older points are dropped once the trail holds this many.
+    pub max_points: usize,
+    /// Seconds a point stays on the trail.
+    pub lifetime: f32,
+    /// Timestamped positions, oldest first.
+    #[reflect(ignore)]
+    points: VecDeque<(f32, Vec2)>,
+}
+
+impl Default for Trail {
+    fn default() -> Self {
+        Self {
+            max_points: 24,
+            lifetime: 0.4,
+            points: VecDeque::new(),
+        }
+    }
+}
+
+impl Trail {
+    /// Adds the latest position, dropping points that are too old or beyond the cap.
+    pub fn push(&mut self, now: f32, position: Vec2) {
+        self.points.push_back((now, position));
+        self.trim(now);
+    }
+
+    /// Drops points older than the lifetime and beyond the cap.
+    pub fn trim(&mut self, now: f32) {
+        while self
+            .points
+            .front()
+            .is_some_and(|(time, _)| now - time > self.lifetime)
+        {
+            self.points.pop_front();
+        }
+        let excess = self.points.len().saturating_sub(self.max_points);
+        self.points.drain(..excess);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+fn leaves_trail(mood: Mood, velocity: Vec2) -> bool {
+    mood == Mood::Rage || velocity.length_squared() > TRAIL_SPEED_THRESHOLD.powi(2)
+}
+
+fn attach_trails(
+    mut commands: Commands,
+    moodel_query: Query<(Entity, &Mood, &LinearVelocity), (With<AiEntity>, Without<Trail>)>,
+) {
+    for (entity, mood, velocity) in &moodel_query {
+        if leaves_trail(*mood, velocity.0) {
+            commands.entity(entity).insert(Trail::default());
+        }
+    }
+}
+
+/// Records positions while a Moodel leaves a trail, and removes the trail once it has faded
+/// after the Moodel slows down.
+fn record_trail_points(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut trail_query: Query<(Entity, &Transform, &Mood, &LinearVelocity, &mut Trail)>,
+) {
+    let now = time.elapsed_secs();
+    for (entity, transform, mood, velocity, mut trail) in &mut trail_query {
+        if leaves_trail(*mood, velocity.0) {
+            trail.push(now, transform.translation.truncate());
+            continue;
+        }
+        trail.trim(now);
+        if trail.is_empty() {
+            commands.entity(entity).remove::<Trail>();
+        }
+    }
+}
+
+fn draw_trails(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    scheme: Res<ColorScheme>,
+    trail_query: Query<(&Trail, &Mood), Without<Culled>>,
+) {
+    let now = time.elapsed_secs();
+    for (trail, mood) in &trail_query {
+        if trail.points.len() < 2 {
+            continue;
+        }
+        let color = mood.color_for_scheme(*scheme);
+        gizmos.linestrip_gradient_2d(trail.points.iter().map(|&(point_time, position)| {
+            let fade = 1.0 - ((now - point_time) / trail.lifetime).clamp(0.0, 1.0);
+            (position, color.with_alpha(fade * TRAIL_ALPHA))
+        }));
+    }
+}
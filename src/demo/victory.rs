@@ -0,0 +1,286 @@
+//! A short celebratory outro played when a level is won.
+//!
+//! Every Moodel turns Happy and bursts outwards from the play area center,
+//! leaving fading trails, while a fanfare plays. After a couple of seconds the
+//! action slows to a freeze-frame, then the sequence finishes and the results can
+//! be shown. Skipping the celebration skips the slow-down too.
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    AppSystems, PausableSystems,
+    audio::PlaySound,
+    demo::{
+        ai::AiEntity,
+        level::LevelOutcome,
+        mood::{ColorScheme, Mood, SetMood},
+        movement::{MovementController, PlayArea},
+        rng::GameRng,
+    },
+    keybindings::{Action, action_just_pressed},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<VictoryConfig>();
+    app.init_resource::<VictoryConfig>();
+    app.register_type::<VictoryTrail>();
+    app.add_sub_state::<VictorySequence>();
+
+    app.add_systems(
+        Update,
+        sync_victory_sequence
+            .run_if(in_state(Screen::Gameplay).and(resource_changed::<LevelOutcome>))
+            .in_set(AppSystems::Update),
+    );
+    app.add_systems(OnEnter(VictorySequence::Celebrating), begin_celebration);
+    app.add_systems(OnEnter(VictorySequence::Flourishing), begin_flourish);
+    app.add_systems(OnExit(VictorySequence::Flourishing), end_flourish);
+    app.add_systems(
+        Update,
+        (
+            tick_flourish.in_set(AppSystems::TickTimers),
+            drive_celebration.in_set(AppSystems::Update),
+        )
+            .run_if(in_state(VictorySequence::Flourishing))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        (
+            tick_celebration.in_set(AppSystems::TickTimers),
+            skip_celebration
+                .run_if(action_just_pressed(Action::SkipCelebration))
+                .in_set(AppSystems::RecordInput),
+            (drive_celebration, spawn_victory_trails).in_set(AppSystems::Update),
+        )
+            .run_if(in_state(VictorySequence::Celebrating))
+            .in_set(PausableSystems),
+    );
+    app.add_systems(
+        Update,
+        fade_victory_trails
+            .in_set(AppSystems::Update)
+            .in_set(PausableSystems),
+    );
+}
+
+/// The stages of the level completion outro. Only exists during gameplay.
+#[derive(SubStates, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[source(Screen = Screen::Gameplay)]
+pub enum VictorySequence {
+    #[default]
+    Inactive,
+    /// AI is overridden while the Moodels celebrate.
+    Celebrating,
+    /// Time slows to a stop on the end of the celebration.
+    Flourishing,
+    /// The celebration is over and the results can be shown.
+    Finished,
+}
+
+/// Tuning for the victory celebration.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct VictoryConfig {
+    /// Whether to play the celebration at all. If disabled, winning goes straight to `Finished`.
+    pub enabled: bool,
+    /// How long the celebration lasts, in seconds.
+    pub duration: f32,
+    /// The impulse that scatters the Moodels when the celebration starts.
+    pub burst_impulse: f32,
+    /// Seconds between trail segments spawned behind each Moodel.
+    pub trail_interval: f32,
+    /// How long each trail segment takes to fade out, in seconds.
+    pub trail_lifetime: f32,
+    /// How long time takes to slow to a freeze at the end of the celebration, in real seconds.
+    pub flourish_duration: f32,
+}
+
+impl Default for VictoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            duration: 2.0,
+            burst_impulse: 600.0,
+            trail_interval: 0.03,
+            trail_lifetime: 0.5,
+            flourish_duration: 0.8,
+        }
+    }
+}
+
+/// Counts down the remaining celebration time.
+#[derive(Resource)]
+struct CelebrationTimer {
+    duration: Timer,
+    trail: Timer,
+}
+
+/// Counts down the slow-down at the end of the celebration. Ticks in real time, since
+/// virtual time is what's being slowed.
+#[derive(Resource)]
+pub struct CompletionFlourish {
+    pub timer: Timer,
+}
+
+/// A fading segment of a Moodel's celebration trail.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct VictoryTrail {
+    pub lifetime: Timer,
+}
+
+/// Starts the celebration when the level is won, and resets it when a new level starts.
+fn sync_victory_sequence(
+    outcome: Res<LevelOutcome>,
+    config: Res<VictoryConfig>,
+    sequence: Res<State<VictorySequence>>,
+    mut next_sequence: ResMut<NextState<VictorySequence>>,
+) {
+    match (*outcome, sequence.get()) {
+        (LevelOutcome::Won, VictorySequence::Inactive) => {
+            next_sequence.set(if config.enabled {
+                VictorySequence::Celebrating
+            } else {
+                VictorySequence::Finished
+            });
+        }
+        (
+            LevelOutcome::InProgress,
+            VictorySequence::Celebrating | VictorySequence::Flourishing | VictorySequence::Finished,
+        ) => {
+            next_sequence.set(VictorySequence::Inactive);
+        }
+        _ => {}
+    }
+}
+
+fn begin_celebration(
+    mut commands: Commands,
+    config: Res<VictoryConfig>,
+    play_area: Res<PlayArea>,
+    mut moodel_query: Query<(Entity, &Transform, &mut ExternalImpulse), With<AiEntity>>,
+    mut set_mood_events: EventWriter<SetMood>,
+    mut sfx_writer: EventWriter<PlaySound>,
+    mut rng: ResMut<GameRng>,
+) {
+    commands.insert_resource(CelebrationTimer {
+        duration: Timer::from_seconds(config.duration, TimerMode::Once),
+        trail: Timer::from_seconds(config.trail_interval, TimerMode::Repeating),
+    });
+
+    for (entity, transform, mut impulse) in &mut moodel_query {
+        set_mood_events.write(SetMood {
+            entity,
+            mood: Mood::Happy,
+        });
+
+        // Burst away from the center, with a bit of spread so it looks like fireworks.
+        let outward = (transform.translation.truncate() - play_area.center).normalize_or(Vec2::Y);
+        let direction = Vec2::from_angle(rng.random_range(-0.5..0.5)).rotate(outward);
+        impulse.apply_impulse(direction * config.burst_impulse * rng.random_range(0.75..1.25));
+    }
+
+    sfx_writer.write(PlaySound::Victory);
+}
+
+fn tick_celebration(
+    time: Res<Time>,
+    mut timer: ResMut<CelebrationTimer>,
+    mut next_sequence: ResMut<NextState<VictorySequence>>,
+) {
+    timer.duration.tick(time.delta());
+    timer.trail.tick(time.delta());
+    if timer.duration.finished() {
+        next_sequence.set(VictorySequence::Flourishing);
+    }
+}
+
+fn skip_celebration(mut next_sequence: ResMut<NextState<VictorySequence>>) {
+    next_sequence.set(VictorySequence::Finished);
+}
+
+fn begin_flourish(mut commands: Commands, config: Res<VictoryConfig>) {
+    commands.insert_resource(CompletionFlourish {
+        timer: Timer::from_seconds(config.flourish_duration, TimerMode::Once),
+    });
+}
+
+/// Eases the game speed down to a stop, then finishes the sequence.
+fn tick_flourish(
+    real_time: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut flourish: ResMut<CompletionFlourish>,
+    mut next_sequence: ResMut<NextState<VictorySequence>>,
+) {
+    flourish.timer.tick(real_time.delta());
+    let remaining = flourish.timer.fraction_remaining();
+    virtual_time.set_relative_speed(remaining * remaining);
+    if flourish.timer.finished() {
+        next_sequence.set(VictorySequence::Finished);
+    }
+}
+
+/// Restores normal speed, including when gameplay is left mid-flourish.
+fn end_flourish(mut commands: Commands, mut virtual_time: ResMut<Time<Virtual>>) {
+    commands.remove_resource::<CompletionFlourish>();
+    virtual_time.set_relative_speed(1.0);
+}
+
+/// Overrides the AI intent so the Moodels keep scattering outwards.
+fn drive_celebration(
+    play_area: Res<PlayArea>,
+    mut moodel_query: Query<(&Transform, &mut MovementController), With<AiEntity>>,
+) {
+    for (transform, mut controller) in &mut moodel_query {
+        controller.intent =
+            (transform.translation.truncate() - play_area.center).normalize_or_zero();
+    }
+}
+
+fn spawn_victory_trails(
+    mut commands: Commands,
+    config: Res<VictoryConfig>,
+    timer: Res<CelebrationTimer>,
+    scheme: Res<ColorScheme>,
+    moodel_query: Query<(&Transform, &Mood), With<AiEntity>>,
+) {
+    if !timer.trail.just_finished() {
+        return;
+    }
+    for (transform, mood) in &moodel_query {
+        commands.spawn((
+            Name::new("Victory Trail"),
+            Sprite {
+                color: mood.color_for_scheme(*scheme),
+                custom_size: Some(Vec2::splat(10.0)),
+                ..default()
+            },
+            Transform::from_translation(transform.translation.truncate().extend(-1.0)),
+            VictoryTrail {
+                lifetime: Timer::from_seconds(config.trail_lifetime, TimerMode::Once),
+            },
+            StateScoped(Screen::Gameplay),
+        ));
+    }
+}
+
+fn fade_victory_trails(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut trail_query: Query<(Entity, &mut VictoryTrail, &mut Sprite, &mut Transform)>,
+) {
+    for (entity, mut trail, mut sprite, mut transform) in &mut trail_query {
+        trail.lifetime.tick(time.delta());
+        if trail.lifetime.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let remaining = trail.lifetime.fraction_remaining();
+        sprite.color.set_alpha(remaining);
+        transform.scale = Vec3::splat(remaining);
+    }
+}
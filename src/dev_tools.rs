@@ -1,10 +1,13 @@
 //! Development tools for the game. This plugin is only enabled in dev builds.
 
-use bevy::{
-    dev_tools::states::log_transitions, input::common_conditions::input_just_pressed, prelude::*,
-    ui::UiDebugOptions,
-};
+mod ai_outlines;
+mod asset_hot_reload;
+mod console;
+mod density_overlay;
+mod teleport;
+
 use avian2d::prelude::*;
+use bevy::{dev_tools::states::log_transitions, prelude::*};
 
 use crate::screens::Screen;
 
@@ -12,18 +15,21 @@ pub(super) fn plugin(app: &mut App) {
     // Log `Screen` state transitions.
     app.add_systems(Update, log_transitions::<Screen>);
 
-    // Toggle the debug overlay for UI.
-    app.add_systems(
-        Update,
-        toggle_debug_ui.run_if(input_just_pressed(TOGGLE_KEY)),
-    );
+    // Developer console (the UI debug overlay is toggled from there with `debug_ui`).
+    app.add_plugins(console::plugin);
 
-    // Add physics debug plugin
-    app.add_plugins(PhysicsDebugPlugin::default());
-}
+    // Color-coded outlines for each Moodel's current AI action.
+    app.add_plugins(ai_outlines::plugin);
 
-const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+    // Heatmap of where Moodels crowd together.
+    app.add_plugins(density_overlay::plugin);
 
-fn toggle_debug_ui(mut options: ResMut<UiDebugOptions>) {
-    options.toggle();
+    // Teleport selected Moodels to the cursor.
+    app.add_plugins(teleport::plugin);
+
+    // Refresh Moodel sprites and sounds when their files change.
+    app.add_plugins(asset_hot_reload::plugin);
+
+    // Add physics debug plugin
+    app.add_plugins(PhysicsDebugPlugin::default());
 }
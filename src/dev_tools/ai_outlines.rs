@@ -0,0 +1,56 @@
+//! Color-coded rings showing the current [`AiAction`] of every Moodel.
+//!
+//! Toggle with F2 or the `ai_outlines` console command.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::demo::ai::{AiAction, AiWanderState};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<AiActionOutlines>();
+
+    app.add_systems(
+        Update,
+        (
+            toggle_ai_outlines.run_if(input_just_pressed(TOGGLE_KEY)),
+            draw_ai_outlines.run_if(ai_outlines_enabled),
+        ),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F2;
+
+/// Radius of the ring drawn around each Moodel.
+const OUTLINE_RADIUS: f32 = 60.0;
+
+/// Whether the AI action outlines are drawn.
+#[derive(Resource, Default)]
+pub struct AiActionOutlines(pub bool);
+
+fn ai_outlines_enabled(outlines: Res<AiActionOutlines>) -> bool {
+    outlines.0
+}
+
+fn toggle_ai_outlines(mut outlines: ResMut<AiActionOutlines>) {
+    outlines.0 = !outlines.0;
+}
+
+fn draw_ai_outlines(mut gizmos: Gizmos, ai_query: Query<(&GlobalTransform, &AiWanderState)>) {
+    for (transform, wander_state) in &ai_query {
+        gizmos.circle_2d(
+            transform.translation().truncate(),
+            OUTLINE_RADIUS,
+            action_color(&wander_state.action),
+        );
+    }
+}
+
+fn action_color(action: &AiAction) -> Color {
+    match action {
+        AiAction::Wandering => Color::srgb(0.2, 0.9, 0.3),
+        AiAction::Aiming { .. } => Color::srgb(1.0, 0.85, 0.1),
+        AiAction::Charging { .. } => Color::srgb(1.0, 0.15, 0.15),
+        AiAction::MovingToTarget { .. } => Color::srgb(0.2, 0.5, 1.0),
+        AiAction::Held => Color::WHITE,
+    }
+}
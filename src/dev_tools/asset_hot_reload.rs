@@ -0,0 +1,67 @@
+//! Live refresh of Moodel sprites and sounds when their files change on disk, for quicker art
+//! and audio iteration. Level files are hot-reloaded by the level module itself.
+
+use bevy::prelude::*;
+
+use crate::demo::{
+    ai::AiEntity,
+    mood::{Mood, MoodAssets},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            refresh_mood_sprites.run_if(resource_exists::<MoodAssets>),
+            restart_modified_sounds,
+        ),
+    );
+}
+
+/// Re-applies a modified mood sprite to every Moodel in that mood. Reassigning the handle
+/// marks their sprites as changed, so their bounds are recalculated if the image was resized.
+fn refresh_mood_sprites(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mood_assets: Res<MoodAssets>,
+    mut moodel_query: Query<(&Mood, &mut Sprite), With<AiEntity>>,
+) {
+    for event in image_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        // Several moods can share a sprite, e.g. when Fear falls back to Neutral's
+        for mood in Mood::all() {
+            let sprite_handle = mood_assets.get_sprite(mood);
+            if sprite_handle.id() != *id {
+                continue;
+            }
+            info!("{:?} sprite modified, refreshing Moodels", mood);
+            for (moodel_mood, mut sprite) in &mut moodel_query {
+                if *moodel_mood == mood {
+                    sprite.image = sprite_handle.clone();
+                }
+            }
+        }
+    }
+}
+
+/// Restarts anything playing a modified sound, such as music or the ambient loops, so the new
+/// version is heard. One-shot sound effects pick it up the next time they play.
+fn restart_modified_sounds(
+    mut commands: Commands,
+    mut audio_events: EventReader<AssetEvent<AudioSource>>,
+    player_query: Query<(Entity, &AudioPlayer), With<AudioSink>>,
+) {
+    for event in audio_events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        for (entity, player) in &player_query {
+            if player.0.id() == *id {
+                info!("Sound modified, restarting it");
+                // Removing the sink makes Bevy play the source again from the start
+                commands.entity(entity).remove::<AudioSink>();
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! A developer console for issuing commands at runtime.
+//!
+//! Toggle it with the backtick key and type `help` for the list of commands.
+
+use avian2d::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::UiDebugOptions};
+use bevy_egui::{EguiContextPass, EguiContexts, egui};
+use rand::Rng;
+
+use super::{ai_outlines::AiActionOutlines, density_overlay::DensityOverlay};
+use crate::{
+    demo::{
+        ai::{AiDebugGizmos, AiEntity, CursorAttract},
+        level::{
+            ActiveLevel, ActiveLevelSource, DespawnMoodel, LevelOutcome, SaveLevel, SpawnLevel,
+            SpawnMoodel,
+        },
+        level_library::available_programmatic_levels,
+        level_share::{URL_FRAGMENT_PREFIX, decode_level, encode_level},
+        mood::{Mood, MoodAssets, SetMood},
+        movement::PlayArea,
+        player_input::Selected,
+        replay::{Replay, ReplayPlayer, ReplayRecorder},
+        rng::GameRng,
+        timeline::Timeline,
+    },
+    keybindings::KeyboardCaptured,
+    save,
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DevConsole>();
+
+    app.add_systems(
+        Update,
+        (
+            toggle_console.run_if(input_just_pressed(TOGGLE_KEY)),
+            run_console_commands,
+        ),
+    );
+    app.add_systems(EguiContextPass, draw_console);
+    app.add_systems(PreUpdate, update_keyboard_capture);
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::Backquote;
+
+/// Maximum number of lines kept in the console output.
+const MAX_LOG_LINES: usize = 200;
+
+const USAGE: &str = "\
+Commands:
+  help                        Show this message
+  spawn <mood> [count]        Spawn Moodels at random positions
+  despawn                     Remove the selected Moodels
+  set_mood <all|mood> <mood>  Change the mood of all Moodels (or those in a mood)
+  load <level>                Load levels/<level>.level.json
+  load_code [id]              Load a programmatic level by id, or list the ids
+  share                       Print a share code for the current level
+  load_share <code>           Load a level from a share code
+  save <level>                Save the live level to assets/levels/<level>.level.json
+  win                         Force the current level to be won
+  debug_ui                    Toggle the UI debug overlay
+  physics_debug               Toggle physics debug rendering
+  timeline <on|off>           Record gameplay events, exported as JSON on level end
+  record <on|off>             Record input from the next level spawn, exported on gameplay end
+  replay <file>               Play back a recorded replay file
+  seed <n>                    Reseed the random number generator
+  cursor_attract              Toggle Moodels being drawn towards the cursor
+  ai_outlines                 Toggle rings showing each Moodel's AI action
+  ai_gizmos                   Toggle AI intent, vision radius and target gizmos
+  density                     Toggle the Moodel density heatmap
+  clear                       Clear the console output";
+
+/// State of the developer console.
+#[derive(Resource, Default)]
+struct DevConsole {
+    open: bool,
+    input: String,
+    log: Vec<String>,
+    /// Submitted lines waiting to be executed against the world.
+    pending: Vec<String>,
+}
+
+impl DevConsole {
+    fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        if self.log.len() > MAX_LOG_LINES {
+            let overflow = self.log.len() - MAX_LOG_LINES;
+            self.log.drain(..overflow);
+        }
+    }
+}
+
+/// A parsed console command.
+#[derive(Debug)]
+enum ConsoleCommand {
+    Help,
+    Spawn { mood: Mood, count: u32 },
+    Despawn,
+    SetMood { from: Option<Mood>, to: Mood },
+    Load(String),
+    LoadCode(String),
+    ListCode,
+    Share,
+    LoadShare(String),
+    Save(String),
+    Win,
+    DebugUi,
+    PhysicsDebug,
+    Timeline(bool),
+    Record(bool),
+    Replay(String),
+    Seed(u64),
+    CursorAttract,
+    AiOutlines,
+    AiGizmos,
+    Density,
+    Clear,
+}
+
+/// Keeps key bindings from firing while egui takes keyboard input, e.g. while typing a command.
+fn update_keyboard_capture(mut contexts: EguiContexts, mut captured: ResMut<KeyboardCaptured>) {
+    let wants_keyboard = contexts
+        .try_ctx_mut()
+        .is_some_and(|ctx| ctx.wants_keyboard_input());
+    captured.set_if_neq(KeyboardCaptured(wants_keyboard));
+}
+
+fn toggle_console(mut console: ResMut<DevConsole>) {
+    console.open = !console.open;
+}
+
+fn draw_console(mut contexts: EguiContexts, mut console: ResMut<DevConsole>) {
+    if !console.open {
+        return;
+    }
+    let Some(ctx) = contexts.try_ctx_mut() else {
+        return;
+    };
+    let console = &mut *console;
+
+    egui::Window::new("Console")
+        .default_width(520.0)
+        .show(ctx, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(260.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for line in &console.log {
+                        ui.monospace(line);
+                    }
+                });
+
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut console.input)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("type `help` for a list of commands"),
+            );
+            if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                let line = std::mem::take(&mut console.input);
+                if !line.trim().is_empty() {
+                    console.pending.push(line);
+                }
+                response.request_focus();
+            }
+        });
+}
+
+/// Executes submitted console lines with full world access.
+fn run_console_commands(world: &mut World) {
+    let pending = std::mem::take(&mut world.resource_mut::<DevConsole>().pending);
+
+    for line in pending {
+        let output = match parse_command(&line) {
+            Ok(ConsoleCommand::Clear) => {
+                world.resource_mut::<DevConsole>().log.clear();
+                continue;
+            }
+            Ok(command) => execute_command(world, command),
+            Err(error) => format!("{error}\n{USAGE}"),
+        };
+
+        let mut console = world.resource_mut::<DevConsole>();
+        console.print(format!("> {line}"));
+        for output_line in output.lines() {
+            console.print(output_line);
+        }
+    }
+}
+
+fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let mut args = line.split_whitespace();
+    let Some(name) = args.next() else {
+        return Err("Empty command".to_string());
+    };
+    let args: Vec<&str> = args.collect();
+
+    let command = match (name, args.as_slice()) {
+        ("help", []) => ConsoleCommand::Help,
+        ("spawn", [mood]) => ConsoleCommand::Spawn {
+            mood: parse_mood(mood)?,
+            count: 1,
+        },
+        ("spawn", [mood, count]) => ConsoleCommand::Spawn {
+            mood: parse_mood(mood)?,
+            count: count
+                .parse()
+                .map_err(|_| format!("Invalid count: {count}"))?,
+        },
+        ("despawn", []) => ConsoleCommand::Despawn,
+        ("set_mood", [target, mood]) => ConsoleCommand::SetMood {
+            from: if target.eq_ignore_ascii_case("all") {
+                None
+            } else {
+                Some(parse_mood(target)?)
+            },
+            to: parse_mood(mood)?,
+        },
+        ("load", [level]) => ConsoleCommand::Load(level.to_string()),
+        ("load_code", [id]) => ConsoleCommand::LoadCode(id.to_string()),
+        ("load_code", []) => ConsoleCommand::ListCode,
+        ("share", []) => ConsoleCommand::Share,
+        ("load_share", [code]) => ConsoleCommand::LoadShare(code.to_string()),
+        ("save", [level]) => ConsoleCommand::Save(level.to_string()),
+        ("win", []) => ConsoleCommand::Win,
+        ("debug_ui", []) => ConsoleCommand::DebugUi,
+        ("physics_debug", []) => ConsoleCommand::PhysicsDebug,
+        ("timeline", ["on"]) => ConsoleCommand::Timeline(true),
+        ("timeline", ["off"]) => ConsoleCommand::Timeline(false),
+        ("record", ["on"]) => ConsoleCommand::Record(true),
+        ("record", ["off"]) => ConsoleCommand::Record(false),
+        ("replay", [path]) => ConsoleCommand::Replay(path.to_string()),
+        ("seed", [seed]) => {
+            ConsoleCommand::Seed(seed.parse().map_err(|_| format!("Invalid seed: {seed}"))?)
+        }
+        ("cursor_attract", []) => ConsoleCommand::CursorAttract,
+        ("ai_outlines", []) => ConsoleCommand::AiOutlines,
+        ("ai_gizmos", []) => ConsoleCommand::AiGizmos,
+        ("density", []) => ConsoleCommand::Density,
+        ("clear", []) => ConsoleCommand::Clear,
+        _ => return Err(format!("Unknown command: {line}")),
+    };
+    Ok(command)
+}
+
+fn parse_mood(name: &str) -> Result<Mood, String> {
+    Mood::from_name(name).ok_or_else(|| format!("Unknown mood: {name}"))
+}
+
+fn execute_command(world: &mut World, command: ConsoleCommand) -> String {
+    match command {
+        ConsoleCommand::Help => USAGE.to_string(),
+        ConsoleCommand::Spawn { mood, count } => spawn_moodels(world, mood, count),
+        ConsoleCommand::Despawn => {
+            let selected: Vec<Entity> = world
+                .query_filtered::<Entity, (With<AiEntity>, With<Selected>)>()
+                .iter(world)
+                .collect();
+            for entity in &selected {
+                world.send_event(DespawnMoodel(*entity));
+            }
+            format!("Despawned {} Moodel(s)", selected.len())
+        }
+        ConsoleCommand::SetMood { from, to } => {
+            let targets: Vec<Entity> = world
+                .query_filtered::<(Entity, &Mood), With<AiEntity>>()
+                .iter(world)
+                .filter(|(_, mood)| from.is_none_or(|from| **mood == from))
+                .map(|(entity, _)| entity)
+                .collect();
+            for entity in &targets {
+                world.send_event(SetMood {
+                    entity: *entity,
+                    mood: to,
+                });
+            }
+            format!("Set {} Moodel(s) to {to:?}", targets.len())
+        }
+        ConsoleCommand::Load(level) => {
+            let path = format!("levels/{level}.level.json");
+            reload_level(world, SpawnLevel::FromFile(path.clone()));
+            format!("Loading {path}")
+        }
+        ConsoleCommand::LoadCode(id) => {
+            reload_level(world, SpawnLevel::Programmatic(id.clone()));
+            format!("Loading programmatic level {id}")
+        }
+        ConsoleCommand::ListCode => format!(
+            "Programmatic levels: {}",
+            available_programmatic_levels().join(", ")
+        ),
+        ConsoleCommand::Share => match &world.resource::<ActiveLevel>().0 {
+            Some(level) => format!(
+                "Share code for {}:\n{URL_FRAGMENT_PREFIX}{}",
+                level.name,
+                encode_level(level)
+            ),
+            None => "No level is loaded".to_string(),
+        },
+        ConsoleCommand::LoadShare(code) => load_shared_level(world, &code),
+        ConsoleCommand::Save(level) => {
+            let path = format!("assets/levels/{level}.level.json");
+            world.send_event(SaveLevel(path.clone()));
+            format!("Saving to {path}")
+        }
+        ConsoleCommand::Win => {
+            *world.resource_mut::<LevelOutcome>() = LevelOutcome::Won;
+            "Level forced to Won".to_string()
+        }
+        ConsoleCommand::DebugUi => {
+            world.resource_mut::<UiDebugOptions>().toggle();
+            "Toggled UI debug overlay".to_string()
+        }
+        ConsoleCommand::PhysicsDebug => {
+            let mut store = world.resource_mut::<GizmoConfigStore>();
+            let (config, _) = store.config_mut::<PhysicsGizmos>();
+            config.enabled = !config.enabled;
+            format!("Physics debug rendering: {}", config.enabled)
+        }
+        ConsoleCommand::Timeline(enabled) => {
+            world.resource_mut::<Timeline>().enabled = enabled;
+            format!("Timeline recording: {enabled}")
+        }
+        ConsoleCommand::Record(enabled) => {
+            let mut recorder = world.resource_mut::<ReplayRecorder>();
+            recorder.enabled = enabled;
+            if !enabled {
+                recorder.export();
+            }
+            format!("Replay recording: {enabled}")
+        }
+        ConsoleCommand::Replay(path) => play_replay(world, &path),
+        ConsoleCommand::Seed(seed) => {
+            *world.resource_mut::<GameRng>() = GameRng::new(seed);
+            format!("Random seed: {seed}")
+        }
+        ConsoleCommand::CursorAttract => {
+            let mut cursor_attract = world.resource_mut::<CursorAttract>();
+            cursor_attract.0 = !cursor_attract.0;
+            format!("Cursor attraction: {}", cursor_attract.0)
+        }
+        ConsoleCommand::AiOutlines => {
+            let mut outlines = world.resource_mut::<AiActionOutlines>();
+            outlines.0 = !outlines.0;
+            format!("AI action outlines: {}", outlines.0)
+        }
+        ConsoleCommand::AiGizmos => {
+            let mut debug_gizmos = world.resource_mut::<AiDebugGizmos>();
+            debug_gizmos.0 = !debug_gizmos.0;
+            format!("AI debug gizmos: {}", debug_gizmos.0)
+        }
+        ConsoleCommand::Density => {
+            let mut overlay = world.resource_mut::<DensityOverlay>();
+            overlay.0 = !overlay.0;
+            format!("Density heatmap: {}", overlay.0)
+        }
+        ConsoleCommand::Clear => String::new(),
+    }
+}
+
+fn spawn_moodels(world: &mut World, mood: Mood, count: u32) -> String {
+    if *world.resource::<State<Screen>>().get() != Screen::Gameplay {
+        return "Moodels can only be spawned during gameplay".to_string();
+    }
+    if !world.contains_resource::<MoodAssets>() {
+        return "Mood assets are still loading".to_string();
+    }
+    let play_area = world.resource::<PlayArea>();
+    let (center, half_size) = (play_area.center, play_area.size / 2.0);
+
+    let mut rng = world.resource_mut::<GameRng>();
+    let positions: Vec<Vec2> = (0..count)
+        .map(|_| {
+            center
+                + Vec2::new(
+                    rng.random_range(-half_size.x..half_size.x),
+                    rng.random_range(-half_size.y..half_size.y),
+                )
+        })
+        .collect();
+    for position in positions {
+        world.send_event(SpawnMoodel {
+            mood,
+            position,
+            max_speed: None,
+        });
+    }
+    format!("Spawned {count} {mood:?} Moodel(s)")
+}
+
+fn play_replay(world: &mut World, path: &str) -> String {
+    if *world.resource::<State<Screen>>().get() != Screen::Gameplay {
+        return "Replays can only be played during gameplay".to_string();
+    }
+    let Some(json) = save::read(path) else {
+        return format!("Could not read {path}");
+    };
+    let replay: Replay = match serde_json::from_str(&json) {
+        Ok(replay) => replay,
+        Err(error) => return format!("Invalid replay file: {error}"),
+    };
+    // Spawning the level reseeds the generator, so the run plays out the same way
+    *world.resource_mut::<GameRng>() = GameRng::new(replay.seed);
+    let summary = format!(
+        "Playing {} events with seed {}",
+        replay.entries.len(),
+        replay.seed
+    );
+    world.resource_mut::<ReplayPlayer>().play(replay);
+    summary
+}
+
+fn load_shared_level(world: &mut World, code: &str) -> String {
+    let code = code.strip_prefix(URL_FRAGMENT_PREFIX).unwrap_or(code);
+    let level = match decode_level(code) {
+        Ok(level) => level,
+        Err(error) => return format!("Invalid share code: {error}"),
+    };
+    let summary = format!("Loading shared level {}", level.name);
+    match serde_json::to_string(&level) {
+        Ok(json) => reload_level(world, SpawnLevel::FromJson(json)),
+        Err(error) => return format!("Failed to serialize the shared level: {error}"),
+    }
+    summary
+}
+
+/// Requests a new level. The current level is replaced once the new one has loaded and passed
+/// its checks. The level replaces the campaign's, so retrying it after a loss plays it again.
+fn reload_level(world: &mut World, request: SpawnLevel) {
+    *world.resource_mut::<ActiveLevelSource>() = ActiveLevelSource::Other(request.clone());
+    world.send_event(request);
+}
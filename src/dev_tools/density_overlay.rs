@@ -0,0 +1,90 @@
+//! A heatmap of where Moodels crowd together, drawn over the spatial grid's cells.
+//!
+//! Toggle with F3 or the `density` console command.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+use std::collections::HashMap;
+
+use crate::demo::spatial_grid::SpatialGrid;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DensityOverlay>();
+
+    app.add_systems(
+        Update,
+        (
+            toggle_density_overlay.run_if(input_just_pressed(TOGGLE_KEY)),
+            update_density_overlay,
+        )
+            .chain(),
+    );
+}
+
+const TOGGLE_KEY: KeyCode = KeyCode::F3;
+
+/// Opacity of the most crowded cell.
+const MAX_CELL_ALPHA: f32 = 0.5;
+/// Drawn above the zones but below the Moodels.
+const OVERLAY_Z: f32 = -0.5;
+
+/// Whether the density heatmap is drawn.
+#[derive(Resource, Default)]
+pub struct DensityOverlay(pub bool);
+
+/// A quad covering one cell of the heatmap.
+#[derive(Component)]
+struct DensityCell;
+
+fn toggle_density_overlay(mut overlay: ResMut<DensityOverlay>) {
+    overlay.0 = !overlay.0;
+}
+
+/// Keeps one quad per occupied cell, with an alpha proportional to how many Moodels it holds
+/// relative to the most crowded cell.
+fn update_density_overlay(
+    mut commands: Commands,
+    overlay: Res<DensityOverlay>,
+    grid: Res<SpatialGrid>,
+    mut cell_sprites: Local<HashMap<IVec2, Entity>>,
+    mut sprite_query: Query<&mut Sprite, With<DensityCell>>,
+) {
+    let counts: HashMap<IVec2, usize> = if overlay.0 {
+        grid.cell_counts().collect()
+    } else {
+        HashMap::new()
+    };
+
+    cell_sprites.retain(|cell, entity| {
+        let occupied = counts.contains_key(cell);
+        if !occupied {
+            commands.entity(*entity).despawn();
+        }
+        occupied
+    });
+
+    let Some(max_count) = counts.values().copied().max() else {
+        return;
+    };
+    let cell_size = grid.cell_size();
+    for (cell, count) in counts {
+        let color =
+            Color::srgb(1.0, 0.3, 0.1).with_alpha(count as f32 / max_count as f32 * MAX_CELL_ALPHA);
+        if let Some(mut sprite) = cell_sprites
+            .get(&cell)
+            .and_then(|entity| sprite_query.get_mut(*entity).ok())
+        {
+            sprite.color = color;
+            continue;
+        }
+        let center = (cell.as_vec2() + 0.5) * cell_size;
+        let entity = commands
+            .spawn((
+                Name::new("Density Cell"),
+                DensityCell,
+                Sprite::from_color(color, Vec2::splat(cell_size)),
+                Transform::from_translation(center.extend(OVERLAY_Z)),
+            ))
+            .id();
+        cell_sprites.insert(cell, entity);
+    }
+}
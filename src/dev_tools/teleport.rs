@@ -0,0 +1,74 @@
+//! Instantly move the selected Moodels to the cursor, for setting up test scenarios.
+//!
+//! Press T with one or more Moodels selected.
+
+use avian2d::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    demo::{
+        ai::{AiAction, AiWanderState},
+        movement::MovementSmoothing,
+        player_input::{Selected, cursor_world_position},
+    },
+    keybindings::keyboard_available,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        teleport_selected_to_cursor
+            .run_if(input_just_pressed(TELEPORT_KEY).and(keyboard_available)),
+    );
+}
+
+const TELEPORT_KEY: KeyCode = KeyCode::KeyT;
+
+/// How far apart multiple teleported Moodels are placed around the cursor.
+const TELEPORT_SPREAD: f32 = 60.0;
+
+fn teleport_selected_to_cursor(
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut selected_query: Query<
+        (
+            &mut Transform,
+            &mut LinearVelocity,
+            Option<&mut MovementSmoothing>,
+            Option<&mut AiWanderState>,
+        ),
+        With<Selected>,
+    >,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(cursor_pos) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    let count = selected_query.iter().count();
+    for (i, (mut transform, mut velocity, smoothing, wander_state)) in
+        selected_query.iter_mut().enumerate()
+    {
+        // Spread multiple Moodels in a ring so they don't spawn inside each other.
+        let offset = if count > 1 {
+            Vec2::from_angle(i as f32 / count as f32 * std::f32::consts::TAU) * TELEPORT_SPREAD
+        } else {
+            Vec2::ZERO
+        };
+        let position = cursor_pos + offset;
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+        velocity.0 = Vec2::ZERO;
+
+        if let Some(mut smoothing) = smoothing {
+            smoothing.current_velocity = Vec2::ZERO;
+        }
+        if let Some(mut wander_state) = wander_state {
+            if matches!(wander_state.action, AiAction::MovingToTarget { .. }) {
+                wander_state.action = AiAction::Wandering;
+            }
+        }
+    }
+
+    info!("Teleported {} Moodel(s) to {:?}", count, cursor_pos);
+}
@@ -0,0 +1,468 @@
+//! Rebindable controls. Systems check logical [`Action`]s through [`ActionInput`] or
+//! [`action_just_pressed`] instead of hardcoded keys and buttons.
+//!
+//! Bindings are saved to disk (or local storage on web) whenever they change, and can be
+//! rebound from the controls menu. Key bindings are ignored while a text field, such as the
+//! dev console, is taking keyboard input.
+
+use bevy::{
+    ecs::system::SystemParam,
+    input::InputSystem,
+    prelude::*,
+    reflect::{DynamicEnum, DynamicVariant},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::{demo::mood::Mood, save};
+
+pub(super) fn plugin(app: &mut App) {
+    app.register_type::<KeyBindings>();
+    app.insert_resource(KeyBindings::load());
+    app.init_resource::<Rebinding>();
+    app.init_resource::<KeyboardCaptured>();
+
+    // Captured right after input is read, so the pressed input doesn't also trigger whatever
+    // it was bound to.
+    app.add_systems(
+        PreUpdate,
+        capture_rebinding
+            .after(InputSystem)
+            .run_if(rebinding_active),
+    );
+    app.add_systems(
+        Update,
+        save_key_bindings
+            .run_if(resource_changed::<KeyBindings>.and(not(resource_added::<KeyBindings>))),
+    );
+}
+
+/// Something the player can do with a single key, mouse button or gamepad button.
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    ToolLine,
+    ToolBox,
+    ToolCircle,
+    ToolEraser,
+    ToggleGridSnap,
+    /// Send the selected Moodels to the cursor.
+    Command,
+    /// Clear the selection.
+    Deselect,
+    Pause,
+    /// Restart the current level.
+    Restart,
+    /// Select every Moodel in a mood.
+    SelectNeutral,
+    SelectCalm,
+    SelectHappy,
+    SelectRage,
+    SelectSad,
+    SelectFear,
+    /// Move the keyboard focus to the next Moodel and select it.
+    FocusNext,
+    /// Select the focused Moodel again.
+    SelectFocused,
+    /// Send the selected Moodels to the keyboard reticle.
+    CommandToReticle,
+    /// Follow the selected Moodel with the camera.
+    FollowSelected,
+    /// Lock or unlock the mood of the selected Moodels.
+    LockMood,
+    /// Toggle the cursor attraction sandbox mode.
+    CursorAttract,
+    SkipCelebration,
+    /// Jump to the next campaign level, in dev builds.
+    SkipLevel,
+    /// Select the Moodel nearest to the gamepad reticle.
+    GamepadSelect,
+    /// Send the selected Moodels to the gamepad reticle.
+    GamepadCommand,
+    GamepadNext,
+    GamepadPrevious,
+}
+
+impl Action {
+    pub fn all() -> [Action; 27] {
+        [
+            Action::ToolLine,
+            Action::ToolBox,
+            Action::ToolCircle,
+            Action::ToolEraser,
+            Action::ToggleGridSnap,
+            Action::Command,
+            Action::Deselect,
+            Action::Pause,
+            Action::Restart,
+            Action::SelectNeutral,
+            Action::SelectCalm,
+            Action::SelectHappy,
+            Action::SelectRage,
+            Action::SelectSad,
+            Action::SelectFear,
+            Action::FocusNext,
+            Action::SelectFocused,
+            Action::CommandToReticle,
+            Action::FollowSelected,
+            Action::LockMood,
+            Action::CursorAttract,
+            Action::SkipCelebration,
+            Action::SkipLevel,
+            Action::GamepadSelect,
+            Action::GamepadCommand,
+            Action::GamepadNext,
+            Action::GamepadPrevious,
+        ]
+    }
+
+    /// The action that selects every Moodel in `mood`.
+    pub fn select_mood(mood: Mood) -> Action {
+        match mood {
+            Mood::Neutral => Action::SelectNeutral,
+            Mood::Calm => Action::SelectCalm,
+            Mood::Happy => Action::SelectHappy,
+            Mood::Rage => Action::SelectRage,
+            Mood::Sad => Action::SelectSad,
+            Mood::Fear => Action::SelectFear,
+        }
+    }
+
+    /// The name shown in the controls menu.
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::ToolLine => "Line Tool",
+            Action::ToolBox => "Box Tool",
+            Action::ToolCircle => "Circle Tool",
+            Action::ToolEraser => "Eraser Tool",
+            Action::ToggleGridSnap => "Toggle Grid Snap",
+            Action::Command => "Command",
+            Action::Deselect => "Deselect",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart Level",
+            Action::SelectNeutral => "Select Neutral",
+            Action::SelectCalm => "Select Calm",
+            Action::SelectHappy => "Select Happy",
+            Action::SelectRage => "Select Rage",
+            Action::SelectSad => "Select Sad",
+            Action::SelectFear => "Select Fear",
+            Action::FocusNext => "Focus Next",
+            Action::SelectFocused => "Select Focused",
+            Action::CommandToReticle => "Command Reticle",
+            Action::FollowSelected => "Follow Moodel",
+            Action::LockMood => "Lock Mood",
+            Action::CursorAttract => "Cursor Attract",
+            Action::SkipCelebration => "Skip Celebration",
+            Action::SkipLevel => "Skip Level",
+            Action::GamepadSelect => "Pad Select",
+            Action::GamepadCommand => "Pad Command",
+            Action::GamepadNext => "Pad Next",
+            Action::GamepadPrevious => "Pad Previous",
+        }
+    }
+
+    fn default_binding(self) -> Binding {
+        match self {
+            Action::ToolLine => Binding::Key(KeyCode::KeyQ),
+            Action::ToolBox => Binding::Key(KeyCode::KeyW),
+            Action::ToolCircle => Binding::Key(KeyCode::KeyE),
+            Action::ToolEraser => Binding::Key(KeyCode::KeyX),
+            Action::ToggleGridSnap => Binding::Key(KeyCode::KeyG),
+            Action::Command => Binding::Mouse(MouseButton::Right),
+            Action::Deselect => Binding::Key(KeyCode::KeyD),
+            Action::Pause => Binding::Key(KeyCode::KeyP),
+            Action::Restart => Binding::Key(KeyCode::KeyR),
+            Action::SelectNeutral => Binding::Key(KeyCode::Digit1),
+            Action::SelectCalm => Binding::Key(KeyCode::Digit2),
+            Action::SelectHappy => Binding::Key(KeyCode::Digit3),
+            Action::SelectRage => Binding::Key(KeyCode::Digit4),
+            Action::SelectSad => Binding::Key(KeyCode::Digit5),
+            Action::SelectFear => Binding::Key(KeyCode::Digit6),
+            Action::FocusNext => Binding::Key(KeyCode::Tab),
+            Action::SelectFocused => Binding::Key(KeyCode::Enter),
+            // Space also skips the celebration, which only runs when nothing can be commanded.
+            Action::CommandToReticle => Binding::Key(KeyCode::Space),
+            Action::FollowSelected => Binding::Key(KeyCode::KeyF),
+            Action::LockMood => Binding::Key(KeyCode::KeyL),
+            Action::CursorAttract => Binding::Key(KeyCode::KeyC),
+            Action::SkipCelebration => Binding::Key(KeyCode::Space),
+            Action::SkipLevel => Binding::Key(KeyCode::KeyN),
+            Action::GamepadSelect => Binding::Gamepad(GamepadButton::South),
+            Action::GamepadCommand => Binding::Gamepad(GamepadButton::East),
+            Action::GamepadNext => Binding::Gamepad(GamepadButton::RightTrigger2),
+            Action::GamepadPrevious => Binding::Gamepad(GamepadButton::LeftTrigger2),
+        }
+    }
+}
+
+/// A key, mouse button or gamepad button an [`Action`] is bound to.
+///
+/// Saved as the name of the key (e.g. `"KeyQ"`), or the button prefixed with `Mouse` or
+/// `Gamepad` (e.g. `"MouseRight"` or `"GamepadSouth"`).
+#[derive(Reflect, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key:?}"),
+            Binding::Mouse(button) => write!(f, "Mouse{button:?}"),
+            Binding::Gamepad(button) => write!(f, "Gamepad{button:?}"),
+        }
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        // Only unit variants can be bound, so they can be built from their name alone.
+        fn from_variant_name<T: FromReflect>(name: &str) -> Option<T> {
+            T::from_reflect(&DynamicEnum::new(name, DynamicVariant::Unit))
+        }
+        let binding = if let Some(button) = name.strip_prefix("Mouse") {
+            from_variant_name(button).map(Binding::Mouse)
+        } else if let Some(button) = name.strip_prefix("Gamepad") {
+            from_variant_name(button).map(Binding::Gamepad)
+        } else {
+            from_variant_name(&name).map(Binding::Key)
+        };
+        binding.ok_or_else(|| format!("unknown key or button: {name}"))
+    }
+}
+
+/// Which key or button each [`Action`] is bound to.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub bindings: HashMap<Action, Binding>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: Action::all()
+                .into_iter()
+                .map(|action| (action, action.default_binding()))
+                .collect(),
+        }
+    }
+}
+
+/// Where bindings are saved: a file in the working directory natively, or a local
+/// storage key on web.
+const KEY_BINDINGS_SAVE_KEY: &str = "keybindings.json";
+
+impl KeyBindings {
+    /// Reads the saved bindings, falling back to the defaults if there are none or they can't
+    /// be read. Actions missing from the file keep their default binding.
+    pub fn load() -> Self {
+        let Some(json) = save::read(KEY_BINDINGS_SAVE_KEY) else {
+            return Self::default();
+        };
+        match serde_json::from_str::<KeyBindings>(&json) {
+            Ok(saved) => {
+                let mut bindings = Self::default();
+                bindings.bindings.extend(saved.bindings);
+                bindings
+            }
+            Err(error) => {
+                warn!("Ignoring unreadable key bindings: {}", error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        match serde_json::to_string_pretty(self) {
+            Ok(json) => save::write(KEY_BINDINGS_SAVE_KEY, &json),
+            Err(error) => error!("Failed to serialize key bindings: {}", error),
+        }
+    }
+
+    pub fn get(&self, action: Action) -> Binding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    pub fn bind(&mut self, action: Action, binding: Binding) {
+        self.bindings.insert(action, binding);
+    }
+}
+
+fn save_key_bindings(bindings: Res<KeyBindings>) {
+    bindings.save();
+}
+
+/// Checks whether [`Action`]s are pressed, whichever key or button they're bound to. Gamepad
+/// buttons count on any connected gamepad.
+#[derive(SystemParam)]
+pub struct ActionInput<'w, 's> {
+    bindings: Res<'w, KeyBindings>,
+    captured: Res<'w, KeyboardCaptured>,
+    keys: Res<'w, ButtonInput<KeyCode>>,
+    mouse_buttons: Res<'w, ButtonInput<MouseButton>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl ActionInput<'_, '_> {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        match self.bindings.get(action) {
+            Binding::Key(key) => !self.captured.0 && self.keys.just_pressed(key),
+            Binding::Mouse(button) => self.mouse_buttons.just_pressed(button),
+            Binding::Gamepad(button) => self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(button)),
+        }
+    }
+}
+
+/// A run condition that's true on the frame `action` is pressed.
+pub fn action_just_pressed(action: Action) -> impl FnMut(ActionInput) -> bool + Clone {
+    move |input: ActionInput| input.just_pressed(action)
+}
+
+/// Whether a text field, such as the dev console, is taking keyboard input, so typing
+/// doesn't also play the game.
+#[derive(Resource, Debug, Default, PartialEq)]
+pub struct KeyboardCaptured(pub bool);
+
+/// A run condition for systems that read the keyboard directly. False while a text field is
+/// taking keyboard input.
+pub fn keyboard_available(captured: Res<KeyboardCaptured>) -> bool {
+    !captured.0
+}
+
+/// The action waiting for a new binding, while the controls menu is listening for one.
+#[derive(Resource, Debug, Default)]
+pub struct Rebinding(pub Option<Action>);
+
+fn rebinding_active(rebinding: Res<Rebinding>) -> bool {
+    rebinding.0.is_some()
+}
+
+/// Binds the waiting action to the next key or button pressed. Escape cancels.
+fn capture_rebinding(
+    mut rebinding: ResMut<Rebinding>,
+    mut bindings: ResMut<KeyBindings>,
+    mut keys: ResMut<ButtonInput<KeyCode>>,
+    mut mouse_buttons: ResMut<ButtonInput<MouseButton>>,
+    mut gamepads: Query<&mut Gamepad>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let key = keys.get_just_pressed().next().copied();
+    // A left click is how the rebind button itself is pressed, so it can't be bound. Unnamed
+    // buttons can't be saved.
+    let button = mouse_buttons
+        .get_just_pressed()
+        .find(|button| !matches!(button, MouseButton::Left | MouseButton::Other(_)))
+        .copied();
+    let gamepad_button = gamepads.iter_mut().find_map(|gamepad| {
+        let button = gamepad
+            .get_just_pressed()
+            .find(|button| !matches!(button, GamepadButton::Other(_)))
+            .copied()?;
+        Some((gamepad, button))
+    });
+
+    // Swallow the press, so it doesn't also trigger whatever it was bound to.
+    let binding = match (key, button, gamepad_button) {
+        (Some(key), _, _) => {
+            keys.clear_just_pressed(key);
+            (key != KeyCode::Escape).then_some(Binding::Key(key))
+        }
+        (None, Some(button), _) => {
+            mouse_buttons.clear_just_pressed(button);
+            Some(Binding::Mouse(button))
+        }
+        (None, None, Some((mut gamepad, button))) => {
+            gamepad.digital_mut().clear_just_pressed(button);
+            Some(Binding::Gamepad(button))
+        }
+        (None, None, None) => return,
+    };
+    if let Some(binding) = binding {
+        info!("Bound {:?} to {}", action, binding);
+        bindings.bind(action, binding);
+    }
+    rebinding.0 = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    fn just_pressed(world: &mut World, action: Action) -> bool {
+        world
+            .run_system_once(move |input: ActionInput| input.just_pressed(action))
+            .unwrap()
+    }
+
+    #[test]
+    fn rebinding_changes_the_key() {
+        let mut world = World::new();
+        world.insert_resource(KeyBindings::default());
+        world.init_resource::<KeyboardCaptured>();
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world
+            .resource_mut::<KeyBindings>()
+            .bind(Action::Restart, Binding::Key(KeyCode::KeyT));
+
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyR);
+        assert!(!just_pressed(&mut world, Action::Restart));
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyT);
+        assert!(just_pressed(&mut world, Action::Restart));
+    }
+
+    #[test]
+    fn captured_keyboard_is_ignored() {
+        let mut world = World::new();
+        world.insert_resource(KeyBindings::default());
+        world.insert_resource(KeyboardCaptured(true));
+        world.init_resource::<ButtonInput<KeyCode>>();
+        world.init_resource::<ButtonInput<MouseButton>>();
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyR);
+        world
+            .resource_mut::<ButtonInput<MouseButton>>()
+            .press(MouseButton::Right);
+
+        assert!(!just_pressed(&mut world, Action::Restart));
+        // Mouse bindings still work
+        assert!(just_pressed(&mut world, Action::Command));
+    }
+
+    #[test]
+    fn bindings_round_trip_through_their_names() {
+        for binding in [
+            Binding::Key(KeyCode::KeyQ),
+            Binding::Mouse(MouseButton::Right),
+            Binding::Gamepad(GamepadButton::South),
+        ] {
+            assert_eq!(Binding::try_from(binding.to_string()), Ok(binding));
+        }
+    }
+}
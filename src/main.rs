@@ -8,21 +8,36 @@ mod audio;
 mod demo;
 #[cfg(feature = "dev")]
 mod dev_tools;
+mod keybindings;
 mod menus;
+mod save;
 mod screens;
 mod theme;
 
 use avian2d::prelude::*;
-use bevy::{asset::AssetMetaCheck, prelude::*};
+use bevy::{
+    asset::AssetMetaCheck,
+    picking::mesh_picking::{MeshPickingCamera, MeshPickingPlugin, MeshPickingSettings},
+    prelude::*,
+};
 use bevy_common_assets::json::JsonAssetPlugin;
 use bevy_egui::EguiPlugin;
 // Picking is now included in DefaultPlugins, no separate import needed
 #[cfg(feature = "dev")]
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 
-// Collision layers for separating Moodels and obstacles
+// Collision layers for separating Moodels, obstacles and triggers. Which layers interact:
+//
+//             Moodel  Obstacle  Trigger
+//   Moodel      x        x         x
+//   Obstacle    x
+//   Trigger     x
+//
+// Triggers are goal zones, hazard zones and mood objects: things Moodels touch, but that the
+// AI doesn't steer around, since its obstacle queries only look at the obstacle layer.
 pub const COLLISION_LAYER_MOODEL: LayerMask = LayerMask(0b0001);
 pub const COLLISION_LAYER_OBSTACLE: LayerMask = LayerMask(0b0010);
+pub const COLLISION_LAYER_TRIGGER: LayerMask = LayerMask(0b0100);
 
 fn main() -> AppExit {
     App::new().add_plugins(AppPlugin).run()
@@ -55,9 +70,24 @@ impl Plugin for AppPlugin {
 
         // Add Avian2D physics plugin
         app.add_plugins(PhysicsPlugins::default().with_length_unit(100.0));
-        
+
+        // Mesh picking is opt-in per entity, so only obstacles can be clicked on
+        app.add_plugins(MeshPickingPlugin);
+        app.insert_resource(MeshPickingSettings {
+            require_markers: true,
+            ..default()
+        });
+
         // Add JSON asset plugin for Level assets
-        app.add_plugins(JsonAssetPlugin::<crate::demo::level::Level>::new(&["level.json"]));
+        app.add_plugins(JsonAssetPlugin::<crate::demo::level::Level>::new(&[
+            "level.json",
+        ]));
+        app.add_plugins(JsonAssetPlugin::<crate::demo::mood_rules::MoodRules>::new(
+            &["rules.json"],
+        ));
+        app.add_plugins(
+            JsonAssetPlugin::<crate::demo::campaign::LevelManifest>::new(&["manifest.json"]),
+        );
 
         // Add other plugins.
         app.add_plugins((
@@ -72,6 +102,7 @@ impl Plugin for AppPlugin {
             },
             #[cfg(feature = "dev")]
             WorldInspectorPlugin::new(),
+            keybindings::plugin,
             menus::plugin,
             screens::plugin,
             theme::plugin,
@@ -91,6 +122,9 @@ impl Plugin for AppPlugin {
         // Set up the `Pause` state.
         app.init_state::<Pause>();
         app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+        // Physics runs outside of `Update`, so it's paused through its own clock.
+        app.add_systems(OnEnter(Pause(true)), pause_physics);
+        app.add_systems(OnExit(Pause(true)), unpause_physics);
 
         // Spawn the main camera and setup assets.
         app.add_systems(Startup, (spawn_camera, setup_assets));
@@ -115,12 +149,26 @@ enum AppSystems {
 #[states(scoped_entities)]
 struct Pause(pub bool);
 
+fn pause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+fn unpause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.unpause();
+}
+
 /// A system set for systems that shouldn't run while the game is paused.
 #[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
 struct PausableSystems;
 
 fn spawn_camera(mut commands: Commands) {
-    commands.spawn((Name::new("Camera"), Camera2d));
+    commands.spawn((
+        Name::new("Camera"),
+        Camera2d,
+        MeshPickingCamera,
+        // Sound effects are panned relative to the camera
+        SpatialListener::new(audio::SPATIAL_EAR_GAP),
+    ));
 }
 
 fn setup_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
@@ -128,6 +176,6 @@ fn setup_assets(mut commands: Commands, asset_server: Res<AssetServer>) {
     // You can download fonts like "Kalam-Bold.ttf" from Google Fonts
     // or "ComicNeue-Bold.ttf" for a rounded, friendly look
     let font = asset_server.load("fonts/ComicNeue-Bold.ttf");
-    // Fallback to default if font not found
+    // Falls back to the default font if this one fails to load (see `fallback_missing_font`)
     commands.insert_resource(crate::demo::level::FontHandle(font));
 }
@@ -0,0 +1,220 @@
+//! The AI tuning menu, reached from the pause menu.
+//!
+//! Exposes the main [`AiConfig`] knobs so behavior can be tuned without the dev inspector.
+//! Changes apply immediately and are saved when the menu is closed.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+
+use crate::{demo::ai::AiConfig, menus::Menu, theme::prelude::*};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::AiTuning), spawn_ai_tuning_menu);
+    app.add_systems(OnExit(Menu::AiTuning), save_ai_config);
+    app.add_systems(
+        Update,
+        (
+            update_knob_labels,
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Menu::AiTuning)),
+    );
+
+    app.register_type::<KnobLabel>();
+}
+
+/// A tunable [`AiConfig`] field, with the range its buttons clamp it to.
+struct Knob {
+    name: &'static str,
+    field: fn(&mut AiConfig) -> &mut f32,
+    step: f32,
+    min: f32,
+    max: f32,
+}
+
+const KNOBS: [Knob; 10] = [
+    Knob {
+        name: "Cohesion",
+        field: |config| &mut config.cohesion_strength,
+        step: 0.01,
+        min: 0.0,
+        max: 0.5,
+    },
+    Knob {
+        name: "Separation",
+        field: |config| &mut config.separation_strength,
+        step: 0.005,
+        min: 0.0,
+        max: 0.2,
+    },
+    Knob {
+        name: "Boundary margin",
+        field: |config| &mut config.avoidance_margin,
+        step: 10.0,
+        min: 0.0,
+        max: 300.0,
+    },
+    Knob {
+        name: "Boundary strength",
+        field: |config| &mut config.avoidance_strength,
+        step: 0.25,
+        min: 0.0,
+        max: 10.0,
+    },
+    Knob {
+        name: "Obstacle margin",
+        field: |config| &mut config.obstacle_avoidance_margin,
+        step: 10.0,
+        min: 0.0,
+        max: 200.0,
+    },
+    Knob {
+        name: "Obstacle strength",
+        field: |config| &mut config.obstacle_avoidance_strength,
+        step: 0.25,
+        min: 0.0,
+        max: 10.0,
+    },
+    Knob {
+        name: "Rage aim time",
+        field: |config| &mut config.rage_charge_aim_duration,
+        step: 0.05,
+        min: 0.1,
+        max: 3.0,
+    },
+    Knob {
+        name: "Rage charge time",
+        field: |config| &mut config.rage_charge_duration,
+        step: 0.25,
+        min: 0.25,
+        max: 5.0,
+    },
+    Knob {
+        name: "Rage knockback",
+        field: |config| &mut config.rage_charge_knockback,
+        step: 50.0,
+        min: 0.0,
+        max: 2000.0,
+    },
+    Knob {
+        name: "Rage burnout time",
+        field: |config| &mut config.rage_burnout_cooldown,
+        step: 1.0,
+        min: 0.0,
+        max: 60.0,
+    },
+];
+
+impl Knob {
+    /// Moves the field by `steps` steps, clamped to the knob's range.
+    fn nudge(&self, config: &mut AiConfig, steps: f32) {
+        let value = (self.field)(config);
+        *value = (*value + self.step * steps).clamp(self.min, self.max);
+    }
+}
+
+/// The label showing the value of the knob at this index of [`KNOBS`].
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct KnobLabel(usize);
+
+fn spawn_ai_tuning_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            widget::ui_root("AI Tuning Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::AiTuning),
+        ))
+        .with_children(|root| {
+            root.spawn(widget::header("AI Tuning"));
+            root.spawn(knob_grid()).with_children(|grid| {
+                for (index, knob) in KNOBS.iter().enumerate() {
+                    grid.spawn((
+                        widget::label(knob.name),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    ));
+                    grid.spawn(knob_widget(index));
+                }
+            });
+            root.spawn(widget::button("Reset to defaults", reset_ai_config));
+            root.spawn(widget::button("Back", go_back_on_click));
+        });
+}
+
+fn knob_grid() -> impl Bundle {
+    (
+        Name::new("Knob Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(6.0),
+            column_gap: Px(30.0),
+            grid_template_columns: RepeatedGridTrack::px(2, 300.0),
+            ..default()
+        },
+    )
+}
+
+fn knob_widget(index: usize) -> impl Bundle {
+    (
+        Name::new("Knob Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small(
+                "-",
+                move |_: Trigger<Pointer<Click>>, mut config: ResMut<AiConfig>| {
+                    KNOBS[index].nudge(&mut config, -1.0);
+                }
+            ),
+            (
+                Name::new("Current Value"),
+                Node {
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                children![(widget::label(""), KnobLabel(index))],
+            ),
+            widget::button_small(
+                "+",
+                move |_: Trigger<Pointer<Click>>, mut config: ResMut<AiConfig>| {
+                    KNOBS[index].nudge(&mut config, 1.0);
+                }
+            ),
+        ],
+    )
+}
+
+fn update_knob_labels(mut config: ResMut<AiConfig>, mut labels: Query<(&mut Text, &KnobLabel)>) {
+    // Reading through the field accessors needs mutable access, which shouldn't count as a change.
+    let config = config.bypass_change_detection();
+    for (mut text, label) in &mut labels {
+        let knob = &KNOBS[label.0];
+        let decimals = match knob.step {
+            step if step >= 1.0 => 0,
+            step if step >= 0.01 => 2,
+            _ => 3,
+        };
+        text.0 = format!("{:.*}", decimals, (knob.field)(config));
+    }
+}
+
+fn reset_ai_config(_: Trigger<Pointer<Click>>, mut config: ResMut<AiConfig>) {
+    *config = AiConfig::default();
+}
+
+fn save_ai_config(config: Res<AiConfig>) {
+    config.save();
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Pause);
+}
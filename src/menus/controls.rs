@@ -0,0 +1,143 @@
+//! The controls menu, reached from the settings menu.
+//!
+//! Click an action's binding, then press the key, mouse button or gamepad button to bind it
+//! to. Escape cancels. Bindings are saved as soon as they change.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
+
+use crate::{
+    keybindings::{Action, KeyBindings, Rebinding},
+    menus::Menu,
+    theme::prelude::*,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Controls), spawn_controls_menu);
+    app.add_systems(OnExit(Menu::Controls), cancel_rebinding);
+    app.add_systems(
+        Update,
+        (
+            update_binding_labels,
+            // While rebinding, Escape is swallowed to cancel the rebind instead.
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Menu::Controls)),
+    );
+
+    app.register_type::<BindingLabel>();
+}
+
+/// The label showing what this action is bound to.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct BindingLabel(Action);
+
+fn spawn_controls_menu(mut commands: Commands) {
+    commands
+        .spawn((
+            widget::ui_root("Controls Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Controls),
+        ))
+        .with_children(|root| {
+            root.spawn(widget::header("Controls"));
+            root.spawn(binding_grid()).with_children(|grid| {
+                for action in Action::all() {
+                    grid.spawn((
+                        widget::label(action.name()),
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                    ));
+                    grid.spawn(binding_button(action)).observe(
+                        move |_: Trigger<Pointer<Click>>, mut rebinding: ResMut<Rebinding>| {
+                            rebinding.0 = Some(action);
+                        },
+                    );
+                }
+            });
+            root.spawn(menu_buttons()).with_children(|buttons| {
+                buttons.spawn(widget::button("Reset to defaults", reset_key_bindings));
+                buttons.spawn(widget::button("Back", go_back_on_click));
+            });
+        });
+}
+
+/// Two columns of actions next to their bindings, so every action fits on screen.
+fn binding_grid() -> impl Bundle {
+    (
+        Name::new("Binding Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(4.0),
+            column_gap: Px(20.0),
+            grid_template_columns: RepeatedGridTrack::px(4, 220.0),
+            ..default()
+        },
+    )
+}
+
+fn menu_buttons() -> impl Bundle {
+    (
+        Name::new("Menu Buttons"),
+        Node {
+            column_gap: Px(20.0),
+            ..default()
+        },
+    )
+}
+
+fn binding_button(action: Action) -> impl Bundle {
+    (
+        Name::new("Binding Button"),
+        Button,
+        Node {
+            justify_self: JustifySelf::Start,
+            padding: UiRect::axes(Px(12.0), Px(2.0)),
+            ..default()
+        },
+        BackgroundColor(ui_palette::BUTTON_BACKGROUND),
+        InteractionPalette {
+            none: ui_palette::BUTTON_BACKGROUND,
+            hovered: ui_palette::BUTTON_HOVERED_BACKGROUND,
+            pressed: ui_palette::BUTTON_PRESSED_BACKGROUND,
+        },
+        children![(
+            widget::label(""),
+            BindingLabel(action),
+            // Don't bubble picking events from the text up to the button.
+            Pickable::IGNORE,
+        )],
+    )
+}
+
+fn update_binding_labels(
+    bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    mut labels: Query<(&mut Text, &BindingLabel)>,
+) {
+    for (mut text, label) in &mut labels {
+        text.0 = if rebinding.0 == Some(label.0) {
+            "Press a key...".to_string()
+        } else {
+            bindings.get(label.0).to_string()
+        };
+    }
+}
+
+fn reset_key_bindings(_: Trigger<Pointer<Click>>, mut bindings: ResMut<KeyBindings>) {
+    *bindings = KeyBindings::default();
+}
+
+fn cancel_rebinding(mut rebinding: ResMut<Rebinding>) {
+    rebinding.0 = None;
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Settings);
+}
@@ -0,0 +1,190 @@
+//! The level select menu, listing every level of the campaign. Levels stay locked
+//! until the one before them has been beaten.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    asset_tracking::ResourceHandles,
+    demo::{
+        campaign::{Campaign, CampaignProgress},
+        level::Level,
+    },
+    menus::Menu,
+    screens::Screen,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(Menu::LevelSelect),
+        (load_campaign_levels, spawn_level_select_menu),
+    );
+    app.add_systems(
+        Update,
+        (
+            populate_level_list.run_if(resource_exists::<CampaignLevelHandles>),
+            go_back.run_if(input_just_pressed(KeyCode::Escape)),
+        )
+            .run_if(in_state(Menu::LevelSelect)),
+    );
+    app.add_systems(OnExit(Menu::LevelSelect), unload_campaign_levels);
+}
+
+/// The campaign's levels, loaded so their names and difficulties can be listed.
+#[derive(Resource)]
+struct CampaignLevelHandles(Vec<Handle<Level>>);
+
+/// The container that level buttons are added to once the levels have loaded.
+#[derive(Component)]
+struct LevelList;
+
+fn load_campaign_levels(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    asset_server: Res<AssetServer>,
+) {
+    let handles = campaign
+        .levels
+        .iter()
+        .map(|level| asset_server.load(level.path.clone()))
+        .collect();
+    commands.insert_resource(CampaignLevelHandles(handles));
+}
+
+fn unload_campaign_levels(mut commands: Commands) {
+    commands.remove_resource::<CampaignLevelHandles>();
+}
+
+fn spawn_level_select_menu(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Level Select Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::LevelSelect),
+        children![
+            widget::header("Levels"),
+            (
+                Name::new("Level List"),
+                LevelList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                },
+                children![widget::label("Loading...")],
+            ),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+/// Replaces the loading label with a button per level once every level has loaded or failed.
+fn populate_level_list(
+    mut commands: Commands,
+    handles: Res<CampaignLevelHandles>,
+    asset_server: Res<AssetServer>,
+    levels: Res<Assets<Level>>,
+    campaign: Res<Campaign>,
+    progress: Res<CampaignProgress>,
+    list: Single<Entity, With<LevelList>>,
+    mut populated: Local<bool>,
+) {
+    if handles.is_added() {
+        *populated = false;
+    }
+    if *populated
+        || handles
+            .0
+            .iter()
+            .any(|handle| asset_server.load_state(handle).is_loading())
+    {
+        return;
+    }
+    *populated = true;
+
+    commands
+        .entity(*list)
+        .despawn_related::<Children>()
+        .with_children(|list| {
+            for (index, (handle, entry)) in handles.0.iter().zip(&campaign.levels).enumerate() {
+                let text = match levels.get(handle) {
+                    Some(level) => format!(
+                        "{} - difficulty {}/{}",
+                        entry.name, level.meta.difficulty, MAX_DIFFICULTY
+                    ),
+                    None => format!("{} failed to load", entry.name),
+                };
+                let unlocked = progress.is_unlocked(&campaign, index);
+                let completed = progress.completed.contains(&entry.path);
+
+                list.spawn(level_row()).with_children(|row| {
+                    if let Some(thumbnail) = &entry.thumbnail {
+                        row.spawn(thumbnail_image(asset_server.load(thumbnail.clone())));
+                    }
+                    row.spawn(widget::label(text));
+                    if completed {
+                        row.spawn(widget::label("Completed"));
+                    }
+                    if unlocked {
+                        row.spawn(play_button(index));
+                    } else {
+                        row.spawn(widget::label("Locked"));
+                    }
+                });
+            }
+        });
+}
+
+/// Levels are rated from 0 to this in their metadata.
+const MAX_DIFFICULTY: u8 = 5;
+
+/// Side length of a level's thumbnail, in pixels.
+const THUMBNAIL_SIZE: f32 = 64.0;
+
+fn level_row() -> impl Bundle {
+    (
+        Name::new("Level Row"),
+        Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(20.0),
+            ..default()
+        },
+    )
+}
+
+fn thumbnail_image(image: Handle<Image>) -> impl Bundle {
+    (
+        Name::new("Level Thumbnail"),
+        ImageNode::new(image),
+        Node {
+            width: Val::Px(THUMBNAIL_SIZE),
+            height: Val::Px(THUMBNAIL_SIZE),
+            ..default()
+        },
+    )
+}
+
+fn play_button(index: usize) -> impl Bundle {
+    widget::button(
+        "Play",
+        move |_: Trigger<Pointer<Click>>,
+              mut campaign: ResMut<Campaign>,
+              resource_handles: Res<ResourceHandles>,
+              mut next_screen: ResMut<NextState<Screen>>| {
+            campaign.current = index;
+            if resource_handles.is_all_done() {
+                next_screen.set(Screen::Gameplay);
+            } else {
+                next_screen.set(Screen::Loading);
+            }
+        },
+    )
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
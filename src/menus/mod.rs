@@ -1,6 +1,9 @@
 //! The game's menus and transitions between them.
 
+mod ai_tuning;
+mod controls;
 mod credits;
+mod level_select;
 mod main;
 mod pause;
 mod settings;
@@ -11,7 +14,10 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Menu>();
 
     app.add_plugins((
+        ai_tuning::plugin,
+        controls::plugin,
         credits::plugin,
+        level_select::plugin,
         main::plugin,
         settings::plugin,
         pause::plugin,
@@ -25,6 +31,9 @@ pub enum Menu {
     None,
     Main,
     Credits,
+    LevelSelect,
     Settings,
     Pause,
+    AiTuning,
+    Controls,
 }
@@ -2,7 +2,7 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{demo::level::ResetLevel, menus::Menu, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
@@ -20,7 +20,9 @@ fn spawn_pause_menu(mut commands: Commands) {
         children![
             widget::header("Game paused"),
             widget::button("Continue", close_menu),
+            widget::button("Restart", restart_level),
             widget::button("Settings", open_settings_menu),
+            widget::button("AI Tuning", open_ai_tuning_menu),
             widget::button("Quit to title", quit_to_title),
         ],
     ));
@@ -30,10 +32,23 @@ fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextStat
     next_menu.set(Menu::Settings);
 }
 
+fn open_ai_tuning_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::AiTuning);
+}
+
 fn close_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::None);
 }
 
+fn restart_level(
+    _: Trigger<Pointer<Click>>,
+    mut reset_events: EventWriter<ResetLevel>,
+    mut next_menu: ResMut<NextState<Menu>>,
+) {
+    reset_events.write(ResetLevel);
+    next_menu.set(Menu::None);
+}
+
 fn quit_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
     next_screen.set(Screen::Title);
 }
@@ -4,7 +4,13 @@
 
 use bevy::{audio::Volume, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 
-use crate::{menus::Menu, screens::Screen, theme::prelude::*};
+use crate::{
+    audio::{MusicVolume, SfxVolume},
+    demo::{mood::ColorScheme, mood_badges::ShowMoodBadges, mood_labels::ShowMoodLabels},
+    menus::Menu,
+    screens::Screen,
+    theme::prelude::*,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Settings), spawn_settings_menu);
@@ -13,10 +19,17 @@ pub(super) fn plugin(app: &mut App) {
         go_back.run_if(in_state(Menu::Settings).and(input_just_pressed(KeyCode::Escape))),
     );
 
-    app.register_type::<GlobalVolumeLabel>();
+    app.register_type::<VolumeLabel>();
     app.add_systems(
         Update,
-        update_global_volume_label.run_if(in_state(Menu::Settings)),
+        update_volume_labels.run_if(in_state(Menu::Settings)),
+    );
+
+    app.register_type::<ToggleLabel>();
+    app.register_type::<ColorSchemeLabel>();
+    app.add_systems(
+        Update,
+        (update_toggle_labels, update_color_scheme_label).run_if(in_state(Menu::Settings)),
     );
 }
 
@@ -28,6 +41,7 @@ fn spawn_settings_menu(mut commands: Commands) {
         children![
             widget::header("Settings"),
             settings_grid(),
+            widget::button("Controls", open_controls_menu),
             widget::button("Back", go_back_on_click),
         ],
     ));
@@ -44,64 +58,234 @@ fn settings_grid() -> impl Bundle {
             ..default()
         },
         children![
+            volume_label("Master Volume"),
+            volume_widget::<GlobalVolume>(VolumeLabel::Master),
+            volume_label("Music Volume"),
+            volume_widget::<MusicVolume>(VolumeLabel::Music),
+            volume_label("Sound Effects Volume"),
+            volume_widget::<SfxVolume>(VolumeLabel::Sfx),
+            volume_label("Mood Labels"),
+            toggle_widget::<ShowMoodLabels>(ToggleLabel::MoodLabels),
+            volume_label("Mood Colors"),
+            color_scheme_widget(),
+            volume_label("Mood Badges"),
+            toggle_widget::<ShowMoodBadges>(ToggleLabel::MoodBadges),
+        ],
+    )
+}
+
+fn volume_label(text: &'static str) -> impl Bundle {
+    (
+        widget::label(text),
+        Node {
+            justify_self: JustifySelf::End,
+            ..default()
+        },
+    )
+}
+
+fn volume_widget<V: VolumeSetting>(label: VolumeLabel) -> impl Bundle {
+    (
+        Name::new("Volume Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![
+            widget::button_small("-", lower_volume::<V>),
             (
-                widget::label("Master Volume"),
+                Name::new("Current Volume"),
                 Node {
-                    justify_self: JustifySelf::End,
+                    padding: UiRect::horizontal(Px(10.0)),
+                    justify_content: JustifyContent::Center,
                     ..default()
-                }
+                },
+                children![(widget::label(""), label)],
             ),
-            global_volume_widget(),
+            widget::button_small("+", raise_volume::<V>),
         ],
     )
 }
 
-fn global_volume_widget() -> impl Bundle {
+/// A single button that turns a setting on or off, showing its current state.
+fn toggle_widget<T: ToggleSetting>(label: ToggleLabel) -> impl Bundle {
+    (
+        Name::new("Toggle Widget"),
+        Node {
+            justify_self: JustifySelf::Start,
+            ..default()
+        },
+        children![(widget::button_medium("", toggle_setting::<T>), label)],
+    )
+}
+
+/// An on/off resource that can be toggled from the settings menu.
+trait ToggleSetting: Resource {
+    fn enabled_mut(&mut self) -> &mut bool;
+}
+
+impl ToggleSetting for ShowMoodLabels {
+    fn enabled_mut(&mut self) -> &mut bool {
+        &mut self.0
+    }
+}
+
+impl ToggleSetting for ShowMoodBadges {
+    fn enabled_mut(&mut self) -> &mut bool {
+        &mut self.0
+    }
+}
+
+fn toggle_setting<T: ToggleSetting>(_: Trigger<Pointer<Click>>, mut setting: ResMut<T>) {
+    let enabled = setting.enabled_mut();
+    *enabled = !*enabled;
+}
+
+/// Which setting a toggle button in the settings menu shows.
+#[derive(Component, Reflect, Clone, Copy)]
+#[reflect(Component)]
+enum ToggleLabel {
+    MoodLabels,
+    MoodBadges,
+}
+
+/// Writes "On" or "Off" on each toggle button.
+fn update_toggle_labels(
+    show_mood_labels: Res<ShowMoodLabels>,
+    show_mood_badges: Res<ShowMoodBadges>,
+    toggles: Query<(Entity, &ToggleLabel)>,
+    children: Query<&Children>,
+    mut texts: Query<&mut Text>,
+) {
+    for (entity, label) in &toggles {
+        let enabled = match label {
+            ToggleLabel::MoodLabels => show_mood_labels.0,
+            ToggleLabel::MoodBadges => show_mood_badges.0,
+        };
+        // The text is nested inside the button.
+        for descendant in children.iter_descendants(entity) {
+            if let Ok(mut text) = texts.get_mut(descendant) {
+                text.0 = if enabled { "On" } else { "Off" }.to_string();
+            }
+        }
+    }
+}
+
+fn color_scheme_widget() -> impl Bundle {
     (
-        Name::new("Global Volume Widget"),
+        Name::new("Color Scheme Widget"),
         Node {
             justify_self: JustifySelf::Start,
             ..default()
         },
         children![
-            widget::button_small("-", lower_global_volume),
+            widget::button_small("<", previous_color_scheme),
             (
-                Name::new("Current Volume"),
+                Name::new("Current Color Scheme"),
                 Node {
                     padding: UiRect::horizontal(Px(10.0)),
                     justify_content: JustifyContent::Center,
                     ..default()
                 },
-                children![(widget::label(""), GlobalVolumeLabel)],
+                children![(widget::label(""), ColorSchemeLabel)],
             ),
-            widget::button_small("+", raise_global_volume),
+            widget::button_small(">", next_color_scheme),
         ],
     )
 }
 
+fn cycle_color_scheme(scheme: &mut ColorScheme, step: usize) {
+    let schemes = ColorScheme::all();
+    let index = schemes.iter().position(|s| *s == *scheme).unwrap_or(0);
+    *scheme = schemes[(index + step) % schemes.len()];
+}
+
+fn previous_color_scheme(_: Trigger<Pointer<Click>>, mut scheme: ResMut<ColorScheme>) {
+    cycle_color_scheme(&mut scheme, ColorScheme::all().len() - 1);
+}
+
+fn next_color_scheme(_: Trigger<Pointer<Click>>, mut scheme: ResMut<ColorScheme>) {
+    cycle_color_scheme(&mut scheme, 1);
+}
+
+/// The label showing the current color scheme.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+struct ColorSchemeLabel;
+
+fn update_color_scheme_label(
+    scheme: Res<ColorScheme>,
+    mut labels: Query<&mut Text, With<ColorSchemeLabel>>,
+) {
+    for mut text in &mut labels {
+        text.0 = scheme.name().to_string();
+    }
+}
+
 const MIN_VOLUME: f32 = 0.0;
 const MAX_VOLUME: f32 = 3.0;
 
-fn lower_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() - 0.1).max(MIN_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+/// A volume resource that can be adjusted from the settings menu.
+trait VolumeSetting: Resource {
+    fn volume_mut(&mut self) -> &mut Volume;
 }
 
-fn raise_global_volume(_: Trigger<Pointer<Click>>, mut global_volume: ResMut<GlobalVolume>) {
-    let linear = (global_volume.volume.to_linear() + 0.1).min(MAX_VOLUME);
-    global_volume.volume = Volume::Linear(linear);
+impl VolumeSetting for GlobalVolume {
+    fn volume_mut(&mut self) -> &mut Volume {
+        &mut self.volume
+    }
 }
 
-#[derive(Component, Reflect)]
+impl VolumeSetting for MusicVolume {
+    fn volume_mut(&mut self) -> &mut Volume {
+        &mut self.0
+    }
+}
+
+impl VolumeSetting for SfxVolume {
+    fn volume_mut(&mut self) -> &mut Volume {
+        &mut self.0
+    }
+}
+
+fn lower_volume<V: VolumeSetting>(_: Trigger<Pointer<Click>>, mut setting: ResMut<V>) {
+    let volume = setting.volume_mut();
+    *volume = Volume::Linear((volume.to_linear() - 0.1).max(MIN_VOLUME));
+}
+
+fn raise_volume<V: VolumeSetting>(_: Trigger<Pointer<Click>>, mut setting: ResMut<V>) {
+    let volume = setting.volume_mut();
+    *volume = Volume::Linear((volume.to_linear() + 0.1).min(MAX_VOLUME));
+}
+
+/// Which volume a label in the settings menu shows.
+#[derive(Component, Reflect, Clone, Copy)]
 #[reflect(Component)]
-struct GlobalVolumeLabel;
+enum VolumeLabel {
+    Master,
+    Music,
+    Sfx,
+}
 
-fn update_global_volume_label(
+fn update_volume_labels(
     global_volume: Res<GlobalVolume>,
-    mut label: Single<&mut Text, With<GlobalVolumeLabel>>,
+    music_volume: Res<MusicVolume>,
+    sfx_volume: Res<SfxVolume>,
+    mut labels: Query<(&mut Text, &VolumeLabel)>,
 ) {
-    let percent = 100.0 * global_volume.volume.to_linear();
-    label.0 = format!("{percent:3.0}%");
+    for (mut text, label) in &mut labels {
+        let volume = match label {
+            VolumeLabel::Master => global_volume.volume,
+            VolumeLabel::Music => music_volume.0,
+            VolumeLabel::Sfx => sfx_volume.0,
+        };
+        let percent = 100.0 * volume.to_linear();
+        text.0 = format!("{percent:3.0}%");
+    }
+}
+
+fn open_controls_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Controls);
 }
 
 fn go_back_on_click(
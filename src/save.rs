@@ -0,0 +1,38 @@
+//! Small JSON save files, written to the working directory natively and to local storage
+//! on web.
+
+use bevy::prelude::*;
+
+/// Reads the save stored under `key`, if there is one.
+#[cfg(not(target_family = "wasm"))]
+pub fn read(key: &str) -> Option<String> {
+    std::fs::read_to_string(key).ok()
+}
+
+/// Writes `json` under `key`, logging an error on failure.
+#[cfg(not(target_family = "wasm"))]
+pub fn write(key: &str, json: &str) {
+    if let Err(error) = std::fs::write(key, json) {
+        error!("Failed to write save file {}: {}", key, error);
+    }
+}
+
+#[cfg(target_family = "wasm")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Reads the save stored under `key`, if there is one.
+#[cfg(target_family = "wasm")]
+pub fn read(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+/// Writes `json` under `key`, logging an error on failure.
+#[cfg(target_family = "wasm")]
+pub fn write(key: &str, json: &str) {
+    let saved = local_storage().is_some_and(|storage| storage.set_item(key, json).is_ok());
+    if !saved {
+        error!("Failed to write {} to local storage", key);
+    }
+}
@@ -0,0 +1,31 @@
+//! The screen shown when a level is lost.
+
+use bevy::prelude::*;
+
+use crate::{screens::Screen, theme::widget};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Defeat), spawn_defeat_screen);
+}
+
+fn spawn_defeat_screen(mut commands: Commands) {
+    commands.spawn((
+        widget::ui_root("Defeat Screen"),
+        StateScoped(Screen::Defeat),
+        children![
+            widget::header("The Moodels got away from you..."),
+            widget::button("Retry", retry_level),
+            widget::button("Back to title", return_to_title),
+        ],
+    ));
+}
+
+/// The campaign, or [`ActiveLevelSource`](crate::demo::level::ActiveLevelSource) for levels
+/// from elsewhere, still points at the lost level, so re-entering gameplay retries it.
+fn retry_level(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Gameplay);
+}
+
+fn return_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}
@@ -2,7 +2,13 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*, ui::Val::*};
 
-use crate::{Pause, demo::level::spawn_level, menus::Menu, screens::Screen};
+use crate::{
+    Pause,
+    demo::level::spawn_level,
+    keybindings::{Action, action_just_pressed, keyboard_available},
+    menus::Menu,
+    screens::Screen,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Screen::Gameplay), spawn_level);
@@ -12,14 +18,15 @@ pub(super) fn plugin(app: &mut App) {
         Update,
         (
             (pause, spawn_pause_overlay, open_pause_menu).run_if(
-                in_state(Screen::Gameplay)
-                    .and(in_state(Menu::None))
-                    .and(input_just_pressed(KeyCode::KeyP).or(input_just_pressed(KeyCode::Escape))),
+                in_state(Screen::Gameplay).and(in_state(Menu::None)).and(
+                    action_just_pressed(Action::Pause)
+                        .or(input_just_pressed(KeyCode::Escape).and(keyboard_available)),
+                ),
             ),
             close_menu.run_if(
                 in_state(Screen::Gameplay)
                     .and(not(in_state(Menu::None)))
-                    .and(input_just_pressed(KeyCode::KeyP)),
+                    .and(action_just_pressed(Action::Pause)),
             ),
         ),
     );
@@ -1,9 +1,11 @@
 //! The game's main screen states and transitions between them.
 
+mod defeat;
 mod gameplay;
 mod loading;
 mod splash;
 mod title;
+mod victory;
 
 use bevy::prelude::*;
 
@@ -11,10 +13,12 @@ pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
 
     app.add_plugins((
+        defeat::plugin,
         gameplay::plugin,
         loading::plugin,
         splash::plugin,
         title::plugin,
+        victory::plugin,
     ));
 }
 
@@ -27,4 +31,8 @@ pub enum Screen {
     Title,
     Loading,
     Gameplay,
+    /// Shown after a level is won, and summarizes the campaign after the last one.
+    Victory,
+    /// Shown when a level is lost.
+    Defeat,
 }
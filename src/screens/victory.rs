@@ -0,0 +1,153 @@
+//! The screen shown after a level is won, with the campaign's results once the last
+//! level is beaten.
+
+use bevy::{ecs::spawn::SpawnIter, prelude::*, ui::Val::*};
+
+use crate::{
+    demo::{
+        campaign::{Campaign, LevelResult},
+        level::{ActiveLevel, ActiveLevelSource},
+        stats::GameStats,
+    },
+    screens::Screen,
+    theme::widget,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Screen::Victory), spawn_victory_screen);
+}
+
+fn spawn_victory_screen(
+    mut commands: Commands,
+    campaign: Res<Campaign>,
+    source: Res<ActiveLevelSource>,
+    active_level: Res<ActiveLevel>,
+    stats: Res<GameStats>,
+) {
+    let root = commands
+        .spawn((
+            widget::ui_root("Victory Screen"),
+            StateScoped(Screen::Victory),
+        ))
+        .id();
+
+    if !source.is_campaign() {
+        // Not part of the campaign, so there's no next level or campaign results to show.
+        let name = active_level.0.as_ref().map(|level| level.name.clone());
+        commands.entity(root).with_children(|parent| {
+            parent.spawn(widget::header("Level complete!"));
+            if let Some(name) = name {
+                parent.spawn(widget::label(name));
+            }
+            parent.spawn(stats_summary(&stats));
+            parent.spawn(widget::button("Retry", retry_level));
+            parent.spawn(widget::button("Menu", return_to_title));
+        });
+    } else if campaign.has_next() {
+        let level = campaign.results.last().cloned();
+        commands.entity(root).with_children(|parent| {
+            parent.spawn(widget::header("Level complete!"));
+            if let Some(result) = level {
+                parent.spawn(widget::label(result.name.clone()));
+                parent.spawn(results_grid(vec![result]));
+            }
+            parent.spawn(stats_summary(&stats));
+            parent.spawn(widget::button("Next Level", next_level));
+            parent.spawn(widget::button("Retry", retry_level));
+            parent.spawn(widget::button("Menu", return_to_title));
+        });
+    } else {
+        commands.entity(root).with_children(|parent| {
+            parent.spawn(widget::header("Every Moodel is where it belongs!"));
+            parent.spawn(widget::label("You completed all the levels."));
+            parent.spawn(results_grid(campaign.results.clone()));
+            parent.spawn(stats_summary(&stats));
+            parent.spawn(widget::button("Retry", retry_level));
+            parent.spawn(widget::button("Back to title", return_to_title));
+        });
+    }
+}
+
+/// Each level's completion time next to its par time.
+fn results_grid(results: Vec<LevelResult>) -> impl Bundle {
+    (
+        Name::new("Results Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(30.0),
+            grid_template_columns: RepeatedGridTrack::auto(3),
+            ..default()
+        },
+        Children::spawn(SpawnIter(results.into_iter().flat_map(|result| {
+            let par = match result.par_time_secs {
+                Some(par) if result.time_secs <= par => format!("par {par:.1}s - under par!"),
+                Some(par) => format!("par {par:.1}s"),
+                None => "no par".to_string(),
+            };
+            [
+                widget::label(result.name),
+                widget::label(format!("{:.1}s", result.time_secs)),
+                widget::label(par),
+            ]
+        }))),
+    )
+}
+
+/// A few lines summing up how the last level played out.
+fn stats_summary(stats: &GameStats) -> impl Bundle {
+    let mut lines = vec![
+        format!("Mood changes: {}", stats.total_mood_changes()),
+        format!("Collisions: {}", stats.collisions),
+    ];
+    if let Some(((from, to), count)) = stats.most_common_change() {
+        lines.push(format!("Most common change: {from:?} to {to:?} ({count}x)"));
+    }
+    let longest_mood = stats
+        .mood_time_secs
+        .iter()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b));
+    if let Some((mood, _)) = longest_mood {
+        lines.push(format!("Most common mood: {mood:?}"));
+    }
+    (
+        Name::new("Stats Summary"),
+        Node {
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Px(5.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(lines.into_iter().map(widget::label))),
+    )
+}
+
+/// Entering gameplay spawns the campaign's current level.
+fn next_level(
+    _: Trigger<Pointer<Click>>,
+    mut campaign: ResMut<Campaign>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if let Some(level) = campaign.advance() {
+        info!("Advancing the campaign to {}", level);
+    }
+    next_screen.set(Screen::Gameplay);
+}
+
+/// Plays the level again, from wherever it came from. A campaign level's result is dropped,
+/// so the retry replaces it.
+fn retry_level(
+    _: Trigger<Pointer<Click>>,
+    mut campaign: ResMut<Campaign>,
+    source: Res<ActiveLevelSource>,
+    mut next_screen: ResMut<NextState<Screen>>,
+) {
+    if source.is_campaign() {
+        campaign.results.pop();
+    }
+    next_screen.set(Screen::Gameplay);
+}
+
+fn return_to_title(_: Trigger<Pointer<Click>>, mut next_screen: ResMut<NextState<Screen>>) {
+    next_screen.set(Screen::Title);
+}